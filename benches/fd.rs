@@ -0,0 +1,89 @@
+#[macro_use]
+extern crate bencher;
+extern crate proto_vulcan;
+use bencher::{black_box, Bencher};
+use proto_vulcan::prelude::*;
+use proto_vulcan::relation::{distinctfd, infd, infdrange};
+use proto_vulcan::state::FiniteDomain;
+
+fn sudoku(b: &mut Bencher) {
+    const BOARD_SIZE: usize = 9;
+    const SQUARE_SIZE: usize = 3;
+
+    b.iter(|| {
+        #[rustfmt::skip]
+        let board = lterm!(
+            [_, _, _, 2, 6, _, 7, _, 1,
+             6, 8, _, _, 7, _, _, 9, _,
+             1, 9, _, _, _, 4, 5, _, _,
+             8, 2, _, 1, _, _, _, 4, _,
+             _, _, 4, 6, _, 2, 9, _, _,
+             _, 5, _, _, _, 3, _, 2, 8,
+             _, _, 9, 3, _, _, _, 7, 4,
+             _, 4, _, _, 5, _, _, 3, 6,
+             7, _, 3, _, 1, 8, _, _, _]
+        );
+
+        let mut rows = vec![lterm!([]); BOARD_SIZE];
+        let mut cols = vec![lterm!([]); BOARD_SIZE];
+        let mut squares = vec![lterm!([]); BOARD_SIZE];
+        for row_index in 0..BOARD_SIZE {
+            for col_index in 0..BOARD_SIZE {
+                let x = board[row_index * BOARD_SIZE + col_index].clone();
+                let square_index = (row_index / SQUARE_SIZE) * (BOARD_SIZE / SQUARE_SIZE)
+                    + (col_index / SQUARE_SIZE);
+                rows[row_index].extend(Some(x.clone()));
+                cols[col_index].extend(Some(x.clone()));
+                squares[square_index].extend(Some(x));
+            }
+        }
+
+        let query = proto_vulcan_query!(|q| {
+            q == board,
+            for x in &board {
+                infdrange(x, &(1..=BOARD_SIZE as isize))
+            },
+            for row in &rows {
+                distinctfd(row)
+            },
+            for col in &cols {
+                distinctfd(col)
+            },
+            for square in &squares {
+                distinctfd(square)
+            }
+        });
+        black_box(query.run().next().unwrap());
+    });
+}
+
+fn sparse_distinctfd(b: &mut Bencher) {
+    // Eight variables with sparse domains of 64 values each within a span of 1000 values.
+    let values = (0..64).map(|i| i * 15).collect::<Vec<isize>>();
+    b.iter(|| {
+        let query = proto_vulcan_query!(|a, b, c, d, e, f, g, h| {
+            infd([a, b, c, d, e, f, g, h], &values),
+            distinctfd([a, b, c, d, e, f, g, h]),
+            a == 0,
+            b == 15,
+            c == 30,
+            d == 45,
+        });
+        black_box(query.run().take(10).count());
+    });
+}
+
+fn sparse_domain_ops(b: &mut Bencher) {
+    let evens = FiniteDomain::from((0..2048).map(|i| i * 2).collect::<Vec<isize>>());
+    let thirds = FiniteDomain::from((0..1365).map(|i| i * 3).collect::<Vec<isize>>());
+    let interval = FiniteDomain::from(1000..=3000);
+    b.iter(|| {
+        black_box(evens.intersect(&thirds));
+        black_box(evens.diff(&thirds));
+        black_box(evens.intersect(&interval));
+        black_box(interval.diff(&evens));
+    });
+}
+
+benchmark_group!(benches, sudoku, sparse_distinctfd, sparse_domain_ops);
+benchmark_main!(benches);
@@ -77,6 +77,67 @@ impl ToTokens for Project {
     }
 }
 
+#[allow(dead_code)]
+#[derive(Clone)]
+struct Nom {
+    nom: Ident,
+    or1_token: Token![|],
+    variables: Punctuated<Ident, Token![,]>,
+    or2_token: Token![|],
+    brace_token: Brace,
+    body: Punctuated<Clause, Token![,]>,
+}
+
+impl Parse for Nom {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let nom: Ident = input.parse()?;
+        if nom.to_string().as_str() != "nom" {
+            return Err(Error::new(nom.span(), "Identifier \"nom\" expected"));
+        }
+
+        let or1_token: Token![|] = input.parse()?;
+        let mut variables = Punctuated::new();
+        loop {
+            if input.peek(Token![|]) {
+                break;
+            }
+            let var: Ident = input.parse()?;
+            variables.push_value(var);
+            if input.peek(Token![|]) {
+                break;
+            }
+            let punct: Token![,] = input.parse()?;
+            variables.push_punct(punct);
+        }
+        let or2_token: Token![|] = input.parse()?;
+
+        let content;
+        Ok(Nom {
+            nom,
+            or1_token,
+            variables,
+            or2_token,
+            brace_token: braced!(content in input),
+            body: content.parse_terminated(Clause::parse)?,
+        })
+    }
+}
+
+impl ToTokens for Nom {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let variables: Vec<&Ident> = self.variables.iter().collect();
+        let body: Vec<&Clause> = self.body.iter().collect();
+        let output = quote! {{
+            #( let #variables: ::proto_vulcan::lterm::LTerm<_, _> = ::proto_vulcan::lterm::LTerm::nom(stringify!(#variables)); )*
+            ::proto_vulcan::operator::fresh::Fresh::new(vec![],
+                ::proto_vulcan::GoalCast::cast_into(
+                    ::proto_vulcan::operator::conj::InferredConj::from_array(&[ #( ::proto_vulcan::GoalCast::cast_into( #body ) ),* ]))
+                )
+        }};
+        output.to_tokens(tokens);
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 struct FnGoal {
@@ -498,6 +559,8 @@ impl Parse for Argument {
             let _ = braced!(content in input);
             let expr = content.parse()?;
             Ok(Argument::Expr { expr, cast: true })
+        } else if TreeTerm::peek_tie(input) {
+            Ok(Argument::TreeTerm(input.parse()?))
         } else if (input.peek(syn::token::Colon2) && input.peek2(Ident))
             || (input.peek(Ident) && (input.peek2(syn::token::Colon2) || input.peek2(Paren))
                 || input.peek(Paren))
@@ -642,15 +705,40 @@ impl ToTokens for Loop {
 #[derive(Clone)]
 struct Operator {
     name: Ident,
+    // Optional term arguments: $operator ( arg0, arg1, ... ) { }
+    args: Punctuated<Argument, Token![,]>,
     brace_token: Brace,
     body: Punctuated<ClauseInOperator, Token![,]>,
 }
 
+impl Operator {
+    /// Checks if the input begins with an operator that has term arguments, i.e.
+    /// `$operator ( ... ) {`, which would otherwise look like a relation.
+    fn peek_with_args(input: ParseStream) -> bool {
+        let fork = input.fork();
+        fork.parse::<Ident>().is_ok()
+            && fork
+                .parse::<proc_macro2::Group>()
+                .map(|group| group.delimiter() == proc_macro2::Delimiter::Parenthesis)
+                .unwrap_or(false)
+            && fork.peek(Brace)
+    }
+}
+
 impl Parse for Operator {
     fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        let args = if input.peek(Paren) {
+            let content;
+            let _ = parenthesized!(content in input);
+            content.parse_terminated(Argument::parse)?
+        } else {
+            Punctuated::new()
+        };
         let content;
         Ok(Operator {
-            name: input.parse()?,
+            name,
+            args,
             brace_token: braced!(content in input),
             body: content.parse_terminated(ClauseInOperator::parse)?,
         })
@@ -660,9 +748,11 @@ impl Parse for Operator {
 impl ToTokens for Operator {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let name = &self.name;
+        let args: Vec<&Argument> = self.args.iter().collect();
         let body: Vec<&ClauseInOperator> = self.body.iter().collect();
-        let output =
-            quote! { #name ( ::proto_vulcan::operator::OperatorParam::new( &[ #( #body ),* ] ) )};
+        let output = quote! {
+            #name ( #( #args, )* ::proto_vulcan::operator::OperatorParam::new( &[ #( #body ),* ] ) )
+        };
         output.to_tokens(tokens);
     }
 }
@@ -1325,17 +1415,52 @@ impl ToTokens for For {
 enum Value {
     Bool(syn::LitBool),
     Number(syn::LitInt),
+    Float(syn::LitFloat),
     Char(syn::LitChar),
     String(syn::LitStr),
+    // 'atom
+    Atom(syn::Lifetime),
+    // 1/2, -3/4
+    Rational {
+        neg: Option<Token![-]>,
+        numer: syn::LitInt,
+        denom: syn::LitInt,
+    },
+}
+
+impl Value {
+    /// Peeks a rational literal `n/d` or `-n/d`.
+    fn peek_rational(input: ParseStream) -> bool {
+        let fork = input.fork();
+        let _ = fork.parse::<Option<Token![-]>>();
+        fork.parse::<syn::LitInt>().is_ok()
+            && fork.parse::<Token![/]>().is_ok()
+            && fork.peek(syn::LitInt)
+    }
 }
 
 impl Parse for Value {
     fn parse(input: ParseStream) -> Result<Self> {
+        if Value::peek_rational(input) {
+            let neg = input.parse()?;
+            let numer = input.parse()?;
+            let _: Token![/] = input.parse()?;
+            let denom: syn::LitInt = input.parse()?;
+            if denom.base10_parse::<u128>()? == 0 {
+                return Err(Error::new(denom.span(), "Zero denominator"));
+            }
+            return Ok(Value::Rational { neg, numer, denom });
+        }
+        if input.peek(syn::Lifetime) {
+            let atom: syn::Lifetime = input.parse()?;
+            return Ok(Value::Atom(atom));
+        }
         let lit: syn::Lit = input.parse()?;
         match lit {
             syn::Lit::Str(s) => Ok(Value::String(s)),
             syn::Lit::Char(c) => Ok(Value::Char(c)),
             syn::Lit::Int(n) => Ok(Value::Number(n)),
+            syn::Lit::Float(x) => Ok(Value::Float(x)),
             syn::Lit::Bool(b) => Ok(Value::Bool(b)),
             _ => Err(Error::new(lit.span(), "Invalid literal")),
         }
@@ -1347,8 +1472,22 @@ impl ToTokens for Value {
         match self {
             Value::Bool(b) => b.to_tokens(tokens),
             Value::Number(n) => n.to_tokens(tokens),
+            Value::Float(x) => x.to_tokens(tokens),
             Value::Char(c) => c.to_tokens(tokens),
             Value::String(s) => s.to_tokens(tokens),
+            Value::Atom(atom) => {
+                let name = atom.ident.to_string();
+                let output = quote! { ::proto_vulcan::atom::Atom::new(#name) };
+                output.to_tokens(tokens);
+            }
+            Value::Rational { neg, numer, denom } => {
+                let output = quote! {
+                    ::proto_vulcan::lterm::LTermInner::Val(
+                        ::proto_vulcan::lvalue::LValue::rational(#neg #numer, #denom)
+                    )
+                };
+                output.to_tokens(tokens);
+            }
         }
     }
 }
@@ -1434,6 +1573,10 @@ impl ToTokens for InnerTreeTerm {
                     quote! { ::proto_vulcan::lterm::LTerm::from_array( &[ #(#items),* ] ) };
                 output.to_tokens(tokens);
             }
+            TreeTerm::Tie { atom, body } => {
+                let output = quote! { ::proto_vulcan::lterm::LTerm::tie( #atom, #body ) };
+                output.to_tokens(tokens);
+            }
         }
     }
 }
@@ -1445,11 +1588,31 @@ enum TreeTerm {
     Var(Ident),
     Field(FieldAccess),
     Any(Token![_]),
-    ImproperList { items: Vec<InnerTreeTerm> },
-    ProperList { items: Vec<InnerTreeTerm> },
+    ImproperList {
+        items: Vec<InnerTreeTerm>,
+    },
+    ProperList {
+        items: Vec<InnerTreeTerm>,
+    },
+    // tie(a, t)
+    Tie {
+        atom: Box<InnerTreeTerm>,
+        body: Box<InnerTreeTerm>,
+    },
 }
 
 impl TreeTerm {
+    /// Returns `true` if the input begins with a binder term `tie(a, t)`.
+    fn peek_tie(input: ParseStream) -> bool {
+        input.peek(Ident)
+            && input.peek2(Paren)
+            && input
+                .cursor()
+                .ident()
+                .map(|x| x.0 == "tie")
+                .unwrap_or(false)
+    }
+
     fn is_empty(&self) -> bool {
         match self {
             TreeTerm::ProperList { items } => items.len() == 0,
@@ -1477,6 +1640,10 @@ impl TreeTerm {
                     item.get_vars(vars);
                 }
             }
+            TreeTerm::Tie { atom, body } => {
+                atom.get_vars(vars);
+                body.get_vars(vars);
+            }
         }
     }
 }
@@ -1489,10 +1656,24 @@ impl Parse for TreeTerm {
         } else if (input.peek(Token![self]) || input.peek(Ident)) && input.peek2(Token![.]) {
             let field_access: FieldAccess = input.parse()?;
             Ok(TreeTerm::Field(field_access))
+        } else if TreeTerm::peek_tie(input) {
+            let _: Ident = input.parse()?;
+            let content;
+            let _ = parenthesized!(content in input);
+            let atom: InnerTreeTerm = content.parse()?;
+            let _: Token![,] = content.parse()?;
+            let body: InnerTreeTerm = content.parse()?;
+            if !content.is_empty() {
+                return Err(content.error("Trailing characters"));
+            }
+            Ok(TreeTerm::Tie {
+                atom: Box::new(atom),
+                body: Box::new(body),
+            })
         } else if input.peek(Ident) {
             let id: Ident = input.parse()?;
             Ok(TreeTerm::Var(id))
-        } else if input.peek(syn::Lit) {
+        } else if input.peek(syn::Lit) || input.peek(syn::Lifetime) || Value::peek_rational(input) {
             let value: Value = input.parse()?;
             Ok(TreeTerm::Value(value))
         } else if input.peek(Bracket) {
@@ -1566,6 +1747,10 @@ impl ToTokens for TreeTerm {
                 }
                 output.to_tokens(tokens);
             }
+            TreeTerm::Tie { atom, body } => {
+                let output = quote! { ::proto_vulcan::lterm::LTerm::tie( #atom, #body ) };
+                output.to_tokens(tokens);
+            }
         }
     }
 }
@@ -1624,12 +1809,600 @@ impl ToTokens for Diseq {
     }
 }
 
+#[allow(dead_code)]
+#[derive(Clone)]
+struct Freshness {
+    left: Argument,
+    hash: Token![#],
+    right: Argument,
+}
+
+impl Parse for Freshness {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Freshness {
+            left: input.parse()?,
+            hash: input.parse()?,
+            right: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Freshness {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let left = &self.left;
+        let right = &self.right;
+        let output = quote! { ::proto_vulcan::relation::nominal::hash ( #left, #right ) };
+        output.to_tokens(tokens)
+    }
+}
+
+/// Relational operator of an arithmetic constraint
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ArithRelOp {
+    // #=
+    Eq,
+    // #!=
+    Ne,
+    // #<
+    Lt,
+    // #<=
+    Le,
+    // #>
+    Gt,
+    // #>=
+    Ge,
+}
+
+impl Parse for ArithRelOp {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let _: Token![#] = input.parse()?;
+        if input.peek(Token![!=]) {
+            let _: Token![!=] = input.parse()?;
+            Ok(ArithRelOp::Ne)
+        } else if input.peek(Token![<=]) {
+            let _: Token![<=] = input.parse()?;
+            Ok(ArithRelOp::Le)
+        } else if input.peek(Token![>=]) {
+            let _: Token![>=] = input.parse()?;
+            Ok(ArithRelOp::Ge)
+        } else if input.peek(Token![==]) {
+            Err(input.error("Expected one of `#=`, `#!=`, `#<`, `#<=`, `#>` or `#>=`."))
+        } else if input.peek(Token![=]) {
+            let _: Token![=] = input.parse()?;
+            Ok(ArithRelOp::Eq)
+        } else if input.peek(Token![<]) {
+            let _: Token![<] = input.parse()?;
+            Ok(ArithRelOp::Lt)
+        } else if input.peek(Token![>]) {
+            let _: Token![>] = input.parse()?;
+            Ok(ArithRelOp::Gt)
+        } else {
+            Err(input.error("Expected one of `#=`, `#!=`, `#<`, `#<=`, `#>` or `#>=`."))
+        }
+    }
+}
+
+/// Binary operator of an arithmetic expression
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ArithBinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    Min,
+    Max,
+}
+
+impl ArithBinOp {
+    fn relation(self) -> proc_macro2::TokenStream {
+        match self {
+            ArithBinOp::Add => quote! { ::proto_vulcan::relation::clpfd::plusfd::plusfd },
+            ArithBinOp::Sub => quote! { ::proto_vulcan::relation::clpfd::minusfd::minusfd },
+            ArithBinOp::Mul => quote! { ::proto_vulcan::relation::clpfd::timesfd::timesfd },
+            ArithBinOp::Div => quote! { ::proto_vulcan::relation::clpfd::arithfd::divfd },
+            ArithBinOp::Mod => quote! { ::proto_vulcan::relation::clpfd::arithfd::modfd },
+            ArithBinOp::Pow => quote! { ::proto_vulcan::relation::clpfd::arithfd::powfd },
+            ArithBinOp::Min => quote! { ::proto_vulcan::relation::clpfd::arithfd::minfd },
+            ArithBinOp::Max => quote! { ::proto_vulcan::relation::clpfd::arithfd::maxfd },
+        }
+    }
+}
+
+/// Arithmetic expression on finite domain terms
+#[derive(Clone)]
+enum ArithExpr {
+    // x, self.x, 1, {expr}
+    Term(Box<Argument>),
+    // -e
+    Neg(Box<ArithExpr>),
+    // abs(e)
+    Abs(Box<ArithExpr>),
+    // e + e, e - e, e * e, e / e, e % e, e ^ e, min(e, e), max(e, e)
+    Binary(ArithBinOp, Box<ArithExpr>, Box<ArithExpr>),
+}
+
+impl ArithExpr {
+    /// Parses sums and differences, which have the lowest precedence.
+    fn parse_sum(input: ParseStream) -> Result<Self> {
+        let mut expr = ArithExpr::parse_product(input)?;
+        loop {
+            let op = if input.peek(Token![+]) {
+                let _: Token![+] = input.parse()?;
+                ArithBinOp::Add
+            } else if input.peek(Token![-]) {
+                let _: Token![-] = input.parse()?;
+                ArithBinOp::Sub
+            } else {
+                break;
+            };
+            let right = ArithExpr::parse_product(input)?;
+            expr = ArithExpr::Binary(op, Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn parse_product(input: ParseStream) -> Result<Self> {
+        let mut expr = ArithExpr::parse_unary(input)?;
+        loop {
+            let op = if input.peek(Token![*]) {
+                let _: Token![*] = input.parse()?;
+                ArithBinOp::Mul
+            } else if input.peek(Token![/]) {
+                let _: Token![/] = input.parse()?;
+                ArithBinOp::Div
+            } else if input.peek(Token![%]) {
+                let _: Token![%] = input.parse()?;
+                ArithBinOp::Mod
+            } else {
+                break;
+            };
+            let right = ArithExpr::parse_unary(input)?;
+            expr = ArithExpr::Binary(op, Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![-]) {
+            let _: Token![-] = input.parse()?;
+            let expr = ArithExpr::parse_unary(input)?;
+            Ok(ArithExpr::Neg(Box::new(expr)))
+        } else {
+            ArithExpr::parse_power(input)
+        }
+    }
+
+    /// Power is right associative, and binds tighter than unary minus.
+    fn parse_power(input: ParseStream) -> Result<Self> {
+        let base = ArithExpr::parse_primary(input)?;
+        if input.peek(Token![^]) {
+            let _: Token![^] = input.parse()?;
+            let exponent = ArithExpr::parse_unary(input)?;
+            Ok(ArithExpr::Binary(
+                ArithBinOp::Pow,
+                Box::new(base),
+                Box::new(exponent),
+            ))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn parse_primary(input: ParseStream) -> Result<Self> {
+        let maybe_ident = input.cursor().ident().map(|x| x.0.to_string());
+        if input.peek(Paren) {
+            let content;
+            let _ = parenthesized!(content in input);
+            let expr = ArithExpr::parse_sum(&content)?;
+            if !content.is_empty() {
+                return Err(content.error("Trailing characters"));
+            }
+            Ok(expr)
+        } else if input.peek(Ident) && input.peek2(Paren) {
+            let name: Ident = input.parse()?;
+            let content;
+            let _ = parenthesized!(content in input);
+            let args: Punctuated<ArithExpr, Token![,]> =
+                content.parse_terminated(ArithExpr::parse_sum)?;
+            let mut args = args.into_iter();
+            let expr = match (
+                maybe_ident.as_deref(),
+                args.next(),
+                args.next(),
+                args.next(),
+            ) {
+                (Some("abs"), Some(e), None, None) => ArithExpr::Abs(Box::new(e)),
+                (Some("min"), Some(a), Some(b), None) => {
+                    ArithExpr::Binary(ArithBinOp::Min, Box::new(a), Box::new(b))
+                }
+                (Some("max"), Some(a), Some(b), None) => {
+                    ArithExpr::Binary(ArithBinOp::Max, Box::new(a), Box::new(b))
+                }
+                _ => {
+                    return Err(Error::new(
+                        name.span(),
+                        "Expected `abs(e)`, `min(e, e)` or `max(e, e)`.",
+                    ))
+                }
+            };
+            Ok(expr)
+        } else if input.peek(syn::LitInt) {
+            // An integer literal is not parsed as a term, where `n / d` would be a rational.
+            let value = Value::Number(input.parse()?);
+            Ok(ArithExpr::Term(Box::new(Argument::TreeTerm(
+                TreeTerm::Value(value),
+            ))))
+        } else if input.peek(Brace) || input.peek(Ident) || input.peek(Token![self]) {
+            Ok(ArithExpr::Term(input.parse()?))
+        } else {
+            Err(input.error("Invalid arithmetic expression."))
+        }
+    }
+
+    /// Appends the goals that compute the value of the expression into a fresh temporary
+    /// variable, and returns the term that holds the value.
+    fn lower(
+        &self,
+        temporaries: &mut Vec<Ident>,
+        goals: &mut Vec<proc_macro2::TokenStream>,
+    ) -> proc_macro2::TokenStream {
+        match self {
+            ArithExpr::Term(term) => quote! { #term },
+            _ => {
+                let temporary = quote::format_ident!("__arith_{}", temporaries.len());
+                temporaries.push(temporary.clone());
+                let result = quote! { ::proto_vulcan::Upcast::to_super(&#temporary) };
+                goals.push(quote! {
+                    ::proto_vulcan::relation::clpfd::infd::infdrange(
+                        #result,
+                        &(::std::isize::MIN..=::std::isize::MAX),
+                    )
+                });
+                self.lower_into(result.clone(), temporaries, goals);
+                result
+            }
+        }
+    }
+
+    /// Appends the goals that constrain `result` to the value of the expression.
+    fn lower_into(
+        &self,
+        result: proc_macro2::TokenStream,
+        temporaries: &mut Vec<Ident>,
+        goals: &mut Vec<proc_macro2::TokenStream>,
+    ) {
+        match self {
+            ArithExpr::Term(term) => {
+                goals.push(quote! { ::proto_vulcan::relation::eq::eq ( #term, #result ) });
+            }
+            ArithExpr::Neg(expr) => {
+                let u = expr.lower(temporaries, goals);
+                goals.push(quote! {
+                    ::proto_vulcan::relation::clpfd::minusfd::minusfd(
+                        ::proto_vulcan::lterm::LTerm::from(0), #u, #result)
+                });
+            }
+            ArithExpr::Abs(expr) => {
+                let u = expr.lower(temporaries, goals);
+                goals.push(quote! { ::proto_vulcan::relation::clpfd::arithfd::absfd(#u, #result) });
+            }
+            ArithExpr::Binary(op, left, right) => {
+                let u = left.lower(temporaries, goals);
+                let v = right.lower(temporaries, goals);
+                let relation = op.relation();
+                goals.push(quote! { #relation(#u, #v, #result) });
+            }
+        }
+    }
+}
+
+impl Parse for ArithExpr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        ArithExpr::parse_sum(input)
+    }
+}
+
+/// Arithmetic constraint, such as `z #= (x + y) * 2`, that is lowered into CLP(FD) relations.
+/// There is no lowering into CLP(Z); the temporaries are finite-domain variables over all `isize`
+/// values.
+#[derive(Clone)]
+struct ArithConstraint {
+    left: ArithExpr,
+    op: ArithRelOp,
+    right: ArithExpr,
+}
+
+impl Parse for ArithConstraint {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(ArithConstraint {
+            left: input.parse()?,
+            op: input.parse()?,
+            right: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ArithConstraint {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let mut temporaries = vec![];
+        let mut goals = vec![];
+        match (self.op, &self.left, &self.right) {
+            // When one side is a term, it holds the value of the other side directly.
+            (ArithRelOp::Eq, ArithExpr::Term(term), expr)
+            | (ArithRelOp::Eq, expr, ArithExpr::Term(term)) => {
+                expr.lower_into(quote! { #term }, &mut temporaries, &mut goals);
+            }
+            (ArithRelOp::Eq, left, right) => {
+                let u = left.lower(&mut temporaries, &mut goals);
+                right.lower_into(u, &mut temporaries, &mut goals);
+            }
+            (op, left, right) => {
+                let u = left.lower(&mut temporaries, &mut goals);
+                let v = right.lower(&mut temporaries, &mut goals);
+                let goal = match op {
+                    ArithRelOp::Ne => {
+                        quote! { ::proto_vulcan::relation::clpfd::diseqfd::diseqfd(#u, #v) }
+                    }
+                    ArithRelOp::Lt => {
+                        quote! { ::proto_vulcan::relation::clpfd::ltfd::ltfd(#u, #v) }
+                    }
+                    ArithRelOp::Le => {
+                        quote! { ::proto_vulcan::relation::clpfd::ltefd::ltefd(#u, #v) }
+                    }
+                    ArithRelOp::Gt => {
+                        quote! { ::proto_vulcan::relation::clpfd::ltfd::ltfd(#v, #u) }
+                    }
+                    ArithRelOp::Ge => {
+                        quote! { ::proto_vulcan::relation::clpfd::ltefd::ltefd(#v, #u) }
+                    }
+                    ArithRelOp::Eq => unreachable!(),
+                };
+                goals.push(goal);
+            }
+        }
+
+        let body = quote! {
+            ::proto_vulcan::operator::conj::InferredConj::from_array(&[
+                #( ::proto_vulcan::GoalCast::cast_into( #goals ) ),*
+            ])
+        };
+        let output = if temporaries.is_empty() {
+            body
+        } else {
+            quote! {{
+                #( let #temporaries: ::proto_vulcan::lterm::LTerm<_, _> =
+                    ::proto_vulcan::lterm::LTerm::var(stringify!(#temporaries)); )*
+                ::proto_vulcan::operator::fresh::Fresh::new(
+                    vec![ #( ::proto_vulcan::Upcast::to_super(&#temporaries) ),* ],
+                    ::proto_vulcan::GoalCast::cast_into(#body),
+                )
+            }}
+        };
+        output.to_tokens(tokens);
+    }
+}
+
+/// Operators of linear expressions
+#[derive(Clone, Copy)]
+enum LinearBinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Linear expression over rationals, such as `2 * x + y / 3 - 1.5`.
+#[derive(Clone)]
+enum LinearExpr {
+    Int(syn::LitInt),
+    Float(syn::LitFloat),
+    Term(Box<Argument>),
+    Neg(Box<LinearExpr>),
+    Binary(LinearBinOp, Box<LinearExpr>, Box<LinearExpr>),
+}
+
+impl LinearExpr {
+    fn parse_sum(input: ParseStream) -> Result<Self> {
+        let mut expr = LinearExpr::parse_product(input)?;
+        loop {
+            let op = if input.peek(Token![+]) {
+                let _: Token![+] = input.parse()?;
+                LinearBinOp::Add
+            } else if input.peek(Token![-]) {
+                let _: Token![-] = input.parse()?;
+                LinearBinOp::Sub
+            } else {
+                return Ok(expr);
+            };
+            let right = LinearExpr::parse_product(input)?;
+            expr = LinearExpr::Binary(op, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn parse_product(input: ParseStream) -> Result<Self> {
+        let mut expr = LinearExpr::parse_unary(input)?;
+        loop {
+            let op = if input.peek(Token![*]) {
+                let _: Token![*] = input.parse()?;
+                LinearBinOp::Mul
+            } else if input.peek(Token![/]) {
+                let _: Token![/] = input.parse()?;
+                LinearBinOp::Div
+            } else {
+                return Ok(expr);
+            };
+            let right = LinearExpr::parse_unary(input)?;
+            expr = LinearExpr::Binary(op, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn parse_unary(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![-]) {
+            let _: Token![-] = input.parse()?;
+            let expr = LinearExpr::parse_unary(input)?;
+            Ok(LinearExpr::Neg(Box::new(expr)))
+        } else if input.peek(Paren) {
+            let content;
+            let _ = parenthesized!(content in input);
+            let expr = LinearExpr::parse_sum(&content)?;
+            if !content.is_empty() {
+                return Err(content.error("Trailing characters"));
+            }
+            Ok(expr)
+        } else if input.peek(syn::LitInt) {
+            Ok(LinearExpr::Int(input.parse()?))
+        } else if input.peek(syn::LitFloat) {
+            Ok(LinearExpr::Float(input.parse()?))
+        } else if input.peek(Brace) || input.peek(Ident) || input.peek(Token![self]) {
+            Ok(LinearExpr::Term(input.parse()?))
+        } else {
+            Err(input.error("Invalid linear expression."))
+        }
+    }
+}
+
+impl Parse for LinearExpr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        LinearExpr::parse_sum(input)
+    }
+}
+
+impl ToTokens for LinearExpr {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let output = match self {
+            LinearExpr::Int(lit) => {
+                quote! { ::proto_vulcan::relation::clpq::LinearExpr::integer(#lit) }
+            }
+            LinearExpr::Float(lit) => {
+                let digits = lit.base10_digits();
+                quote! { ::proto_vulcan::relation::clpq::LinearExpr::decimal(#digits) }
+            }
+            LinearExpr::Term(term) => {
+                quote! { ::proto_vulcan::relation::clpq::LinearExpr::term(#term) }
+            }
+            LinearExpr::Neg(expr) => quote! { -(#expr) },
+            LinearExpr::Binary(op, left, right) => match op {
+                LinearBinOp::Add => quote! { (#left) + (#right) },
+                LinearBinOp::Sub => quote! { (#left) - (#right) },
+                LinearBinOp::Mul => quote! { (#left) * (#right) },
+                LinearBinOp::Div => quote! { (#left) / (#right) },
+            },
+        };
+        output.to_tokens(tokens);
+    }
+}
+
+/// Relations of linear constraints
+#[derive(Clone, Copy)]
+enum LinearRelOp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Parse for LinearRelOp {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![==]) {
+            let _: Token![==] = input.parse()?;
+            Ok(LinearRelOp::Eq)
+        } else if input.peek(Token![<=]) {
+            let _: Token![<=] = input.parse()?;
+            Ok(LinearRelOp::Le)
+        } else if input.peek(Token![>=]) {
+            let _: Token![>=] = input.parse()?;
+            Ok(LinearRelOp::Ge)
+        } else if input.peek(Token![<]) {
+            let _: Token![<] = input.parse()?;
+            Ok(LinearRelOp::Lt)
+        } else if input.peek(Token![>]) {
+            let _: Token![>] = input.parse()?;
+            Ok(LinearRelOp::Gt)
+        } else {
+            Err(input.error("Expected `==`, `<`, `<=`, `>` or `>=`."))
+        }
+    }
+}
+
+/// Linear constraint over rationals, such as `2 * x + y <= 10`.
+#[derive(Clone)]
+struct LinearConstraint {
+    left: LinearExpr,
+    op: LinearRelOp,
+    right: LinearExpr,
+}
+
+impl Parse for LinearConstraint {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(LinearConstraint {
+            left: input.parse()?,
+            op: input.parse()?,
+            right: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for LinearConstraint {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let left = &self.left;
+        let right = &self.right;
+        let op = match self.op {
+            LinearRelOp::Eq => quote! { Eq },
+            LinearRelOp::Lt => quote! { Lt },
+            LinearRelOp::Le => quote! { Le },
+            LinearRelOp::Gt => quote! { Gt },
+            LinearRelOp::Ge => quote! { Ge },
+        };
+        let output = quote! {
+            ::proto_vulcan::relation::clpq::linear::linear(
+                #left,
+                ::proto_vulcan::state::simplex::LinearRelation::#op,
+                #right,
+            )
+        };
+        output.to_tokens(tokens);
+    }
+}
+
+/// Braced list of linear constraints, such as `{ 2 * x + y <= 10, x >= 0 }`.
+#[derive(Clone)]
+struct LinearConstraints {
+    constraints: Punctuated<LinearConstraint, Token![,]>,
+}
+
+impl Parse for LinearConstraints {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let _ = braced!(content in input);
+        Ok(LinearConstraints {
+            constraints: content.parse_terminated(LinearConstraint::parse)?,
+        })
+    }
+}
+
+impl ToTokens for LinearConstraints {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let constraints = self.constraints.iter();
+        let output = quote! {
+            ::proto_vulcan::operator::conj::InferredConj::from_array(&[
+                #( ::proto_vulcan::GoalCast::cast_into( #constraints ) ),*
+            ])
+        };
+        output.to_tokens(tokens);
+    }
+}
+
 #[derive(Clone)]
 enum Clause {
     /// for x in coll { }
     For(For),
     /// project |x, y, z| { }
     Project(Project),
+    /// nom |a, b| { }
+    Nom(Nom),
     // fngoal |state| { }
     FnGoal(FnGoal),
     /// |x, y, z| { }
@@ -1638,6 +2411,12 @@ enum Clause {
     Eq(Eq),
     // x != y
     Diseq(Diseq),
+    // z #= (x + y) * 2, x #< y, ...
+    Arith(ArithConstraint),
+    // a # t
+    Freshness(Freshness),
+    // { 2 * x + y <= 10, ... }
+    Linear(LinearConstraints),
     // true
     Succeed(syn::LitBool),
     // false
@@ -1650,7 +2429,7 @@ enum Clause {
     Closure(Closure),
     // loop { }
     Loop(Loop),
-    // $operator { }
+    // $operator { } or $operator ( arg0, arg1, ... ) { }
     Operator(Operator),
     // $operator $term { pattern0 => body0, ...}
     PatternMatchOperator(PatternMatchOperator),
@@ -1668,6 +2447,12 @@ impl Parse for Clause {
         {
             let project: Project = input.parse()?;
             Ok(Clause::Project(project))
+        } else if input.peek(Ident)
+            && input.peek2(Token![|])
+            && maybe_ident == Some(String::from("nom"))
+        {
+            let nom: Nom = input.parse()?;
+            Ok(Clause::Nom(nom))
         } else if input.peek(Ident)
             && (input.peek2(Token![|]) || (input.peek2(Token![move]) && input.peek3(Token![|])))
             && maybe_ident == Some(String::from("fngoal"))
@@ -1689,6 +2474,15 @@ impl Parse for Clause {
         } else if input.peek(Token![|]) {
             let fresh: Fresh = input.parse()?;
             Ok(Clause::Fresh(fresh))
+        } else if input.peek(Brace) && LinearConstraints::parse(&input.fork()).is_ok() {
+            let linear: LinearConstraints = input.parse()?;
+            Ok(Clause::Linear(linear))
+        } else if ArithConstraint::parse(&input.fork()).is_ok() {
+            let arith: ArithConstraint = input.parse()?;
+            Ok(Clause::Arith(arith))
+        } else if Freshness::parse(&input.fork()).is_ok() {
+            let freshness: Freshness = input.parse()?;
+            Ok(Clause::Freshness(freshness))
         } else if let Ok(_) = Eq::parse(&input.fork()) {
             let eq: Eq = input.parse()?;
             Ok(Clause::Eq(eq))
@@ -1705,6 +2499,9 @@ impl Parse for Clause {
         } else if input.peek(Bracket) {
             let conjunction: Conjunction = input.parse()?;
             Ok(Clause::Conjunction(conjunction))
+        } else if input.peek(Ident) && input.peek2(Paren) && Operator::peek_with_args(input) {
+            let operator: Operator = input.parse()?;
+            Ok(Clause::Operator(operator))
         } else if input.peek(Ident) && input.peek2(Paren) {
             let relation: Relation = input.parse()?;
             Ok(Clause::Relation(relation))
@@ -1731,6 +2528,9 @@ impl ToTokens for Clause {
             Clause::Project(project) => {
                 project.to_tokens(tokens);
             }
+            Clause::Nom(nom) => {
+                nom.to_tokens(tokens);
+            }
             Clause::FnGoal(fngoal) => {
                 fngoal.to_tokens(tokens);
             }
@@ -1743,6 +2543,15 @@ impl ToTokens for Clause {
             Clause::Diseq(diseq) => {
                 diseq.to_tokens(tokens);
             }
+            Clause::Arith(arith) => {
+                arith.to_tokens(tokens);
+            }
+            Clause::Freshness(freshness) => {
+                freshness.to_tokens(tokens);
+            }
+            Clause::Linear(linear) => {
+                linear.to_tokens(tokens);
+            }
             Clause::Succeed(_) => {
                 let output = quote! { ::proto_vulcan::relation::succeed() };
                 output.to_tokens(tokens);
@@ -1800,6 +2609,10 @@ impl ToTokens for ClauseInOperator {
                 let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#project) ] };
                 output.to_tokens(tokens);
             }
+            Clause::Nom(nom) => {
+                let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#nom) ] };
+                output.to_tokens(tokens);
+            }
             Clause::FnGoal(fngoal) => {
                 let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#fngoal) ] };
                 output.to_tokens(tokens);
@@ -1816,6 +2629,18 @@ impl ToTokens for ClauseInOperator {
                 let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#diseq) ] };
                 output.to_tokens(tokens);
             }
+            Clause::Arith(arith) => {
+                let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#arith) ] };
+                output.to_tokens(tokens);
+            }
+            Clause::Freshness(freshness) => {
+                let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#freshness) ] };
+                output.to_tokens(tokens);
+            }
+            Clause::Linear(linear) => {
+                let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#linear) ] };
+                output.to_tokens(tokens);
+            }
             Clause::Succeed(_) => {
                 let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(::proto_vulcan::relation::succeed()) ] };
                 output.to_tokens(tokens);
@@ -2085,6 +2910,11 @@ fn make_compound_unnamed_struct(itemstruct: syn::ItemStruct) -> TokenStream {
         .map(|(n, _)| syn::Index::from(n))
         .collect();
 
+    let wildcards: Vec<proc_macro2::TokenStream> = field_indices
+        .iter()
+        .map(|_| quote! { ::proto_vulcan::compound::CompoundTerm::new_wildcard() })
+        .collect();
+
     let output = quote!(
         #[allow(non_snake_case)]
         #vis mod #mod_name {
@@ -2187,6 +3017,14 @@ fn make_compound_unnamed_struct(itemstruct: syn::ItemStruct) -> TokenStream {
             }
         }
 
+        impl #impl_generics #struct_name #type_generics #where_clause {
+            /// Returns a new compound term where the fields are wildcards, for registering the
+            /// compound type with `register_compound`.
+            pub fn new_template() -> #struct_name #type_generics {
+                Into::into(#mod_name::#inner_ident(#( #wildcards ),* ))
+            }
+        }
+
         #[automatically_derived]
         impl #impl_generics ::proto_vulcan::compound::CompoundTerm #type_generics for #struct_name #type_generics #where_clause {
             fn new_var(name: &'static str) -> #struct_name #type_generics {
@@ -2385,6 +3223,16 @@ fn make_compound_named_struct(itemstruct: syn::ItemStruct) -> TokenStream {
             }
         }
 
+        impl #impl_generics #struct_name #type_generics #where_clause {
+            /// Returns a new compound term where the fields are wildcards, for registering the
+            /// compound type with `register_compound`.
+            pub fn new_template() -> #struct_name #type_generics {
+                Into::into(#mod_name::#inner_ident {
+                    #( #field_names: ::proto_vulcan::compound::CompoundTerm::new_wildcard() ),*
+                })
+            }
+        }
+
         #[automatically_derived]
         impl #impl_generics ::proto_vulcan::compound::CompoundTerm #type_generics for #struct_name #type_generics #where_clause {
             fn new_var(name: &'static str) -> #struct_name #type_generics {
@@ -0,0 +1,143 @@
+//! # Atoms
+//! An atom is an interned symbol. Atoms with the same name are represented by the same interned
+//! string, and they are compared and hashed by the address of the string, in constant time.
+//!
+//! Atoms are written in proto-vulcan with a leading quote: `'red`.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! fn main() {
+//!     let query = proto_vulcan_query!(|q| {
+//!         conde {
+//!             q == 'red,
+//!             q == 'green,
+//!         },
+//!         q != 'red,
+//!     });
+//!     let mut iter = query.run();
+//!     assert_eq!(iter.next().unwrap().q, Atom::from("green"));
+//!     assert!(iter.next().is_none());
+//! }
+//! ```
+//!
+//! The interned names are never released. The interner is local to the thread, like the terms
+//! that refer to the atoms, and atoms cannot be sent to other threads.
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+thread_local! {
+    static INTERNER: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::new());
+}
+
+/// Interned symbol
+#[derive(Clone, Copy)]
+pub struct Atom {
+    name: &'static str,
+    // Atoms are interned per thread.
+    _marker: PhantomData<*const ()>,
+}
+
+impl Atom {
+    /// Returns the atom with the given name.
+    pub fn new(name: &str) -> Atom {
+        let name = INTERNER.with(|interner| {
+            let mut interner = interner.borrow_mut();
+            match interner.get(name) {
+                Some(interned) => *interned,
+                None => {
+                    let interned: &'static str = Box::leak(String::from(name).into_boxed_str());
+                    interner.insert(interned);
+                    interned
+                }
+            }
+        });
+        Atom {
+            name,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Name of the atom
+    pub fn as_str(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl PartialEq for Atom {
+    fn eq(&self, other: &Atom) -> bool {
+        std::ptr::eq(self.name, other.name)
+    }
+}
+
+impl Eq for Atom {}
+
+impl Hash for Atom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.name.as_ptr() as usize).hash(state)
+    }
+}
+
+impl PartialEq<str> for Atom {
+    fn eq(&self, other: &str) -> bool {
+        self.name == other
+    }
+}
+
+impl PartialEq<&str> for Atom {
+    fn eq(&self, other: &&str) -> bool {
+        self.name == *other
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(name: &str) -> Atom {
+        Atom::new(name)
+    }
+}
+
+impl From<Atom> for &'static str {
+    fn from(atom: Atom) -> &'static str {
+        atom.name
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+impl fmt::Debug for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}", self.name)
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Atom;
+
+    #[test]
+    fn test_atom_1() {
+        let red = Atom::new("red");
+        let green = Atom::from("green");
+        assert_eq!(red, Atom::from(&String::from("red")[..]));
+        assert!(red != green);
+        assert!(red == "red");
+        assert_eq!(red.as_str(), "red");
+        let name: &str = green.into();
+        assert_eq!(name, "green");
+        assert_eq!(format!("{} {:?}", red, green), "red 'green");
+    }
+}
@@ -42,14 +42,40 @@
 //! # Type conversions
 //! Type conversions to supertypes are done implicitly via inserted `Into::into` calls;
 //! for conversions to subtypes, such as compound types, unification must be used.
+//!
+//! # Construction by name
+//! Compound terms can be constructed from their type name and fields, for example with the
+//! `univo` and `functoro` relations, when the compound type has been registered with
+//! [`register_compound`]. The registration takes a template of the compound, where the
+//! fields are wildcards; the constructed compound is the template with its fields unified
+//! with the given terms. The `#[compound]` attribute generates a `new_template()` function
+//! that returns such a template. The registry is kept per thread.
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::compound::{compound_template, register_compound};
+//! use proto_vulcan::prelude::*;
+//!
+//! #[compound]
+//! struct Pair(LTerm, LTerm);
+//!
+//! fn main() {
+//!     register_compound(|| -> LTerm { Pair::new_template().into() });
+//!     let pair: Option<LTerm> = compound_template("Pair", 2);
+//!     assert!(pair.is_some());
+//!     let triple: Option<LTerm> = compound_template("Pair", 3);
+//!     assert!(triple.is_none());
+//! }
+//! ```
 
 use crate::engine::Engine;
 use crate::lterm::{LTerm, LTermInner};
 use crate::state::SMap;
 use crate::user::User;
 use crate::{Downcast, Upcast};
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
@@ -418,3 +444,63 @@ where
         self
     }
 }
+
+type CompoundTemplate<U, E> = Rc<dyn Fn() -> LTerm<U, E>>;
+
+/// Compound templates by type name and number of fields
+type CompoundTemplates<U, E> = HashMap<&'static str, HashMap<usize, CompoundTemplate<U, E>>>;
+
+thread_local! {
+    static REGISTRY: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Registers a compound type for construction by name. The `template` function must return
+/// a compound term where the fields are wildcards, such as the one returned by the
+/// `new_template()` function generated by `#[compound]`. A later registration of a compound with
+/// the same type name and number of fields replaces the earlier one.
+///
+/// # Panics
+///
+/// Panics if the template is not a compound term.
+pub fn register_compound<U, E, F>(template: F)
+where
+    U: User,
+    E: Engine<U>,
+    F: Fn() -> LTerm<U, E> + 'static,
+{
+    let (name, arity) = match template().as_ref() {
+        LTermInner::Compound(object) => (object.type_name(), object.children().count()),
+        _ => panic!("Compound template must be a compound term"),
+    };
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let templates = registry
+            .entry(TypeId::of::<CompoundTemplates<U, E>>())
+            .or_insert_with(|| Box::new(CompoundTemplates::<U, E>::new()))
+            .downcast_mut::<CompoundTemplates<U, E>>()
+            .unwrap();
+        templates
+            .entry(name)
+            .or_insert_with(HashMap::new)
+            .insert(arity, Rc::new(template));
+    })
+}
+
+/// Returns a new template of the registered compound type with the given type name and
+/// number of fields, or `None` if no such compound type has been registered.
+pub fn compound_template<U, E>(name: &str, arity: usize) -> Option<LTerm<U, E>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let template = REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .get(&TypeId::of::<CompoundTemplates<U, E>>())
+            .and_then(|templates| templates.downcast_ref::<CompoundTemplates<U, E>>())
+            .and_then(|templates| templates.get(name))
+            .and_then(|templates| templates.get(&arity))
+            .cloned()
+    });
+    template.map(|template| template())
+}
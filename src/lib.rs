@@ -11,6 +11,7 @@ pub use proto_vulcan_macros::{
 #[macro_use]
 extern crate derivative;
 
+pub mod atom;
 pub mod compound;
 use compound::CompoundObject;
 
@@ -22,6 +23,7 @@ pub mod lresult;
 pub mod lterm;
 pub mod lvalue;
 pub mod operator;
+pub mod order;
 pub mod query;
 pub mod relation;
 pub mod solver;
@@ -69,6 +71,7 @@ pub mod prelude {
         compound, lterm, proto_vulcan, proto_vulcan_closure, proto_vulcan_query,
     };
 
+    pub use crate::atom::Atom;
     pub use crate::compound::CompoundTerm;
     pub use crate::engine::{DefaultEngine, Engine};
     pub use crate::goal::{AnyGoal, Goal};
@@ -1,3 +1,4 @@
+use crate::atom::Atom;
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::engine::Engine;
@@ -178,6 +179,58 @@ where
     }
 }
 
+impl<U, E> PartialEq<f64> for LResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &f64) -> bool {
+        match self.as_ref() {
+            LTermInner::Val(val) => val == other,
+            _ => false,
+        }
+    }
+}
+
+impl<U, E> PartialEq<LResult<U, E>> for f64
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &LResult<U, E>) -> bool {
+        match other.as_ref() {
+            LTermInner::Val(val) => val == self,
+            _ => false,
+        }
+    }
+}
+
+impl<U, E> PartialEq<Atom> for LResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &Atom) -> bool {
+        match self.as_ref() {
+            LTermInner::Val(val) => val == other,
+            _ => false,
+        }
+    }
+}
+
+impl<U, E> PartialEq<LResult<U, E>> for Atom
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &LResult<U, E>) -> bool {
+        match other.as_ref() {
+            LTermInner::Val(val) => val == self,
+            _ => false,
+        }
+    }
+}
+
 impl<U, E> PartialEq<char> for LResult<U, E>
 where
     U: User,
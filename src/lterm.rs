@@ -1,3 +1,4 @@
+use crate::atom::Atom;
 use crate::compound::CompoundObject;
 use crate::engine::{DefaultEngine, Engine};
 use crate::user::{DefaultUser, User};
@@ -14,7 +15,7 @@ pub use crate::lvalue::LValue;
 
 static UNIQUE_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
-#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct VarID(usize);
 
 impl VarID {
@@ -60,6 +61,16 @@ where
 
     // Compound object
     Compound(Rc<dyn CompoundObject<U, E>>),
+
+    /// Nominal atom (uid, name)
+    Nom(VarID, &'static str),
+
+    /// Binder term that binds the nominal atom within the body (atom, body)
+    Tie(LTerm<U, E>, LTerm<U, E>),
+
+    /// Suspension of a permutation of nominal atoms on a variable (permutation, variable). The
+    /// permutation is applied when the variable is bound.
+    Susp(Vec<(LTerm<U, E>, LTerm<U, E>)>, LTerm<U, E>),
 }
 
 #[derive(Derivative)]
@@ -97,6 +108,34 @@ where
         }
     }
 
+    /// Constructs a new nominal atom. Each nominal atom is distinct from all other atoms,
+    /// including atoms with the same name.
+    pub fn nom(name: &'static str) -> LTerm<U, E> {
+        LTerm {
+            inner: Rc::new(LTermInner::Nom(VarID::new(), name)),
+        }
+    }
+
+    /// Constructs a binder term that binds the nominal atom `a` within the term `t`. Binder
+    /// terms unify modulo alpha-equivalence.
+    pub fn tie(a: LTerm<U, E>, t: LTerm<U, E>) -> LTerm<U, E> {
+        assert!(a.is_nom(), "Error: Binder term must bind a nominal atom.");
+        LTerm {
+            inner: Rc::new(LTermInner::Tie(a, t)),
+        }
+    }
+
+    /// Constructs a suspension of the permutation `perm` on the variable `x`.
+    pub fn susp(perm: Vec<(LTerm<U, E>, LTerm<U, E>)>, x: LTerm<U, E>) -> LTerm<U, E> {
+        if perm.is_empty() {
+            x
+        } else {
+            LTerm {
+                inner: Rc::new(LTermInner::Susp(perm, x)),
+            }
+        }
+    }
+
     pub fn user(u: U::UserTerm) -> LTerm<U, E> {
         LTerm {
             inner: Rc::new(LTermInner::User(u)),
@@ -254,6 +293,52 @@ where
         }
     }
 
+    pub fn is_string(&self) -> bool {
+        match self.as_ref() {
+            LTermInner::Val(LValue::String(_)) => true,
+            _ => false,
+        }
+    }
+
+    pub fn get_string(&self) -> Option<&str> {
+        match self.as_ref() {
+            LTermInner::Val(LValue::String(u)) => Some(u),
+            _ => None,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        match self.as_ref() {
+            LTermInner::Val(LValue::Float(_)) => true,
+            _ => false,
+        }
+    }
+
+    pub fn get_float(&self) -> Option<f64> {
+        match self.as_ref() {
+            LTermInner::Val(LValue::Float(u)) => Some(*u),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an integer or rational term as a rational number.
+    #[cfg(feature = "rational")]
+    pub fn get_rational(&self) -> Option<num_rational::BigRational> {
+        match self.as_ref() {
+            LTermInner::Val(val) => val.to_rational(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an integer term as an arbitrary-precision integer.
+    #[cfg(feature = "bigint")]
+    pub fn get_bigint(&self) -> Option<num_bigint::BigInt> {
+        match self.as_ref() {
+            LTermInner::Val(val) => val.to_bigint(),
+            _ => None,
+        }
+    }
+
     pub fn is_var(&self) -> bool {
         match self.as_ref() {
             LTermInner::<U, E>::Var(_, _) => true,
@@ -268,6 +353,20 @@ where
         }
     }
 
+    pub fn is_nom(&self) -> bool {
+        match self.as_ref() {
+            LTermInner::Nom(_, _) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_tie(&self) -> bool {
+        match self.as_ref() {
+            LTermInner::Tie(_, _) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_user(&self) -> bool {
         match self.as_ref() {
             LTermInner::User(_) => true,
@@ -383,6 +482,8 @@ where
                 }
                 vars
             }
+            LTermInner::Tie(_, body) => body.anyvars(),
+            LTermInner::Susp(_, x) => x.anyvars(),
             _ => {
                 if self.is_any() {
                     vec![self.clone()]
@@ -436,6 +537,16 @@ where
     }
 }
 
+impl<U, E> From<f64> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(u: f64) -> LTerm<U, E> {
+        LTerm::from(LTermInner::Val(LValue::Float(u)))
+    }
+}
+
 impl<U, E> From<bool> for LTerm<U, E>
 where
     U: User,
@@ -466,6 +577,38 @@ where
     }
 }
 
+#[cfg(feature = "bigint")]
+impl<U, E> From<num_bigint::BigInt> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(u: num_bigint::BigInt) -> LTerm<U, E> {
+        LTerm::from(LTermInner::Val(LValue::from(u)))
+    }
+}
+
+#[cfg(feature = "rational")]
+impl<U, E> From<num_rational::BigRational> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(u: num_rational::BigRational) -> LTerm<U, E> {
+        LTerm::from(LTermInner::Val(LValue::from(u)))
+    }
+}
+
+impl<U, E> From<Atom> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(u: Atom) -> LTerm<U, E> {
+        LTerm::from(LTermInner::Val(LValue::Atom(u)))
+    }
+}
+
 impl<U, E> From<char> for LTerm<U, E>
 where
     U: User,
@@ -520,6 +663,9 @@ where
             LTermInner::Empty => write!(f, "Empty"),
             LTermInner::Cons(head, tail) => write!(f, "({:?}, {:?})", head, tail),
             LTermInner::Compound(cf) => write!(f, "{:?}", cf),
+            LTermInner::Nom(uid, name) => write!(f, "Nom({:?}, {:?})", uid, name),
+            LTermInner::Tie(a, t) => write!(f, "Tie({:?}, {:?})", a, t),
+            LTermInner::Susp(perm, x) => write!(f, "Susp({:?}, {:?})", perm, x),
         }
     }
 }
@@ -569,6 +715,18 @@ where
                 }
             }
             LTermInner::Compound(compound_term) => write!(f, "{:?}", compound_term),
+            LTermInner::Nom(_, name) => write!(f, "{}", name),
+            LTermInner::Tie(a, t) => write!(f, "tie({}, {})", a, t),
+            LTermInner::Susp(perm, x) => {
+                write!(f, "susp([")?;
+                for (count, (a, b)) in perm.iter().enumerate() {
+                    if count != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "({} {})", a, b)?;
+                }
+                write!(f, "], {})", x)
+            }
         }
     }
 }
@@ -590,6 +748,15 @@ where
                 tail.hash(state);
             }
             LTermInner::Compound(cf) => cf.compound_hash(state),
+            LTermInner::Nom(uid, _) => uid.hash(state),
+            LTermInner::Tie(a, t) => {
+                a.hash(state);
+                t.hash(state);
+            }
+            LTermInner::Susp(perm, x) => {
+                perm.hash(state);
+                x.hash(state);
+            }
         }
     }
 }
@@ -613,6 +780,13 @@ where
             (LTermInner::Compound(self_cf), LTermInner::Compound(other_cf)) => {
                 self_cf.compound_eq(other_cf.as_object())
             }
+            (LTermInner::Nom(self_uid, _), LTermInner::Nom(other_uid, _)) => self_uid == other_uid,
+            (LTermInner::Tie(self_a, self_t), LTermInner::Tie(other_a, other_t)) => {
+                (self_a == other_a) & (self_t == other_t)
+            }
+            (LTermInner::Susp(self_perm, self_x), LTermInner::Susp(other_perm, other_x)) => {
+                (self_perm == other_perm) & (self_x == other_x)
+            }
             _ => false,
         }
     }
@@ -696,6 +870,58 @@ where
     }
 }
 
+impl<U, E> PartialEq<f64> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &f64) -> bool {
+        match self.as_ref() {
+            LTermInner::Val(val) => val == other,
+            _ => false,
+        }
+    }
+}
+
+impl<U, E> PartialEq<LTerm<U, E>> for f64
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &LTerm<U, E>) -> bool {
+        match other.as_ref() {
+            LTermInner::Val(val) => val == self,
+            _ => false,
+        }
+    }
+}
+
+impl<U, E> PartialEq<Atom> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &Atom) -> bool {
+        match self.as_ref() {
+            LTermInner::Val(val) => val == other,
+            _ => false,
+        }
+    }
+}
+
+impl<U, E> PartialEq<LTerm<U, E>> for Atom
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &LTerm<U, E>) -> bool {
+        match other.as_ref() {
+            LTermInner::Val(val) => val == self,
+            _ => false,
+        }
+    }
+}
+
 impl<U, E> PartialEq<char> for LTerm<U, E>
 where
     U: User,
@@ -1326,6 +1552,38 @@ mod test {
         assert_eq!(u[2], false);
     }
 
+    #[test]
+    fn test_lterm_float() {
+        let u: LTerm<DefaultUser> = lterm!(2.5);
+        assert!(u.is_float());
+        assert_eq!(u.get_float(), Some(2.5));
+        assert_eq!(u.get_number(), None);
+        let v: LTerm<DefaultUser> = lterm!(2);
+        assert!(!v.is_float());
+        assert_eq!(v.get_float(), None);
+    }
+
+    #[cfg(feature = "rational")]
+    #[test]
+    fn test_lterm_rational() {
+        use num_bigint::BigInt;
+        use num_rational::BigRational;
+
+        let u: LTerm<DefaultUser> = lterm!(-1 / 3);
+        assert_eq!(
+            u.get_rational(),
+            Some(BigRational::new(BigInt::from(-1), BigInt::from(3)))
+        );
+        assert_eq!(format!("{}", u), "-1/3");
+        let v: LTerm<DefaultUser> = lterm!(3);
+        assert_eq!(
+            v.get_rational(),
+            Some(BigRational::from_integer(BigInt::from(3)))
+        );
+        let w: LTerm<DefaultUser> = lterm!(0.5);
+        assert_eq!(w.get_rational(), None);
+    }
+
     #[test]
     fn test_lterm_display() {
         assert_eq!(format!("{}", lterm!(1234) as LTerm<DefaultUser>), "1234");
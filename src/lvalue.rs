@@ -1,12 +1,60 @@
+use crate::atom::Atom;
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+#[cfg(feature = "rational")]
+use num_rational::BigRational;
+#[cfg(feature = "bigint")]
+use num_traits::ToPrimitive;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 /// Literal Logic Value
-#[derive(PartialEq, Hash, Clone)]
+///
+/// Floating point values are compared by their bit patterns, which gives them total-order
+/// equality semantics: `NaN` is equal to itself, and `0.0` and `-0.0` are different values.
+/// Rational values are written as `n/d` or `-n/d` literals in terms, such as `q == 1/2`.
+#[derive(Clone)]
 pub enum LValue {
     Bool(bool),
     Number(isize),
+    Float(f64),
     Char(char),
     String(String),
+    Atom(Atom),
+    #[cfg(feature = "bigint")]
+    BigInt(BigInt),
+    #[cfg(feature = "rational")]
+    Rational(BigRational),
+}
+
+impl LValue {
+    /// Returns the value as an arbitrary-precision integer, if it is an integer.
+    #[cfg(feature = "bigint")]
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            LValue::Number(n) => Some(BigInt::from(*n)),
+            LValue::BigInt(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a rational number, if it is an integer or a rational.
+    #[cfg(feature = "rational")]
+    pub fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            LValue::Rational(q) => Some(q.clone()),
+            _ => self.to_bigint().map(BigRational::from_integer),
+        }
+    }
+
+    /// The rational value `numer / denom`, which is a number if it is an integer.
+    ///
+    /// # Panics
+    /// Panics if `denom` is zero.
+    #[cfg(feature = "rational")]
+    pub fn rational(numer: isize, denom: isize) -> LValue {
+        LValue::from(BigRational::new(BigInt::from(numer), BigInt::from(denom)))
+    }
 }
 
 impl From<bool> for LValue {
@@ -21,12 +69,43 @@ impl From<isize> for LValue {
     }
 }
 
+impl From<f64> for LValue {
+    fn from(u: f64) -> LValue {
+        LValue::Float(u)
+    }
+}
+
 impl<T: Copy + Into<LValue>> From<&T> for LValue {
     fn from(u: &T) -> LValue {
         (*u).into()
     }
 }
 
+/// Integers within the range of `isize` are converted to numbers, so that each value has a
+/// single representation.
+#[cfg(feature = "bigint")]
+impl From<BigInt> for LValue {
+    fn from(u: BigInt) -> LValue {
+        match u.to_isize() {
+            Some(n) => LValue::Number(n),
+            None => LValue::BigInt(u),
+        }
+    }
+}
+
+/// Rationals that are integers are converted to integers, so that each value has a single
+/// representation.
+#[cfg(feature = "rational")]
+impl From<BigRational> for LValue {
+    fn from(u: BigRational) -> LValue {
+        if u.is_integer() {
+            LValue::from(u.to_integer())
+        } else {
+            LValue::Rational(u)
+        }
+    }
+}
+
 impl From<char> for LValue {
     fn from(u: char) -> LValue {
         LValue::Char(u)
@@ -45,6 +124,12 @@ impl From<String> for LValue {
     }
 }
 
+impl From<Atom> for LValue {
+    fn from(u: Atom) -> LValue {
+        LValue::Atom(u)
+    }
+}
+
 impl PartialEq<bool> for LValue {
     fn eq(&self, other: &bool) -> bool {
         match self {
@@ -81,6 +166,24 @@ impl PartialEq<LValue> for isize {
     }
 }
 
+impl PartialEq<f64> for LValue {
+    fn eq(&self, other: &f64) -> bool {
+        match self {
+            LValue::Float(x) => x.to_bits() == other.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<LValue> for f64 {
+    fn eq(&self, other: &LValue) -> bool {
+        match other {
+            LValue::Float(x) => x.to_bits() == self.to_bits(),
+            _ => false,
+        }
+    }
+}
+
 impl PartialEq<char> for LValue {
     fn eq(&self, other: &char) -> bool {
         match self {
@@ -153,8 +256,62 @@ impl PartialEq<LValue> for &str {
     }
 }
 
+impl PartialEq<Atom> for LValue {
+    fn eq(&self, other: &Atom) -> bool {
+        match self {
+            LValue::Atom(x) => x == other,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<LValue> for Atom {
+    fn eq(&self, other: &LValue) -> bool {
+        match other {
+            LValue::Atom(x) => x == self,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq for LValue {
+    fn eq(&self, other: &LValue) -> bool {
+        match (self, other) {
+            (LValue::Bool(x), LValue::Bool(y)) => x == y,
+            (LValue::Number(x), LValue::Number(y)) => x == y,
+            (LValue::Float(x), LValue::Float(y)) => x.to_bits() == y.to_bits(),
+            (LValue::Char(x), LValue::Char(y)) => x == y,
+            (LValue::String(x), LValue::String(y)) => x == y,
+            (LValue::Atom(x), LValue::Atom(y)) => x == y,
+            #[cfg(feature = "bigint")]
+            (LValue::BigInt(x), LValue::BigInt(y)) => x == y,
+            #[cfg(feature = "rational")]
+            (LValue::Rational(x), LValue::Rational(y)) => x == y,
+            _ => false,
+        }
+    }
+}
+
 impl Eq for LValue {}
 
+impl Hash for LValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            LValue::Bool(val) => val.hash(state),
+            LValue::Number(val) => val.hash(state),
+            LValue::Float(val) => val.to_bits().hash(state),
+            LValue::Char(val) => val.hash(state),
+            LValue::String(val) => val.hash(state),
+            LValue::Atom(val) => val.hash(state),
+            #[cfg(feature = "bigint")]
+            LValue::BigInt(val) => val.hash(state),
+            #[cfg(feature = "rational")]
+            LValue::Rational(val) => val.hash(state),
+        }
+    }
+}
+
 // The custom formatter prints values without the enum member specifiers
 // i.e instead of String("foo") we get just "foo"
 impl fmt::Debug for LValue {
@@ -162,8 +319,14 @@ impl fmt::Debug for LValue {
         match self {
             LValue::Bool(val) => write!(f, "{:?}", val),
             LValue::Number(val) => write!(f, "{:?}", val),
+            LValue::Float(val) => write!(f, "{:?}", val),
             LValue::Char(val) => write!(f, "{:?}", val),
             LValue::String(val) => write!(f, "{:?}", val),
+            LValue::Atom(val) => write!(f, "{:?}", val),
+            #[cfg(feature = "bigint")]
+            LValue::BigInt(val) => write!(f, "{}", val),
+            #[cfg(feature = "rational")]
+            LValue::Rational(val) => write!(f, "{}", val),
         }
     }
 }
@@ -173,8 +336,14 @@ impl fmt::Display for LValue {
         match self {
             LValue::Bool(val) => write!(f, "{}", val),
             LValue::Number(val) => write!(f, "{}", val),
+            LValue::Float(val) => write!(f, "{:?}", val),
             LValue::Char(val) => write!(f, "'{}'", val),
             LValue::String(val) => write!(f, "\"{}\"", val),
+            LValue::Atom(val) => write!(f, "{}", val),
+            #[cfg(feature = "bigint")]
+            LValue::BigInt(val) => write!(f, "{}", val),
+            #[cfg(feature = "rational")]
+            LValue::Rational(val) => write!(f, "{}", val),
         }
     }
 }
@@ -290,12 +459,90 @@ mod test {
         assert!(u != v);
     }
 
+    #[test]
+    fn test_lvalue_float() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let u = LValue::from(1.5);
+        assert!(u == 1.5);
+        assert!(1.5 == u);
+        assert!(u != 1);
+        assert!(1 != u);
+        assert!(u != "1.5");
+
+        // Whole floats are not numbers.
+        let v = LValue::from(&1.0);
+        assert!(v != 1);
+        assert!(v != LValue::from(1));
+
+        // NaN is equal to itself, and negative zero is distinct from zero.
+        let nan = LValue::from(f64::NAN);
+        assert!(nan == nan.clone());
+        assert!(LValue::from(0.0) != LValue::from(-0.0));
+
+        let hash = |u: &LValue| {
+            let mut hasher = DefaultHasher::new();
+            u.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&nan), hash(&LValue::from(f64::NAN)));
+        assert_eq!(hash(&u), hash(&LValue::from(1.5)));
+    }
+
+    #[test]
+    fn test_lvalue_atom() {
+        let u = LValue::from(Atom::from("red"));
+        assert!(u == Atom::from("red"));
+        assert!(Atom::from("red") == u);
+        assert!(u != Atom::from("green"));
+        assert!(u != "red");
+        assert!("red" != u);
+        assert_eq!(format!("{} {:?}", u, u), "red 'red");
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_lvalue_bigint() {
+        let u = LValue::from(BigInt::from(isize::MAX) + 1);
+        assert!(u != isize::MAX);
+        assert_eq!(format!("{}", u), "9223372036854775808");
+        assert_eq!(u.to_bigint(), Some(BigInt::from(isize::MAX) + 1));
+
+        // Integers within the range of isize are numbers.
+        let v = LValue::from(BigInt::from(-5));
+        assert!(v == -5);
+        assert_eq!(v.to_bigint(), Some(BigInt::from(-5)));
+    }
+
+    #[cfg(feature = "rational")]
+    #[test]
+    fn test_lvalue_rational() {
+        let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+        let u = LValue::from(half.clone());
+        assert!(u == LValue::Rational(half));
+        assert_eq!(format!("{}", u), "1/2");
+
+        // Integral rationals are numbers.
+        let two = BigRational::new(BigInt::from(4), BigInt::from(2));
+        assert!(LValue::from(two) == 2);
+
+        assert!(LValue::rational(-3, 6) == LValue::from(-BigRational::new(1.into(), 2.into())));
+        assert!(LValue::rational(6, 3) == 2);
+        assert_eq!(
+            LValue::from(2).to_rational(),
+            Some(BigRational::from_integer(BigInt::from(2)))
+        );
+        assert_eq!(LValue::from(1.5).to_rational(), None);
+    }
+
     #[test]
     fn test_lvalue_display() {
         assert_eq!(format!("{}", LValue::from(true)), "true");
         assert_eq!(format!("{}", LValue::from(false)), "false");
         assert_eq!(format!("{}", LValue::from(1234)), "1234");
         assert_eq!(format!("{}", LValue::from(-1234)), "-1234");
+        assert_eq!(format!("{}", LValue::from(1.5)), "1.5");
+        assert_eq!(format!("{}", LValue::from(-2.0)), "-2.0");
         assert_eq!(format!("{}", LValue::from('a')), "'a'");
         assert_eq!(
             format!("{}", LValue::from("Hello, world!")),
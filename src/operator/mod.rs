@@ -31,6 +31,10 @@
 //! Even though the structs are identical, the first goal on each arm of
 //! `PatternMatchOperatorParam` is the pattern and the match-term equality.
 //!
+//! Operators can also take term arguments, written as `operator(arg0, arg1) { <body> }`. The
+//! arguments are passed to the operator function before the `OperatorParam`, for example
+//! `pub fn minimize<U, E>(cost: LTerm<U, E>, param: OperatorParam<U, E, Goal<U, E>>)`.
+//!
 //! For example `onceo` can be implemented as:
 //! ```rust
 //! extern crate proto_vulcan;
@@ -238,6 +242,10 @@ pub mod matchu;
 #[doc(hidden)]
 pub mod onceo;
 
+#[cfg(feature = "clpfd")]
+#[doc(hidden)]
+pub mod optimize;
+
 #[cfg(feature = "core")]
 #[doc(hidden)]
 pub mod project;
@@ -274,6 +282,14 @@ pub use onceo::onceo;
 #[doc(inline)]
 pub use matche::matche;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use optimize::maximize;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use optimize::minimize;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use matchu::matchu;
@@ -0,0 +1,375 @@
+//! # Branch-and-bound optimization
+//!
+//! `minimize(cost) { <body> }` and `maximize(cost) { <body> }` search for solutions of the
+//! body goals, and return each solution that improves the cost of the previously returned
+//! solution. The last returned solution is the optimum.
+//!
+//! The cost is a finite domain variable. Whenever a solution is found, the remaining search
+//! is constrained to solutions that are strictly better than it, which prunes the branches
+//! that cannot improve the cost. If the cost is not bound by the body goals, it is labeled in
+//! the direction of the optimization; other finite domain variables are labeled in ascending
+//! order before the solution is accepted.
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, DFSGoal, Goal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::operator::conj::Conj;
+use crate::operator::OperatorParam;
+use crate::relation::clpfd::labeling::{labeling, LabelingOption};
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Objective {
+    Minimize,
+    Maximize,
+}
+
+impl Objective {
+    /// Returns true if `cost` is strictly better than the current `bound`.
+    fn improves(self, cost: isize, bound: Option<isize>) -> bool {
+        match (self, bound) {
+            (_, None) => true,
+            (Objective::Minimize, Some(bound)) => cost < bound,
+            (Objective::Maximize, Some(bound)) => cost > bound,
+        }
+    }
+
+    /// Domain of costs that are strictly better than `bound`, or `None` if there are none.
+    fn improving_domain(self, bound: isize) -> Option<FiniteDomain> {
+        match self {
+            Objective::Minimize => bound
+                .checked_sub(1)
+                .map(|max| FiniteDomain::from(isize::MIN..=max)),
+            Objective::Maximize => bound
+                .checked_add(1)
+                .map(|min| FiniteDomain::from(min..=isize::MAX)),
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Optimize<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    objective: Objective,
+    cost: LTerm<U, E>,
+    goal: Goal<U, E>,
+}
+
+impl<U, E> Optimize<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn new(objective: Objective, cost: LTerm<U, E>, goal: Goal<U, E>) -> Goal<U, E> {
+        Goal::dynamic(Rc::new(Optimize {
+            objective,
+            cost,
+            goal,
+        }))
+    }
+}
+
+impl<U, E> Solve<U, E> for Optimize<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        // The bound is shared by all branches of the search.
+        let bound = Rc::new(Cell::new(None));
+
+        let c = CostBoundConstraint::new(self.objective, self.cost.clone(), Rc::clone(&bound));
+        let state = match c.run(state) {
+            Ok(state) => state,
+            Err(_) => return Stream::empty(),
+        };
+
+        let goal = self.goal.clone();
+        let cost = self.cost.clone();
+        let order = match self.objective {
+            Objective::Minimize => LabelingOption::Up,
+            Objective::Maximize => LabelingOption::Down,
+        };
+        // A solution is only complete when all domain variables have values; otherwise the
+        // bound could be tightened by a partial solution that fails later.
+        let g: Goal<U, E> = proto_vulcan!([
+            goal,
+            labeling(&[order], cost),
+            fngoal move |solver, state| {
+                let vars = state.dstore_ref().keys().cloned().collect::<LTerm<U, E>>();
+                let g: Goal<U, E> = proto_vulcan!(labeling(&[], vars));
+                g.solve(solver, state)
+            },
+        ]);
+
+        // Solutions are checked against the bound in the order they are found.
+        let improve = ImproveBound {
+            objective: self.objective,
+            cost: self.cost.clone(),
+            bound,
+        };
+        Stream::bind_dfs(g.solve(solver, state), DFSGoal::dynamic(Rc::new(improve)))
+    }
+}
+
+/// Passes through solutions that improve the bound, and tightens the bound.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+struct ImproveBound<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    objective: Objective,
+    cost: LTerm<U, E>,
+    bound: Rc<Cell<Option<isize>>>,
+}
+
+impl<U, E> Solve<U, E> for ImproveBound<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.smap_ref().walk(&self.cost).get_number() {
+            Some(cost) if self.objective.improves(cost, self.bound.get()) => {
+                self.bound.set(Some(cost));
+                Stream::unit(Box::new(state))
+            }
+            _ => Stream::empty(),
+        }
+    }
+}
+
+/// Constrains the cost to be strictly better than the best solution found so far.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct CostBoundConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    objective: Objective,
+    cost: LTerm<U, E>,
+    bound: Rc<Cell<Option<isize>>>,
+}
+
+impl<U, E> CostBoundConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn new(
+        objective: Objective,
+        cost: LTerm<U, E>,
+        bound: Rc<Cell<Option<isize>>>,
+    ) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(CostBoundConstraint {
+            objective,
+            cost,
+            bound,
+        })
+    }
+}
+
+impl<U, E> Constraint<U, E> for CostBoundConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let bound = match self.bound.get() {
+            Some(bound) => bound,
+            None => return Ok(state.with_constraint(self)),
+        };
+
+        let costwalk = state.smap_ref().walk(&self.cost).clone();
+        match costwalk.as_ref() {
+            LTermInner::Var(_, _) => {
+                if state.dstore_ref().contains_key(&costwalk) {
+                    let domain = self.objective.improving_domain(bound).ok_or(())?;
+                    Ok(state
+                        .process_domain(&costwalk, Rc::new(domain))?
+                        .with_constraint(self))
+                } else {
+                    Ok(state.with_constraint(self))
+                }
+            }
+            LTermInner::Val(LValue::Number(cost))
+                if self.objective.improves(*cost, Some(bound)) =>
+            {
+                Ok(state.with_constraint(self))
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.cost.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for CostBoundConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+/// Minimize operator
+///
+/// Returns successively better solutions to the body goals, such that the last returned
+/// solution has the smallest `cost`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::operator::minimize;
+/// use proto_vulcan::relation::{infdrange, plusfd, timesfd};
+/// fn main() {
+///     // Minimize x^2 - 4x with x + y == 6
+///     let query = proto_vulcan_query!(|x, cost| {
+///         |y, xx, x4| {
+///             minimize(cost) {
+///                 infdrange([x, y], &(0..=6)),
+///                 infdrange([xx, x4], &(0..=36)),
+///                 infdrange(cost, &(-4..=36)),
+///                 plusfd(x, y, 6),
+///                 timesfd(x, x, xx),
+///                 timesfd(4, x, x4),
+///                 plusfd(cost, x4, xx),
+///             }
+///         }
+///     });
+///     let last = query.run().last().unwrap();
+///     assert_eq!(last.x, 2);
+///     assert_eq!(last.cost, -4);
+/// }
+/// ```
+pub fn minimize<U, E>(cost: LTerm<U, E>, param: OperatorParam<U, E, Goal<U, E>>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    Optimize::new(
+        Objective::Minimize,
+        cost,
+        Conj::from_conjunctions(param.body),
+    )
+}
+
+/// Maximize operator
+///
+/// Returns successively better solutions to the body goals, such that the last returned
+/// solution has the largest `cost`.
+pub fn maximize<U, E>(cost: LTerm<U, E>, param: OperatorParam<U, E, Goal<U, E>>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    Optimize::new(
+        Objective::Maximize,
+        cost,
+        Conj::from_conjunctions(param.body),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{maximize, minimize};
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::{infd, infdrange};
+    use crate::relation::clpfd::ltfd::ltfd;
+    use crate::relation::clpfd::plusfd::plusfd;
+
+    #[test]
+    fn test_minimize_1() {
+        let query = proto_vulcan_query!(|x| {
+            minimize(x) {
+                infd(x, &[7, 3, 5, 9]),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_minimize_2() {
+        // Solutions are found in the order of conde, and only improving ones are returned.
+        let query = proto_vulcan_query!(|x, cost| {
+            minimize(cost) {
+                infdrange([x, cost], &(0..=100)),
+                conde {
+                    x == 10,
+                    x == 20,
+                    x == 5,
+                    x == 30,
+                    x == 1,
+                },
+                plusfd(x, 2, cost),
+            }
+        });
+        let costs = query
+            .run()
+            .map(|r| r.cost.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert!(costs.windows(2).all(|w| w[1] < w[0]));
+        assert_eq!(costs.last(), Some(&3));
+    }
+
+    #[test]
+    fn test_maximize_1() {
+        let query = proto_vulcan_query!(|x, y, cost| {
+            maximize(cost) {
+                infdrange([x, y], &(0..=5)),
+                infdrange(cost, &(0..=10)),
+                ltfd(x, y),
+                plusfd(x, y, cost),
+            }
+        });
+        let last = query.run().last().unwrap();
+        assert_eq!(last.x, 4);
+        assert_eq!(last.y, 5);
+        assert_eq!(last.cost, 9);
+    }
+
+    #[test]
+    fn test_maximize_2() {
+        let query = proto_vulcan_query!(|x| {
+            maximize(x) {
+                infdrange(x, &(0..=3)),
+                x != 3,
+            }
+        });
+        let results = query
+            .run()
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(results, vec![2]);
+    }
+
+    #[test]
+    fn test_minimize_fail_1() {
+        let query = proto_vulcan_query!(|x| {
+            minimize(x) {
+                infdrange(x, &(0..=3)),
+                false,
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+}
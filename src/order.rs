@@ -0,0 +1,492 @@
+//! # Standard order of terms
+//! A total order over terms, in the spirit of the standard order of terms in Prolog. Terms
+//! of different kinds are ordered by their kind:
+//!
+//! Variables < Numbers < Booleans < Characters < Atoms < Strings < Nominal atoms < Lists <
+//! Binders < Compound objects < User terms
+//!
+//! * Variables are ordered by their age, the oldest first.
+//! * Numbers are ordered by their value. Numbers of different types with the same value are
+//!   ordered by their type: `Float < Number < BigInt < Rational`. Floats are ordered by
+//!   their bits, such that `-NaN < -inf < -0.0 < 0.0 < inf < NaN`.
+//! * Atoms are ordered alphabetically by their names.
+//! * The empty list is the first list, and non-empty lists are ordered by their first
+//!   elements and then by their tails.
+//! * Compound objects are ordered by their type names, then by the number of their fields,
+//!   and then by their fields from left to right.
+//!
+//! Two terms are equal in the standard order when they are identical.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use std::cmp::Ordering;
+//! fn main() {
+//!     let mut terms: Vec<LTerm> = vec![
+//!         lterm!([1, 2]),
+//!         lterm!('b),
+//!         lterm!("a"),
+//!         lterm!(2.5),
+//!         lterm!(1),
+//!     ];
+//!     terms.sort_by(LTerm::standard_cmp);
+//!     assert_eq!(terms, vec![lterm!(1), lterm!(2.5), lterm!('b), lterm!("a"), lterm!([1, 2])]);
+//!     let one: LTerm = lterm!(1);
+//!     assert_eq!(one.standard_cmp(&lterm!(1.0)), Ordering::Greater);
+//! }
+//! ```
+use crate::compound::CompoundObject;
+use crate::engine::Engine;
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::state::SMap;
+use crate::user::User;
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+#[cfg(feature = "rational")]
+use num_rational::BigRational;
+#[cfg(feature = "bigint")]
+use num_traits::FromPrimitive;
+use std::cmp::Ordering;
+
+impl<U, E> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Compares the terms in the standard order of terms.
+    pub fn standard_cmp(&self, other: &LTerm<U, E>) -> Ordering {
+        compare_terms(None, self, other).unwrap()
+    }
+}
+
+/// Term ordered by the standard order of terms
+#[derive(Derivative)]
+#[derivative(Clone(bound = "U: User"), Debug(bound = "U: User"))]
+pub struct StandardOrder<U, E>(pub LTerm<U, E>)
+where
+    U: User,
+    E: Engine<U>;
+
+impl<U, E> PartialEq for StandardOrder<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0.standard_cmp(&other.0) == Ordering::Equal
+    }
+}
+
+impl<U, E> Eq for StandardOrder<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+}
+
+impl<U, E> PartialOrd for StandardOrder<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<U, E> Ord for StandardOrder<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.standard_cmp(&other.0)
+    }
+}
+
+/// Compares the terms in the standard order of terms under the substitution, or returns
+/// `None` if the order depends on a variable that is not bound yet.
+pub(crate) fn decided_cmp<U, E>(
+    smap: &SMap<U, E>,
+    a: &LTerm<U, E>,
+    b: &LTerm<U, E>,
+) -> Option<Ordering>
+where
+    U: User,
+    E: Engine<U>,
+{
+    compare_terms(Some(smap), a, b)
+}
+
+fn walk<'a, U, E>(smap: Option<&'a SMap<U, E>>, t: &'a LTerm<U, E>) -> &'a LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match smap {
+        Some(smap) => smap.walk(t),
+        None => t,
+    }
+}
+
+fn kind_rank<U, E>(t: &LTerm<U, E>) -> u8
+where
+    U: User,
+    E: Engine<U>,
+{
+    match t.as_ref() {
+        LTermInner::Var(_, _) | LTermInner::Susp(_, _) => 0,
+        LTermInner::Val(LValue::Bool(_)) => 2,
+        LTermInner::Val(LValue::Char(_)) => 3,
+        LTermInner::Val(LValue::Atom(_)) => 4,
+        LTermInner::Val(LValue::String(_)) => 5,
+        LTermInner::Val(_) => 1,
+        LTermInner::Nom(_, _) => 6,
+        LTermInner::Empty | LTermInner::Cons(_, _) => 7,
+        LTermInner::Tie(_, _) => 8,
+        LTermInner::Compound(_) => 9,
+        LTermInner::User(_) => 10,
+        LTermInner::Projection(p) => kind_rank(p),
+    }
+}
+
+/// Compares the terms. With a substitution, the variables are walked, and the comparison of
+/// distinct variables is undecided; without one, variables are compared by their age.
+fn compare_terms<U, E>(
+    smap: Option<&SMap<U, E>>,
+    a: &LTerm<U, E>,
+    b: &LTerm<U, E>,
+) -> Option<Ordering>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut a = walk(smap, a);
+    let mut b = walk(smap, b);
+    loop {
+        if let LTermInner::Projection(p) = a.as_ref() {
+            a = walk(smap, p);
+            continue;
+        }
+        if let LTermInner::Projection(p) = b.as_ref() {
+            b = walk(smap, p);
+            continue;
+        }
+
+        let (ra, rb) = (kind_rank(a), kind_rank(b));
+        if ra == 0 || rb == 0 {
+            return compare_vars(smap.is_some(), a, b);
+        }
+        if ra != rb {
+            return Some(ra.cmp(&rb));
+        }
+
+        match (a.as_ref(), b.as_ref()) {
+            (LTermInner::Val(x), LTermInner::Val(y)) => return Some(compare_values(x, y)),
+            (LTermInner::Nom(x, _), LTermInner::Nom(y, _)) => return Some(x.cmp(y)),
+            (LTermInner::Empty, LTermInner::Empty) => return Some(Ordering::Equal),
+            (LTermInner::Empty, LTermInner::Cons(_, _)) => return Some(Ordering::Less),
+            (LTermInner::Cons(_, _), LTermInner::Empty) => return Some(Ordering::Greater),
+            (LTermInner::Cons(ha, ta), LTermInner::Cons(hb, tb)) => {
+                match compare_terms(smap, ha, hb)? {
+                    Ordering::Equal => {
+                        a = walk(smap, ta);
+                        b = walk(smap, tb);
+                    }
+                    ordering => return Some(ordering),
+                }
+            }
+            (LTermInner::Tie(na, ba), LTermInner::Tie(nb, bb)) => {
+                match compare_terms(smap, na, nb)? {
+                    Ordering::Equal => {
+                        a = walk(smap, ba);
+                        b = walk(smap, bb);
+                    }
+                    ordering => return Some(ordering),
+                }
+            }
+            (LTermInner::Compound(x), LTermInner::Compound(y)) => {
+                return compare_compounds(smap, x.as_ref(), y.as_ref())
+            }
+            _ => return Some(Ordering::Equal),
+        }
+    }
+}
+
+fn compare_vars<U, E>(decided: bool, a: &LTerm<U, E>, b: &LTerm<U, E>) -> Option<Ordering>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let var_id = |t: &LTerm<U, E>| match t.as_ref() {
+        LTermInner::Var(id, _) => Some(*id),
+        LTermInner::Susp(_, x) => match x.as_ref() {
+            LTermInner::Var(id, _) => Some(*id),
+            _ => None,
+        },
+        _ => None,
+    };
+    match (var_id(a), var_id(b)) {
+        (Some(x), Some(y)) if x == y && a == b => Some(Ordering::Equal),
+        _ if decided => None,
+        (Some(x), Some(y)) => Some(x.cmp(&y)),
+        (Some(_), None) => Some(Ordering::Less),
+        (None, Some(_)) => Some(Ordering::Greater),
+        (None, None) => Some(Ordering::Equal),
+    }
+}
+
+fn compare_compounds<U, E>(
+    smap: Option<&SMap<U, E>>,
+    a: &dyn CompoundObject<U, E>,
+    b: &dyn CompoundObject<U, E>,
+) -> Option<Ordering>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let ordering = a
+        .type_name()
+        .cmp(b.type_name())
+        .then(a.children().count().cmp(&b.children().count()));
+    if ordering != Ordering::Equal {
+        return Some(ordering);
+    }
+    for (x, y) in a.children().zip(b.children()) {
+        let ordering = match (x.as_term(), y.as_term()) {
+            (Some(x), Some(y)) => compare_terms(smap, x, y)?,
+            (Some(x), None) => compare_term_compound(smap, x, y)?,
+            (None, Some(y)) => compare_term_compound(smap, y, x)?.reverse(),
+            (None, None) => compare_compounds(smap, x, y)?,
+        };
+        if ordering != Ordering::Equal {
+            return Some(ordering);
+        }
+    }
+    Some(Ordering::Equal)
+}
+
+/// Compares a term field to a compound object field.
+fn compare_term_compound<U, E>(
+    smap: Option<&SMap<U, E>>,
+    x: &LTerm<U, E>,
+    y: &dyn CompoundObject<U, E>,
+) -> Option<Ordering>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let x = walk(smap, x);
+    match x.as_ref() {
+        LTermInner::Compound(x) => compare_compounds(smap, x.as_ref(), y),
+        _ if smap.is_some() && kind_rank(x) == 0 => None,
+        _ => Some(kind_rank(x).cmp(&9)),
+    }
+}
+
+/// Key that orders floats totally by their bits
+fn float_key(f: f64) -> u64 {
+    let bits = f.to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | (1 << 63)
+    }
+}
+
+fn type_rank(v: &LValue) -> u8 {
+    match v {
+        LValue::Float(_) => 0,
+        LValue::Number(_) => 1,
+        #[cfg(feature = "bigint")]
+        LValue::BigInt(_) => 2,
+        #[cfg(feature = "rational")]
+        LValue::Rational(_) => 3,
+        _ => 4,
+    }
+}
+
+fn compare_values(x: &LValue, y: &LValue) -> Ordering {
+    match (x, y) {
+        (LValue::Bool(x), LValue::Bool(y)) => x.cmp(y),
+        (LValue::Char(x), LValue::Char(y)) => x.cmp(y),
+        (LValue::Atom(x), LValue::Atom(y)) => x.as_str().cmp(y.as_str()),
+        (LValue::String(x), LValue::String(y)) => x.cmp(y),
+        (LValue::Float(x), LValue::Float(y)) => float_key(*x).cmp(&float_key(*y)),
+        (LValue::Float(x), y) => compare_float(*x, y).then(Ordering::Less),
+        (x, LValue::Float(y)) => compare_float(*y, x).reverse().then(Ordering::Greater),
+        (x, y) => compare_exact(x, y).then(type_rank(x).cmp(&type_rank(y))),
+    }
+}
+
+/// Compares the values of integers and rationals.
+fn compare_exact(x: &LValue, y: &LValue) -> Ordering {
+    match (x, y) {
+        (LValue::Number(x), LValue::Number(y)) => x.cmp(y),
+        #[cfg(feature = "rational")]
+        (LValue::Rational(_), _) | (_, LValue::Rational(_)) => {
+            let to_rational = |v: &LValue| match v {
+                LValue::Rational(r) => r.clone(),
+                v => BigRational::from_integer(v.to_bigint().unwrap()),
+            };
+            to_rational(x).cmp(&to_rational(y))
+        }
+        #[cfg(feature = "bigint")]
+        (x, y) => x.to_bigint().unwrap().cmp(&y.to_bigint().unwrap()),
+        #[cfg(not(feature = "bigint"))]
+        _ => Ordering::Equal,
+    }
+}
+
+/// Compares the value of a float to the value of an integer or a rational.
+fn compare_float(f: f64, v: &LValue) -> Ordering {
+    if f.is_nan() {
+        return if f.is_sign_negative() {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        };
+    }
+    if f.is_infinite() {
+        return if f < 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        };
+    }
+    match v {
+        LValue::Number(n) => {
+            // Finite floats of at least 2^127 in magnitude are outside the range of i128.
+            if f.abs() >= 2f64.powi(127) {
+                return if f < 0.0 {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+            }
+            let floor = f.floor();
+            match (floor as i128).cmp(&(*n as i128)) {
+                Ordering::Equal if f > floor => Ordering::Greater,
+                ordering => ordering,
+            }
+        }
+        #[cfg(feature = "rational")]
+        LValue::Rational(r) => BigRational::from_float(f).unwrap().cmp(r),
+        #[cfg(feature = "bigint")]
+        LValue::BigInt(n) => {
+            let floor = f.floor();
+            match BigInt::from_f64(floor).unwrap().cmp(n) {
+                Ordering::Equal if f > floor => Ordering::Greater,
+                ordering => ordering,
+            }
+        }
+        _ => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StandardOrder;
+    use crate::lterm::LTerm;
+    use crate::prelude::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_standard_order_kinds() {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let terms: Vec<LTerm> = vec![
+            x.clone(),
+            y.clone(),
+            lterm!(-3),
+            lterm!(1.5),
+            lterm!(2),
+            lterm!(false),
+            lterm!(true),
+            lterm!('c'),
+            lterm!('apple),
+            lterm!('banana),
+            lterm!("apple"),
+            lterm!([]),
+            lterm!([1]),
+            lterm!([1, 2]),
+            lterm!([2]),
+        ];
+        for (i, a) in terms.iter().enumerate() {
+            for (j, b) in terms.iter().enumerate() {
+                assert_eq!(a.standard_cmp(b), i.cmp(&j), "{} {}", a, b);
+            }
+        }
+
+        let mut sorted = terms
+            .iter()
+            .rev()
+            .cloned()
+            .map(StandardOrder)
+            .collect::<Vec<_>>();
+        sorted.sort();
+        assert_eq!(
+            sorted.into_iter().map(|t| t.0).collect::<Vec<LTerm>>(),
+            terms
+        );
+    }
+
+    #[test]
+    fn test_standard_order_numbers() {
+        let cmp = |a: LTerm, b: LTerm| a.standard_cmp(&b);
+        assert_eq!(cmp(lterm!(1.0), lterm!(1)), Ordering::Less);
+        assert_eq!(cmp(lterm!(1.5), lterm!(1)), Ordering::Greater);
+        assert_eq!(cmp(lterm!(-1.5), lterm!(-1)), Ordering::Less);
+        assert_eq!(cmp(lterm!(-0.0), lterm!(0.0)), Ordering::Less);
+        assert_eq!(cmp(lterm!(0.0), lterm!(0.0)), Ordering::Equal);
+        assert_eq!(cmp(LTerm::from(f64::NAN), lterm!(1)), Ordering::Greater);
+        assert_eq!(
+            cmp(LTerm::from(f64::NAN), LTerm::from(f64::NAN)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            cmp(LTerm::from(f64::INFINITY), LTerm::from(isize::MAX)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            cmp(LTerm::from(1e300), LTerm::from(isize::MAX)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            cmp(LTerm::from(-1e300), LTerm::from(isize::MIN)),
+            Ordering::Less
+        );
+        assert_eq!(
+            cmp(LTerm::from(isize::MAX), LTerm::from(isize::MAX - 1)),
+            Ordering::Greater
+        );
+    }
+
+    #[cfg(feature = "rational")]
+    #[test]
+    fn test_standard_order_bignum() {
+        use num_bigint::BigInt;
+        use num_rational::BigRational;
+
+        let big = |n: isize| -> LTerm { LTerm::from(BigInt::from(n)) };
+        let ratio =
+            |n: isize, d: isize| -> LTerm { LTerm::from(BigRational::new(n.into(), d.into())) };
+        let huge = || -> LTerm { LTerm::from(BigInt::from(isize::MAX) + 1) };
+        // Integers are normalized, so a small bigint is the same term as a number
+        assert_eq!(big(2).standard_cmp(&LTerm::from(2)), Ordering::Equal);
+        assert_eq!(
+            huge().standard_cmp(&LTerm::from(isize::MAX)),
+            Ordering::Greater
+        );
+        assert_eq!(LTerm::from(-1e300).standard_cmp(&huge()), Ordering::Less);
+        assert_eq!(ratio(1, 2).standard_cmp(&LTerm::from(1)), Ordering::Less);
+        assert_eq!(
+            ratio(3, 2).standard_cmp(&LTerm::from(1.25)),
+            Ordering::Greater
+        );
+        assert_eq!(ratio(5, 2).standard_cmp(&big(2)), Ordering::Greater);
+        assert_eq!(ratio(4, 2).standard_cmp(&big(2)), Ordering::Equal);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::compound::CompoundObject;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Absento<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    tag: LTerm<U, E>,
+    t: LTerm<U, E>,
+}
+
+impl<U, E> Absento<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(tag: LTerm<U, E>, t: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Absento { tag, t })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Absento<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match AbsentoConstraint::new(self.tag.clone(), self.t.clone()).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// A relation that succeeds when `tag` does not occur anywhere in the term `t`.
+///
+/// The constraint is kept for the variables of `t`, and it is checked again when they are
+/// bound. The `tag` is usually a ground atom, such as a string that names a closure tag in a
+/// relational interpreter.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::absento;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         |x| {
+///             absento("closure", q),
+///             q == [1, [2, x]],
+///             conde {
+///                 x == "closure",
+///                 x == "lambda",
+///             },
+///         }
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, [2, "lambda"]]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn absento<U, E, G>(tag: LTerm<U, E>, t: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Absento::new(tag, t)
+}
+
+/// Absento constraint
+///
+/// A term that is not a variable is disequal to the tag, and the constraint is applied to the
+/// parts of lists and compound terms. A variable keeps the constraint in the store.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct AbsentoConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    tag: LTerm<U, E>,
+    t: LTerm<U, E>,
+}
+
+impl<U, E> AbsentoConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(tag: LTerm<U, E>, t: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(AbsentoConstraint { tag, t })
+    }
+
+    pub fn tag(&self) -> &LTerm<U, E> {
+        &self.tag
+    }
+
+    pub fn t(&self) -> &LTerm<U, E> {
+        &self.t
+    }
+
+    fn run_compound(
+        &self,
+        mut state: State<U, E>,
+        compound: &dyn CompoundObject<U, E>,
+    ) -> SResult<U, E> {
+        for child in compound.children() {
+            state = match child.as_term() {
+                Some(t) => AbsentoConstraint::new(self.tag.clone(), t.clone()).run(state)?,
+                None => self.run_compound(state, child)?,
+            };
+        }
+        Ok(state)
+    }
+}
+
+impl<U, E> Constraint<U, E> for AbsentoConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let twalk = state.smap_ref().walk(&self.t).clone();
+        if twalk.is_var() {
+            return Ok(state.with_constraint(AbsentoConstraint::new(self.tag.clone(), twalk)));
+        }
+
+        let state = state.disunify(&self.tag, &twalk)?;
+        match twalk.as_ref() {
+            LTermInner::Cons(head, tail) => {
+                let state = AbsentoConstraint::new(self.tag.clone(), head.clone()).run(state)?;
+                AbsentoConstraint::new(self.tag.clone(), tail.clone()).run(state)
+            }
+            LTermInner::Compound(compound) => self.run_compound(state, compound.as_ref()),
+            _ => Ok(state),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.tag.clone(), self.t.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(AbsentoConstraint::new(
+            smap.walk_star(&self.tag),
+            smap.walk_star(&self.t),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for AbsentoConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "absento({}, {})", self.tag, self.t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::absento;
+    use crate::prelude::*;
+    use crate::relation::typeo::numbero;
+
+    #[test]
+    fn test_absento_1() {
+        let query = proto_vulcan_query!(|q| {
+            absento(5, [1, [2, 3], 4]),
+        });
+        assert_eq!(query.run().count(), 1);
+
+        let query = proto_vulcan_query!(|q| {
+            absento(5, [1, [2, 5], 4]),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_absento_2() {
+        // The constraint applies to the parts of the term as they are bound.
+        let query = proto_vulcan_query!(|q| {
+            |x, y| {
+                absento("tag", q),
+                q == [x | y],
+                y == [1, "tag"],
+            }
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            |x, y| {
+                absento("tag", q),
+                q == [x | y],
+                y == [],
+            }
+        });
+        let result = query.run().next().unwrap();
+        let x = match result.q.as_ref() {
+            crate::lterm::LTermInner::Cons(x, _) => x.clone(),
+            _ => panic!(),
+        };
+        assert_eq!(
+            format!("{}", result.q),
+            format!("[{0}]  where  {{ absento(\"tag\", {0}) }}", x)
+        );
+    }
+
+    #[test]
+    fn test_absento_3() {
+        // A type constraint makes the absento constraint of a different type redundant.
+        let query = proto_vulcan_query!(|x| {
+            absento("tag", x),
+            numbero(x),
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(
+            format!("{}", result.x),
+            format!("{0}  where  {{ numbero({0}) }}", *result.x)
+        );
+
+        let query = proto_vulcan_query!(|x| {
+            absento(5, x),
+            x != 5,
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(
+            format!("{}", result.x),
+            format!("{0}  where  {{ absento(5, {0}) }}", *result.x)
+        );
+    }
+}
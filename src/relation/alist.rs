@@ -0,0 +1,219 @@
+//! # Association lists
+//! Relations over association lists, which are lists of two-element lists `[key, value]`.
+//! A binding shadows the bindings of the same key later in the list. The keys are compared
+//! with equality and disequality constraints, such that the relations are correct also when
+//! the keys are not known: a lookup that skips a binding constrains the looked up key to be
+//! different from the key of the binding.
+//!
+//! * `lookupo(key, alist, value)`: `value` is the value of the first binding of `key`
+//! * `extendo(key, value, alist, out)`: `out` is `alist` with a binding of `key` to `value`
+//!   that shadows the earlier bindings of `key`
+//! * `removeo(key, alist, out)`: `out` is `alist` without the bindings of `key`
+//! * `keyso(alist, keys)`: `keys` are the keys that are bound in `alist`, in order and without
+//!   the shadowed duplicates
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::lookupo;
+//! fn main() {
+//!     let query = proto_vulcan_query!(|k, v| {
+//!         lookupo(k, [[1, 'a], [2, 'b], [1, 'c]], v)
+//!     });
+//!     let mut iter = query.run();
+//!     let result = iter.next().unwrap();
+//!     assert_eq!(result.k, 1);
+//!     assert_eq!(result.v, Atom::from("a"));
+//!     let result = iter.next().unwrap();
+//!     assert_eq!(result.k, 2);
+//!     assert_eq!(result.v, Atom::from("b"));
+//!     assert!(iter.next().is_none());
+//! }
+//! ```
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+
+/// A relation where `value` is the value of the first binding of `key` in the association
+/// list `alist`.
+pub fn lookupo<U, E, G>(
+    key: LTerm<U, E>,
+    alist: LTerm<U, E>,
+    value: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match alist {
+            [[k, v] | _] => {
+                k == key,
+                v == value,
+            },
+            [[k, _] | rest] => {
+                k != key,
+                lookupo(key, rest, value),
+            }
+        }
+    )
+}
+
+/// A relation where `out` is the association list `alist` extended with a binding of `key`
+/// to `value`. The new binding shadows the earlier bindings of `key`.
+pub fn extendo<U, E, G>(
+    key: LTerm<U, E>,
+    value: LTerm<U, E>,
+    alist: LTerm<U, E>,
+    out: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(out == [[key, value] | alist])
+}
+
+/// A relation where `out` is the association list `alist` without the bindings of `key`.
+pub fn removeo<U, E, G>(
+    key: LTerm<U, E>,
+    alist: LTerm<U, E>,
+    out: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match alist {
+            [] => out == [],
+            [[k, _] | rest] => {
+                k == key,
+                removeo(key, rest, out),
+            },
+            [[k, v] | rest] => |r| {
+                k != key,
+                out == [[k, v] | r],
+                removeo(key, rest, r),
+            }
+        }
+    )
+}
+
+/// A relation where `keys` is the list of the keys bound in the association list `alist`, in
+/// the order of their first bindings.
+pub fn keyso<U, E, G>(alist: LTerm<U, E>, keys: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match alist {
+            [] => keys == [],
+            [[k, _] | rest] => |unshadowed, ks| {
+                keys == [k | ks],
+                removeo(k, rest, unshadowed),
+                keyso(unshadowed, ks),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{extendo, keyso, lookupo, removeo};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_lookupo_1() {
+        // Shadowing
+        let query = proto_vulcan_query!(|v| { lookupo(1, [[1, 'a], [2, 'b], [1, 'c]], v) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().v, Atom::from("a"));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|v| { lookupo(3, [[1, 'a], [2, 'b]], v) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_lookupo_2() {
+        // Unbound key in the association list
+        let query = proto_vulcan_query!(|x, v| {
+            lookupo(1, [[x, 'a], [1, 'b]], v),
+            x == 1,
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().v, Atom::from("a"));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x, v| {
+            lookupo(1, [[x, 'a], [1, 'b]], v),
+            x == 2,
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().v, Atom::from("b"));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_extendo_1() {
+        let query = proto_vulcan_query!(|v| {
+            |alist| {
+                extendo(1, 'c, [[1, 'a], [2, 'b]], alist),
+                lookupo(1, alist, v),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().v, Atom::from("c"));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_removeo_1() {
+        let query = proto_vulcan_query!(|q| { removeo(1, [[1, 'a], [2, 'b], [1, 'c]], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([[2, 'b]]));
+        assert!(iter.next().is_none());
+
+        // Removal of an unbound key
+        let query = proto_vulcan_query!(|k, q| {
+            removeo(k, [[1, 'a], [2, 'b]], q),
+            k == 3,
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([[1, 'a], [2, 'b]]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_keyso_1() {
+        let query = proto_vulcan_query!(|q| { keyso([[1, 'a], [2, 'b], [1, 'c], [3, 'd]], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+
+        // Unbound key
+        let query = proto_vulcan_query!(|x, q| {
+            keyso([[1, 'a], [x, 'b]], q),
+            x == 1,
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x, q| {
+            keyso([[1, 'a], [x, 'b]], q),
+            x == 2,
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+    }
+}
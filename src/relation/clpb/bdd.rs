@@ -0,0 +1,203 @@
+//! Reduced ordered binary decision diagrams
+//!
+//! Nodes are hash-consed in a node table, so that two equivalent functions over the same
+//! variable order are represented by the same node. Variables are identified by their index
+//! in the variable order.
+use std::collections::HashMap;
+
+pub type NodeId = usize;
+
+/// Constant false function
+pub const FALSE: NodeId = 0;
+
+/// Constant true function
+pub const TRUE: NodeId = 1;
+
+/// Variable index of the terminal nodes, ordered after all variables.
+const TERMINAL: usize = usize::MAX;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct Node {
+    var: usize,
+    low: NodeId,
+    high: NodeId,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    And,
+    Or,
+    Xor,
+}
+
+#[derive(Debug)]
+pub struct Bdd {
+    nodes: Vec<Node>,
+    unique: HashMap<Node, NodeId>,
+    memo: HashMap<(BinOp, NodeId, NodeId), NodeId>,
+}
+
+impl Bdd {
+    pub fn new() -> Bdd {
+        let terminal = |id| Node {
+            var: TERMINAL,
+            low: id,
+            high: id,
+        };
+        Bdd {
+            nodes: vec![terminal(FALSE), terminal(TRUE)],
+            unique: HashMap::new(),
+            memo: HashMap::new(),
+        }
+    }
+
+    fn mk(&mut self, var: usize, low: NodeId, high: NodeId) -> NodeId {
+        if low == high {
+            return low;
+        }
+        let node = Node { var, low, high };
+        match self.unique.get(&node) {
+            Some(id) => *id,
+            None => {
+                let id = self.nodes.len();
+                self.nodes.push(node);
+                self.unique.insert(node, id);
+                id
+            }
+        }
+    }
+
+    pub fn constant(value: bool) -> NodeId {
+        if value {
+            TRUE
+        } else {
+            FALSE
+        }
+    }
+
+    /// Function that is true when the variable is true.
+    pub fn var(&mut self, var: usize) -> NodeId {
+        self.mk(var, FALSE, TRUE)
+    }
+
+    pub fn not(&mut self, u: NodeId) -> NodeId {
+        self.apply(BinOp::Xor, u, TRUE)
+    }
+
+    pub fn apply(&mut self, op: BinOp, u: NodeId, v: NodeId) -> NodeId {
+        match op {
+            BinOp::And => {
+                if u == FALSE || v == FALSE {
+                    return FALSE;
+                } else if u == TRUE || u == v {
+                    return v;
+                } else if v == TRUE {
+                    return u;
+                }
+            }
+            BinOp::Or => {
+                if u == TRUE || v == TRUE {
+                    return TRUE;
+                } else if u == FALSE || u == v {
+                    return v;
+                } else if v == FALSE {
+                    return u;
+                }
+            }
+            BinOp::Xor => {
+                if u == v {
+                    return FALSE;
+                } else if u == FALSE {
+                    return v;
+                } else if v == FALSE {
+                    return u;
+                }
+            }
+        }
+
+        if let Some(id) = self.memo.get(&(op, u, v)) {
+            return *id;
+        }
+
+        let unode = self.nodes[u];
+        let vnode = self.nodes[v];
+        let var = std::cmp::min(unode.var, vnode.var);
+        let (ulow, uhigh) = if unode.var == var {
+            (unode.low, unode.high)
+        } else {
+            (u, u)
+        };
+        let (vlow, vhigh) = if vnode.var == var {
+            (vnode.low, vnode.high)
+        } else {
+            (v, v)
+        };
+        let low = self.apply(op, ulow, vlow);
+        let high = self.apply(op, uhigh, vhigh);
+        let id = self.mk(var, low, high);
+        self.memo.insert((op, u, v), id);
+        id
+    }
+
+    /// Function `u` with variable `var` fixed to `value`.
+    pub fn restrict(&mut self, u: NodeId, var: usize, value: bool) -> NodeId {
+        let mut memo = HashMap::new();
+        self.restrict_rec(u, var, value, &mut memo)
+    }
+
+    fn restrict_rec(
+        &mut self,
+        u: NodeId,
+        var: usize,
+        value: bool,
+        memo: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        let node = self.nodes[u];
+        if node.var > var {
+            // Terminal nodes and nodes below the variable do not depend on it.
+            return u;
+        } else if node.var == var {
+            return if value { node.high } else { node.low };
+        }
+
+        if let Some(id) = memo.get(&u) {
+            return *id;
+        }
+        let low = self.restrict_rec(node.low, var, value, memo);
+        let high = self.restrict_rec(node.high, var, value, memo);
+        let id = self.mk(node.var, low, high);
+        memo.insert(u, id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bdd, BinOp, FALSE, TRUE};
+
+    #[test]
+    fn test_bdd_1() {
+        let mut bdd = Bdd::new();
+        let x = bdd.var(0);
+        let y = bdd.var(1);
+        let nx = bdd.not(x);
+        assert_eq!(bdd.apply(BinOp::And, x, nx), FALSE);
+        assert_eq!(bdd.apply(BinOp::Or, x, nx), TRUE);
+
+        // Equivalent functions share the same node
+        let xy = bdd.apply(BinOp::And, x, y);
+        let ny = bdd.not(y);
+        let nxny = bdd.apply(BinOp::Or, nx, ny);
+        assert_eq!(bdd.not(nxny), xy);
+    }
+
+    #[test]
+    fn test_bdd_2() {
+        let mut bdd = Bdd::new();
+        let x = bdd.var(0);
+        let y = bdd.var(1);
+        let xy = bdd.apply(BinOp::Xor, x, y);
+        assert_eq!(bdd.restrict(xy, 0, true), bdd.not(y));
+        assert_eq!(bdd.restrict(xy, 1, false), x);
+    }
+}
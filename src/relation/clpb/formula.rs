@@ -0,0 +1,369 @@
+//! Boolean formulas over logic terms
+//!
+//! Formulas are built from terms and constants with the operators `!`, `&`, `|` and `^`, and
+//! with the `implies`, `equiv` and `card` constructors. For example, the formula "x and not y"
+//! is built as `Formula::from(&x) & !Formula::from(&y)`.
+use super::bdd::{Bdd, BinOp, NodeId};
+use crate::engine::Engine;
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::state::SMap;
+use crate::user::User;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+pub enum Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    Const(bool),
+    Var(LTerm<U, E>),
+    Not(Box<Formula<U, E>>),
+    And(Box<Formula<U, E>>, Box<Formula<U, E>>),
+    Or(Box<Formula<U, E>>, Box<Formula<U, E>>),
+    Xor(Box<Formula<U, E>>, Box<Formula<U, E>>),
+    Implies(Box<Formula<U, E>>, Box<Formula<U, E>>),
+    Equiv(Box<Formula<U, E>>, Box<Formula<U, E>>),
+    /// The number of true formulas is one of the counts.
+    Card(Vec<usize>, Vec<Formula<U, E>>),
+}
+
+impl<U, E> Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn implies<T: Into<Formula<U, E>>>(self, other: T) -> Formula<U, E> {
+        Formula::Implies(Box::new(self), Box::new(other.into()))
+    }
+
+    pub fn equiv<T: Into<Formula<U, E>>>(self, other: T) -> Formula<U, E> {
+        Formula::Equiv(Box::new(self), Box::new(other.into()))
+    }
+
+    /// Formula that is true when the number of true formulas is one of the `counts`.
+    pub fn card<T: Into<Formula<U, E>>>(counts: &[usize], formulas: Vec<T>) -> Formula<U, E> {
+        Formula::Card(
+            counts.to_vec(),
+            formulas.into_iter().map(Into::into).collect(),
+        )
+    }
+
+    /// Variables of the formula, in the order of first occurrence.
+    pub fn vars(&self) -> Vec<LTerm<U, E>> {
+        let mut vars = vec![];
+        self.collect_vars(&mut vars);
+        vars
+    }
+
+    fn collect_vars(&self, vars: &mut Vec<LTerm<U, E>>) {
+        match self {
+            Formula::Const(_) => (),
+            Formula::Var(x) => {
+                if !vars.contains(x) {
+                    vars.push(x.clone());
+                }
+            }
+            Formula::Not(f) => f.collect_vars(vars),
+            Formula::And(f, g)
+            | Formula::Or(f, g)
+            | Formula::Xor(f, g)
+            | Formula::Implies(f, g)
+            | Formula::Equiv(f, g) => {
+                f.collect_vars(vars);
+                g.collect_vars(vars);
+            }
+            Formula::Card(_, fs) => {
+                for f in fs {
+                    f.collect_vars(vars);
+                }
+            }
+        }
+    }
+
+    /// Replaces the variables with their values in the substitution map. Returns `None` if
+    /// some variable is bound to a non-boolean value.
+    pub fn walk(&self, smap: &SMap<U, E>) -> Option<Formula<U, E>> {
+        let walk_box = |f: &Formula<U, E>| f.walk(smap).map(Box::new);
+        Some(match self {
+            Formula::Const(b) => Formula::Const(*b),
+            Formula::Var(x) => {
+                let xwalk = smap.walk(x);
+                match xwalk.as_ref() {
+                    LTermInner::Var(_, _) => Formula::Var(xwalk.clone()),
+                    LTermInner::Val(LValue::Bool(b)) => Formula::Const(*b),
+                    _ => return None,
+                }
+            }
+            Formula::Not(f) => Formula::Not(walk_box(f)?),
+            Formula::And(f, g) => Formula::And(walk_box(f)?, walk_box(g)?),
+            Formula::Or(f, g) => Formula::Or(walk_box(f)?, walk_box(g)?),
+            Formula::Xor(f, g) => Formula::Xor(walk_box(f)?, walk_box(g)?),
+            Formula::Implies(f, g) => Formula::Implies(walk_box(f)?, walk_box(g)?),
+            Formula::Equiv(f, g) => Formula::Equiv(walk_box(f)?, walk_box(g)?),
+            Formula::Card(counts, fs) => Formula::Card(
+                counts.clone(),
+                fs.iter()
+                    .map(|f| f.walk(smap))
+                    .collect::<Option<Vec<Formula<U, E>>>>()?,
+            ),
+        })
+    }
+
+    /// Folds the constants of the formula.
+    pub fn simplify(self) -> Formula<U, E> {
+        match self {
+            Formula::Not(f) => match f.simplify() {
+                Formula::Const(b) => Formula::Const(!b),
+                Formula::Not(g) => *g,
+                f => Formula::Not(Box::new(f)),
+            },
+            Formula::And(f, g) => match (f.simplify(), g.simplify()) {
+                (Formula::Const(false), _) | (_, Formula::Const(false)) => Formula::Const(false),
+                (Formula::Const(true), h) | (h, Formula::Const(true)) => h,
+                (f, g) => Formula::And(Box::new(f), Box::new(g)),
+            },
+            Formula::Or(f, g) => match (f.simplify(), g.simplify()) {
+                (Formula::Const(true), _) | (_, Formula::Const(true)) => Formula::Const(true),
+                (Formula::Const(false), h) | (h, Formula::Const(false)) => h,
+                (f, g) => Formula::Or(Box::new(f), Box::new(g)),
+            },
+            Formula::Xor(f, g) => match (f.simplify(), g.simplify()) {
+                (Formula::Const(false), h) | (h, Formula::Const(false)) => h,
+                (Formula::Const(true), h) | (h, Formula::Const(true)) => !h,
+                (f, g) => Formula::Xor(Box::new(f), Box::new(g)),
+            },
+            Formula::Implies(f, g) => match (f.simplify(), g.simplify()) {
+                (Formula::Const(false), _) | (_, Formula::Const(true)) => Formula::Const(true),
+                (Formula::Const(true), h) => h,
+                (h, Formula::Const(false)) => !h,
+                (f, g) => Formula::Implies(Box::new(f), Box::new(g)),
+            },
+            Formula::Equiv(f, g) => match (f.simplify(), g.simplify()) {
+                (Formula::Const(true), h) | (h, Formula::Const(true)) => h,
+                (Formula::Const(false), h) | (h, Formula::Const(false)) => !h,
+                (f, g) => Formula::Equiv(Box::new(f), Box::new(g)),
+            },
+            Formula::Card(mut counts, fs) => {
+                // True formulas are removed from the list by decrementing the counts.
+                let mut rest = vec![];
+                let mut ntrue = 0;
+                for f in fs {
+                    match f.simplify() {
+                        Formula::Const(true) => ntrue += 1,
+                        Formula::Const(false) => (),
+                        f => rest.push(f),
+                    }
+                }
+                counts = counts
+                    .into_iter()
+                    .filter(|c| *c >= ntrue && *c - ntrue <= rest.len())
+                    .map(|c| c - ntrue)
+                    .collect();
+                counts.sort_unstable();
+                counts.dedup();
+                if counts.is_empty() {
+                    Formula::Const(false)
+                } else if rest.is_empty() || counts.len() == rest.len() + 1 {
+                    // Either nothing is left to count, or every count is allowed.
+                    Formula::Const(true)
+                } else {
+                    Formula::Card(counts, rest)
+                }
+            }
+            f => f,
+        }
+    }
+
+    /// Builds the decision diagram of the formula. Variables are numbered by their index in
+    /// `order`.
+    pub fn to_bdd(&self, bdd: &mut Bdd, order: &HashMap<LTerm<U, E>, usize>) -> NodeId {
+        match self {
+            Formula::Const(b) => Bdd::constant(*b),
+            Formula::Var(x) => bdd.var(order[x]),
+            Formula::Not(f) => {
+                let u = f.to_bdd(bdd, order);
+                bdd.not(u)
+            }
+            Formula::And(f, g) => {
+                let (u, v) = (f.to_bdd(bdd, order), g.to_bdd(bdd, order));
+                bdd.apply(BinOp::And, u, v)
+            }
+            Formula::Or(f, g) => {
+                let (u, v) = (f.to_bdd(bdd, order), g.to_bdd(bdd, order));
+                bdd.apply(BinOp::Or, u, v)
+            }
+            Formula::Xor(f, g) => {
+                let (u, v) = (f.to_bdd(bdd, order), g.to_bdd(bdd, order));
+                bdd.apply(BinOp::Xor, u, v)
+            }
+            Formula::Implies(f, g) => {
+                let (u, v) = (f.to_bdd(bdd, order), g.to_bdd(bdd, order));
+                let nu = bdd.not(u);
+                bdd.apply(BinOp::Or, nu, v)
+            }
+            Formula::Equiv(f, g) => {
+                let (u, v) = (f.to_bdd(bdd, order), g.to_bdd(bdd, order));
+                let x = bdd.apply(BinOp::Xor, u, v);
+                bdd.not(x)
+            }
+            Formula::Card(counts, fs) => {
+                // exactly[c] is the function "exactly c of the formulas seen so far are true".
+                let mut exactly = vec![Bdd::constant(true)];
+                for f in fs {
+                    let u = f.to_bdd(bdd, order);
+                    let nu = bdd.not(u);
+                    let mut next = Vec::with_capacity(exactly.len() + 1);
+                    for c in 0..=exactly.len() {
+                        let stay = match exactly.get(c) {
+                            Some(e) => bdd.apply(BinOp::And, nu, *e),
+                            None => Bdd::constant(false),
+                        };
+                        let step = match c.checked_sub(1) {
+                            Some(p) => bdd.apply(BinOp::And, u, exactly[p]),
+                            None => Bdd::constant(false),
+                        };
+                        next.push(bdd.apply(BinOp::Or, stay, step));
+                    }
+                    exactly = next;
+                }
+                counts
+                    .iter()
+                    .filter_map(|c| exactly.get(*c).copied())
+                    .fold(Bdd::constant(false), |acc, e| bdd.apply(BinOp::Or, acc, e))
+            }
+        }
+    }
+}
+
+impl<U, E> From<bool> for Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(b: bool) -> Formula<U, E> {
+        Formula::Const(b)
+    }
+}
+
+impl<U, E> From<LTerm<U, E>> for Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(x: LTerm<U, E>) -> Formula<U, E> {
+        match x.as_ref() {
+            LTermInner::Val(LValue::Bool(b)) => Formula::Const(*b),
+            _ => Formula::Var(x),
+        }
+    }
+}
+
+impl<U, E> From<&LTerm<U, E>> for Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(x: &LTerm<U, E>) -> Formula<U, E> {
+        Formula::from(x.clone())
+    }
+}
+
+impl<U, E> Not for Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    type Output = Formula<U, E>;
+
+    fn not(self) -> Formula<U, E> {
+        Formula::Not(Box::new(self))
+    }
+}
+
+impl<U, E, T> BitAnd<T> for Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    T: Into<Formula<U, E>>,
+{
+    type Output = Formula<U, E>;
+
+    fn bitand(self, other: T) -> Formula<U, E> {
+        Formula::And(Box::new(self), Box::new(other.into()))
+    }
+}
+
+impl<U, E, T> BitOr<T> for Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    T: Into<Formula<U, E>>,
+{
+    type Output = Formula<U, E>;
+
+    fn bitor(self, other: T) -> Formula<U, E> {
+        Formula::Or(Box::new(self), Box::new(other.into()))
+    }
+}
+
+impl<U, E, T> BitXor<T> for Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    T: Into<Formula<U, E>>,
+{
+    type Output = Formula<U, E>;
+
+    fn bitxor(self, other: T) -> Formula<U, E> {
+        Formula::Xor(Box::new(self), Box::new(other.into()))
+    }
+}
+
+impl<U, E> fmt::Display for Formula<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Operands that are binary operations are parenthesized.
+        let operand = |g: &Formula<U, E>, f: &mut fmt::Formatter| match g {
+            Formula::Const(_) | Formula::Var(_) | Formula::Not(_) | Formula::Card(_, _) => {
+                write!(f, "{}", g)
+            }
+            _ => write!(f, "({})", g),
+        };
+        let binary = |g: &Formula<U, E>, op: &str, h: &Formula<U, E>, f: &mut fmt::Formatter| {
+            operand(g, f)?;
+            write!(f, " {} ", op)?;
+            operand(h, f)
+        };
+        match self {
+            Formula::Const(b) => write!(f, "{}", b),
+            Formula::Var(x) => write!(f, "{}", x),
+            Formula::Not(g) => {
+                write!(f, "!")?;
+                operand(g, f)
+            }
+            Formula::And(g, h) => binary(g, "&", h, f),
+            Formula::Or(g, h) => binary(g, "|", h, f),
+            Formula::Xor(g, h) => binary(g, "^", h, f),
+            Formula::Implies(g, h) => binary(g, "->", h, f),
+            Formula::Equiv(g, h) => binary(g, "<->", h, f),
+            Formula::Card(counts, fs) => {
+                write!(f, "card({:?}, [", counts)?;
+                for (i, g) in fs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", g)?;
+                }
+                write!(f, "])")
+            }
+        }
+    }
+}
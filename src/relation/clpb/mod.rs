@@ -0,0 +1,41 @@
+//! # CLP(B)
+//! Proto-vulcan implements boolean constraints over variables that take values `true` and
+//! `false`. Formulas are built as `Formula`-values from terms with the operators `!`, `&`, `|`
+//! and `^`, and the `implies` and `equiv` methods. A formula is passed to a relation as a
+//! quoted Rust expression.
+//!
+//! * `sat(f)`: the formula `f` is true.
+//! * `taut(f, t)`: `t` is `true` if `f` is entailed by the constraints, or `false` if `f` is
+//!   inconsistent with them.
+//! * `card(counts, xs)`: the number of true terms in the list `xs` is one of `counts`.
+//!
+//! The constraints that share variables are combined and checked for satisfiability with a
+//! binary decision diagram. Variables whose values are implied by the constraints are bound,
+//! and the remaining formula is kept in the answer as a residual constraint.
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::clpb::Formula;
+//! use proto_vulcan::relation::{card, sat};
+//! fn main() {
+//!     let query = proto_vulcan_query!(|x, y, z| {
+//!         card(&[1], [x, y, z]),
+//!         sat({ !Formula::from(&x) }),
+//!         sat({ Formula::from(&y).implies(&x) }),
+//!     });
+//!     let mut iter = query.run();
+//!     let result = iter.next().unwrap();
+//!     assert_eq!(result.x, false);
+//!     assert_eq!(result.y, false);
+//!     assert_eq!(result.z, true);
+//!     assert!(iter.next().is_none());
+//! }
+//! ```
+
+mod bdd;
+pub mod formula;
+pub mod sat;
+pub mod taut;
+
+#[doc(inline)]
+pub use formula::Formula;
@@ -0,0 +1,336 @@
+//! Satisfiability constraints
+use super::bdd::{Bdd, FALSE, TRUE};
+use super::formula::Formula;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Sat<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    formula: Formula<U, E>,
+}
+
+impl<U, E> Sat<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(formula: Formula<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Sat { formula })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Sat<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match SatConstraint::new(self.formula.clone()).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Constrains the boolean formula to be true.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::clpb::Formula;
+/// use proto_vulcan::relation::sat;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, y| {
+///         sat({ Formula::from(&x) ^ &y }),
+///         sat({ Formula::from(&x).implies(false) }),
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, false);
+///     assert_eq!(result.y, true);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn sat<U, E, G>(formula: Formula<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Sat::new(formula)
+}
+
+/// Cardinality constraint: the number of true terms in the list `xs` is one of the `counts`.
+pub fn card<U, E, G>(counts: &[usize], xs: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    let formulas = if xs.is_list() {
+        xs.iter().map(Formula::from).collect()
+    } else {
+        vec![Formula::from(xs)]
+    };
+    Sat::new(Formula::card(counts, formulas))
+}
+
+/// Boolean constraint
+///
+/// The constraints that share variables are merged into a single constraint, whose formula
+/// is checked for satisfiability with a decision diagram. Variables whose value is implied by
+/// the formula are bound to it.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct SatConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    formula: Formula<U, E>,
+}
+
+impl<U, E> SatConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(formula: Formula<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(SatConstraint { formula })
+    }
+
+    pub fn formula(&self) -> &Formula<U, E> {
+        &self.formula
+    }
+}
+
+/// Conjunction of the formulas of all boolean constraints in the store.
+pub(super) fn store_formula<U, E>(state: &State<U, E>) -> Option<Formula<U, E>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut formula = Formula::Const(true);
+    for c in state.cstore_ref().iter() {
+        if let Some(satc) = c.downcast_ref::<SatConstraint<U, E>>() {
+            let f = satc.formula.walk(state.smap_ref())?;
+            formula = formula & f;
+        }
+    }
+    Some(formula)
+}
+
+/// Numbers the variables of the formula in the order of their first occurrence.
+pub(super) fn variable_order<U, E>(formula: &Formula<U, E>) -> HashMap<LTerm<U, E>, usize>
+where
+    U: User,
+    E: Engine<U>,
+{
+    formula
+        .vars()
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| (x, i))
+        .collect()
+}
+
+impl<U, E> Constraint<U, E> for SatConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, mut state: State<U, E>) -> SResult<U, E> {
+        let mut formula = self.formula.walk(state.smap_ref()).ok_or(())?;
+
+        // Merge the constraints that share variables with the formula until none are left.
+        loop {
+            let vars = formula.vars();
+            let shared = state
+                .cstore_ref()
+                .iter()
+                .filter(|c| match c.downcast_ref::<SatConstraint<U, E>>() {
+                    Some(satc) => satc
+                        .formula
+                        .vars()
+                        .iter()
+                        .any(|x| vars.contains(state.smap_ref().walk(x))),
+                    None => false,
+                })
+                .cloned()
+                .collect::<Vec<Rc<dyn Constraint<U, E>>>>();
+            if shared.is_empty() {
+                break;
+            }
+            for c in shared {
+                let (taken_state, c) = state.take_constraint(&c);
+                state = taken_state;
+                if let Some(satc) = c.as_ref().and_then(|c| c.downcast_ref::<Self>()) {
+                    formula = formula & satc.formula.walk(state.smap_ref()).ok_or(())?;
+                }
+            }
+        }
+
+        let formula = formula.simplify();
+        let order = variable_order(&formula);
+        let mut bdd = Bdd::new();
+        let root = formula.to_bdd(&mut bdd, &order);
+        if root == FALSE {
+            return Err(());
+        } else if root == TRUE {
+            return Ok(state);
+        }
+
+        // A variable is implied to be true if the formula is unsatisfiable when it is false,
+        // and vice versa.
+        let mut implied = vec![];
+        for (x, i) in order.iter() {
+            if bdd.restrict(root, *i, false) == FALSE {
+                implied.push((x.clone(), true));
+            } else if bdd.restrict(root, *i, true) == FALSE {
+                implied.push((x.clone(), false));
+            }
+        }
+
+        // The constraint is added back before the implied values are unified, so that it is
+        // run again with them.
+        let mut state = state.with_constraint(SatConstraint::new(formula));
+        for (x, value) in implied {
+            state = state.unify(&x, &LTerm::from(value))?;
+        }
+        Ok(state)
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        self.formula.vars()
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        match self.formula.walk(smap) {
+            Some(formula) => Some(SatConstraint::new(formula.simplify())),
+            None => Some(SatConstraint::new(self.formula.clone())),
+        }
+    }
+}
+
+impl<U, E> std::fmt::Display for SatConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "sat({})", self.formula)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{card, sat};
+    use crate::prelude::*;
+    use crate::relation::clpb::Formula;
+
+    #[test]
+    fn test_sat_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            sat({ Formula::from(&x) & !Formula::from(&y) }),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, true);
+        assert_eq!(result.y, false);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_sat_2() {
+        // Unsatisfiable conjunction of constraints fails without enumerating the variables.
+        let query = proto_vulcan_query!(|x, y| {
+            sat({ Formula::from(&x) | &y }),
+            sat({ Formula::from(&x) | !Formula::from(&y) }),
+            sat({ !Formula::from(&x) | &y }),
+            sat({ !Formula::from(&x) | !Formula::from(&y) }),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_sat_3() {
+        // The residual formula is kept in the answer.
+        let query = proto_vulcan_query!(|x, y| {
+            sat({ Formula::from(&x) ^ &y }),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result.x.is_any());
+        assert!(result.x.is_constrained());
+        assert_eq!(
+            format!("{}", result.x),
+            format!("{0}  where  {{ sat({0} ^ {1}) }}", *result.x, *result.y)
+        );
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_sat_4() {
+        // Constraints are propagated through unification.
+        let query = proto_vulcan_query!(|x, y, z| {
+            sat({ Formula::from(&x).implies(&y) }),
+            sat({ Formula::from(&y).equiv(&z) }),
+            x == true,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.y, true);
+        assert_eq!(result.z, true);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_sat_5() {
+        let query = proto_vulcan_query!(|x| {
+            sat({ Formula::from(&x) }),
+            x == 1,
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_card_1() {
+        let query = proto_vulcan_query!(|x, y, z| {
+            card(&[2], [x, y, z]),
+            x == false,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.y, true);
+        assert_eq!(result.z, true);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_card_2() {
+        let query = proto_vulcan_query!(|x, y, z| {
+            card(&[0, 3], [x, y, z]),
+            conde {
+                [x == true, y == true],
+                [x == true, y == false],
+            },
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.z, true);
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,136 @@
+//! Tautology checking
+use super::bdd::{Bdd, BinOp, FALSE};
+use super::formula::Formula;
+use super::sat::{store_formula, variable_order};
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::State;
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Taut<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    formula: Formula<U, E>,
+    t: LTerm<U, E>,
+}
+
+impl<U, E> Taut<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(formula: Formula<U, E>, t: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Taut { formula, t })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Taut<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let (constraints, formula) =
+            match (store_formula(&state), self.formula.walk(state.smap_ref())) {
+                (Some(constraints), Some(formula)) => (constraints, formula),
+                _ => return Stream::empty(),
+            };
+
+        let order = variable_order(&(constraints.clone() & formula.clone()));
+        let mut bdd = Bdd::new();
+        let c = constraints.to_bdd(&mut bdd, &order);
+        let f = formula.to_bdd(&mut bdd, &order);
+        let nf = bdd.not(f);
+
+        let value = if bdd.apply(BinOp::And, c, nf) == FALSE {
+            true
+        } else if bdd.apply(BinOp::And, c, f) == FALSE {
+            false
+        } else {
+            return Stream::empty();
+        };
+
+        match state.unify(&self.t, &LTerm::from(value)) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Tautology check: `t` is `true` if the formula is entailed by the current boolean
+/// constraints, and `false` if the formula is inconsistent with them. Fails otherwise. The
+/// formula is not added as a constraint.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::clpb::Formula;
+/// use proto_vulcan::relation::{sat, taut};
+/// fn main() {
+///     let query = proto_vulcan_query!(|t| {
+///         |x, y| {
+///             sat({ Formula::from(&x) & &y }),
+///             taut({ Formula::from(&x) | &y }, t),
+///         }
+///     });
+///     assert_eq!(query.run().next().unwrap().t, true);
+/// }
+/// ```
+pub fn taut<U, E, G>(formula: Formula<U, E>, t: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Taut::new(formula, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::taut;
+    use crate::prelude::*;
+    use crate::relation::clpb::sat::sat;
+    use crate::relation::clpb::Formula;
+
+    #[test]
+    fn test_taut_1() {
+        let query = proto_vulcan_query!(|t| {
+            |x| {
+                taut({ Formula::from(&x) | !Formula::from(&x) }, t),
+            }
+        });
+        assert_eq!(query.run().next().unwrap().t, true);
+    }
+
+    #[test]
+    fn test_taut_2() {
+        let query = proto_vulcan_query!(|t| {
+            |x, y| {
+                sat({ Formula::from(&x).implies(!Formula::from(&y)) }),
+                taut({ Formula::from(&x) & &y }, t),
+            }
+        });
+        assert_eq!(query.run().next().unwrap().t, false);
+    }
+
+    #[test]
+    fn test_taut_3() {
+        // Neither entailed nor inconsistent
+        let query = proto_vulcan_query!(|t| {
+            |x, y| {
+                sat({ Formula::from(&x) | &y }),
+                taut({ Formula::from(&x) }, t),
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -0,0 +1,671 @@
+//! Finite domain arithmetic: division, modulo, absolute value, minimum, maximum and power
+//!
+//! Each relation constrains `w` to be the result of an operation on `u` and `v`. When the
+//! domains of the operands are small, the domains are filtered to the values that have
+//! support, otherwise the bounds of the domains are narrowed.
+//!
+//! * `divfd(u, v, w)`: w = u / v, truncated toward zero
+//! * `modfd(u, v, w)`: w = u mod v, with the sign of the divisor `v`
+//! * `absfd(u, w)`: w = |u|
+//! * `minfd(u, v, w)`: w = min(u, v)
+//! * `maxfd(u, v, w)`: w = max(u, v)
+//! * `powfd(u, v, w)`: w = u ^ v, with v >= 0
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::cmp::{max, min};
+use std::rc::Rc;
+
+/// Largest number of operand value combinations that are enumerated to filter the domains.
+const MAX_SUPPORT_COMBINATIONS: usize = 1 << 12;
+
+/// Arithmetic operation of `ArithFdConstraint`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArithOp {
+    Div,
+    Mod,
+    Abs,
+    Min,
+    Max,
+    Pow,
+}
+
+impl ArithOp {
+    /// Returns `None` if the operation is undefined for the operands, or if the result
+    /// does not fit in `isize`.
+    fn apply(self, u: isize, v: isize) -> Option<isize> {
+        match self {
+            ArithOp::Div => u.checked_div(v),
+            ArithOp::Mod => {
+                if v == -1 {
+                    // Avoid overflow of `isize::MIN % -1`
+                    Some(0)
+                } else {
+                    let r = u.checked_rem(v)?;
+                    if r != 0 && (r < 0) != (v < 0) {
+                        Some(r + v)
+                    } else {
+                        Some(r)
+                    }
+                }
+            }
+            ArithOp::Abs => u.checked_abs(),
+            ArithOp::Min => Some(min(u, v)),
+            ArithOp::Max => Some(max(u, v)),
+            ArithOp::Pow => {
+                if v < 0 {
+                    None
+                } else if v > u32::MAX as isize {
+                    match u {
+                        0 | 1 => Some(u),
+                        -1 => Some(if v % 2 == 0 { 1 } else { -1 }),
+                        _ => None,
+                    }
+                } else {
+                    u.checked_pow(v as u32)
+                }
+            }
+        }
+    }
+
+    fn is_unary(self) -> bool {
+        self == ArithOp::Abs
+    }
+}
+
+/// `b ^ e` for non-negative `b`, saturated to `i128::MAX`.
+fn saturating_pow(b: i128, e: isize) -> i128 {
+    match b {
+        0 if e == 0 => 1,
+        0 | 1 => b,
+        _ => b.checked_pow(min(e, 128) as u32).unwrap_or(i128::MAX),
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ArithFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    op: ArithOp,
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> ArithFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        op: ArithOp,
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        w: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(ArithFd { op, u, v, w })))
+    }
+}
+
+impl<U, E> Solve<U, E> for ArithFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let c = ArithFdConstraint::new(self.op, self.u.clone(), self.v.clone(), self.w.clone());
+        match c.run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Division: `w` is `u / v` truncated toward zero. The divisor `v` is constrained to be
+/// non-zero.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{divfd, infdrange};
+/// fn main() {
+///     let query = proto_vulcan_query!(|x| {
+///         infdrange(x, &(0..=20)),
+///         divfd(x, 7, 2),
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().x, 14);
+///     assert_eq!(iter.next().unwrap().x, 15);
+///     assert_eq!(iter.next().unwrap().x, 16);
+///     assert_eq!(iter.next().unwrap().x, 17);
+///     assert_eq!(iter.next().unwrap().x, 18);
+///     assert_eq!(iter.next().unwrap().x, 19);
+///     assert_eq!(iter.next().unwrap().x, 20);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn divfd<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ArithFd::new(ArithOp::Div, u, v, w)
+}
+
+/// Modulo: `w` is `u mod v`, where the result has the sign of the divisor `v`. The divisor
+/// is constrained to be non-zero.
+pub fn modfd<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ArithFd::new(ArithOp::Mod, u, v, w)
+}
+
+/// Absolute value: `w` is `|u|`.
+pub fn absfd<U, E, G>(u: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ArithFd::new(ArithOp::Abs, u.clone(), u, w)
+}
+
+/// Minimum: `w` is the smaller of `u` and `v`.
+pub fn minfd<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ArithFd::new(ArithOp::Min, u, v, w)
+}
+
+/// Maximum: `w` is the larger of `u` and `v`.
+pub fn maxfd<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ArithFd::new(ArithOp::Max, u, v, w)
+}
+
+/// Power: `w` is `u` raised to the power of `v`. The exponent is constrained to be
+/// non-negative.
+pub fn powfd<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ArithFd::new(ArithOp::Pow, u, v, w)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ArithFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    op: ArithOp,
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> ArithFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(
+        op: ArithOp,
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        w: LTerm<U, E>,
+    ) -> Rc<dyn Constraint<U, E>> {
+        assert!(u.is_var() || u.is_number());
+        assert!(v.is_var() || v.is_number());
+        assert!(w.is_var() || w.is_number());
+        Rc::new(ArithFdConstraint { op, u, v, w })
+    }
+
+    /// Filters the domains to values that take part in some solution of the constraint.
+    fn filter_supported(
+        &self,
+        state: State<U, E>,
+        (uwalk, udomain): (&LTerm<U, E>, &FiniteDomain),
+        (vwalk, vdomain): (&LTerm<U, E>, &FiniteDomain),
+        (wwalk, wdomain): (&LTerm<U, E>, &FiniteDomain),
+    ) -> SResult<U, E> {
+        let mut usupport = vec![];
+        let mut vsupport = vec![];
+        let mut wsupport = vec![];
+        for u in udomain.iter() {
+            let vs = if self.op.is_unary() {
+                FiniteDomain::from(u)
+            } else {
+                vdomain.clone()
+            };
+            for v in vs.iter() {
+                match self.op.apply(u, v) {
+                    Some(w) if wdomain.contains(w) => {
+                        usupport.push(u);
+                        vsupport.push(v);
+                        wsupport.push(w);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        if usupport.is_empty() {
+            return Err(());
+        }
+
+        let state = state.process_domain(uwalk, Rc::new(FiniteDomain::from(usupport)))?;
+        let state = if self.op.is_unary() {
+            state
+        } else {
+            state.process_domain(vwalk, Rc::new(FiniteDomain::from(vsupport)))?
+        };
+        state.process_domain(wwalk, Rc::new(FiniteDomain::from(wsupport)))
+    }
+
+    /// Narrows the bounds of the domains.
+    fn filter_bounds(
+        &self,
+        state: State<U, E>,
+        (uwalk, udomain): (&LTerm<U, E>, &FiniteDomain),
+        (vwalk, vdomain): (&LTerm<U, E>, &FiniteDomain),
+        (wwalk, wdomain): (&LTerm<U, E>, &FiniteDomain),
+    ) -> SResult<U, E> {
+        let (umin, umax) = (udomain.min() as i128, udomain.max() as i128);
+        let (vmin, vmax) = (vdomain.min() as i128, vdomain.max() as i128);
+        let (wmin, wmax) = (wdomain.min() as i128, wdomain.max() as i128);
+        let narrow = super::narrow_bounds;
+
+        match self.op {
+            ArithOp::Div => {
+                // The divisor is never zero, and the extremes of the quotient are found at
+                // the corners of the non-zero parts of the divisor's domain.
+                let state = state.process_domain(
+                    vwalk,
+                    Rc::new(vdomain.diff(FiniteDomain::from(0)).ok_or(())?),
+                )?;
+                let divisors = [vmin, -1, 1, vmax]
+                    .iter()
+                    .copied()
+                    .filter(|v| *v != 0 && vmin <= *v && *v <= vmax)
+                    .collect::<Vec<i128>>();
+                let quotients = [umin, umax]
+                    .iter()
+                    .flat_map(|u| divisors.iter().map(move |v| u / v))
+                    .collect::<Vec<i128>>();
+                let qmin = quotients.iter().copied().min().ok_or(())?;
+                let qmax = quotients.iter().copied().max().ok_or(())?;
+                let state = narrow(state, wwalk, qmin, qmax)?;
+
+                // u = w * v + r, where |r| < |v|
+                let products = [wmin * vmin, wmin * vmax, wmax * vmin, wmax * vmax];
+                let rmax = max(vmin.abs(), vmax.abs()) - 1;
+                let pmin = products.iter().copied().min().unwrap();
+                let pmax = products.iter().copied().max().unwrap();
+                narrow(state, uwalk, pmin - rmax, pmax + rmax)
+            }
+            ArithOp::Mod => {
+                let state = state.process_domain(
+                    vwalk,
+                    Rc::new(vdomain.diff(FiniteDomain::from(0)).ok_or(())?),
+                )?;
+                if vmin > 0 {
+                    let hi = if umin >= 0 {
+                        min(vmax - 1, umax)
+                    } else {
+                        vmax - 1
+                    };
+                    narrow(state, wwalk, 0, hi)
+                } else if vmax < 0 {
+                    let lo = if umax <= 0 {
+                        max(vmin + 1, umin)
+                    } else {
+                        vmin + 1
+                    };
+                    narrow(state, wwalk, lo, 0)
+                } else {
+                    narrow(state, wwalk, vmin + 1, vmax - 1)
+                }
+            }
+            ArithOp::Abs => {
+                let (lo, hi) = if umin >= 0 {
+                    (umin, umax)
+                } else if umax <= 0 {
+                    (-umax, -umin)
+                } else {
+                    (0, max(-umin, umax))
+                };
+                let state = narrow(state, wwalk, lo, hi)?;
+                narrow(state, uwalk, -wmax, wmax)
+            }
+            ArithOp::Min => {
+                let state = narrow(state, wwalk, min(umin, vmin), min(umax, vmax))?;
+                let state = narrow(state, uwalk, wmin, umax)?;
+                narrow(state, vwalk, wmin, vmax)
+            }
+            ArithOp::Max => {
+                let state = narrow(state, wwalk, max(umin, vmin), max(umax, vmax))?;
+                let state = narrow(state, uwalk, umin, wmax)?;
+                narrow(state, vwalk, vmin, wmax)
+            }
+            ArithOp::Pow => {
+                let state = narrow(state, vwalk, 0, vmax)?;
+                let vmin = max(vmin, 0) as isize;
+                let vmax = vmax as isize;
+                let (lo, hi) = if umin >= 1 {
+                    (saturating_pow(umin, vmin), saturating_pow(umax, vmax))
+                } else if umin >= 0 {
+                    (0, max(1, saturating_pow(umax, vmax)))
+                } else {
+                    let m = max(1, saturating_pow(max(-umin, umax), vmax));
+                    (-m, m)
+                };
+                narrow(state, wwalk, lo, hi)
+            }
+        }
+    }
+}
+
+impl<U, E> Constraint<U, E> for ArithFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let smap = state.get_smap();
+        let dstore = state.get_dstore();
+        let bound = super::bound_operands(&state, &self.operands());
+
+        let uwalk = smap.walk(&self.u);
+        let singleton_udomain;
+        let maybe_udomain = match uwalk.as_ref() {
+            LTermInner::Var(_, _) => dstore.get(uwalk),
+            LTermInner::Val(LValue::Number(u)) => {
+                singleton_udomain = Rc::new(FiniteDomain::from(*u));
+                Some(&singleton_udomain)
+            }
+            _ => return Err(()),
+        };
+
+        let vwalk = smap.walk(&self.v);
+        let singleton_vdomain;
+        let maybe_vdomain = match vwalk.as_ref() {
+            LTermInner::Var(_, _) => dstore.get(vwalk),
+            LTermInner::Val(LValue::Number(v)) => {
+                singleton_vdomain = Rc::new(FiniteDomain::from(*v));
+                Some(&singleton_vdomain)
+            }
+            _ => return Err(()),
+        };
+
+        let wwalk = smap.walk(&self.w);
+        let singleton_wdomain;
+        let maybe_wdomain = match wwalk.as_ref() {
+            LTermInner::Var(_, _) => dstore.get(wwalk),
+            LTermInner::Val(LValue::Number(w)) => {
+                singleton_wdomain = Rc::new(FiniteDomain::from(*w));
+                Some(&singleton_wdomain)
+            }
+            _ => return Err(()),
+        };
+
+        // If all operators are bound to numbers, then we can drop the constraint or fail if
+        // constraint is not fulfilled.
+        if let (Some(u), Some(v), Some(w)) =
+            (uwalk.get_number(), vwalk.get_number(), wwalk.get_number())
+        {
+            if self.op.apply(u, v) == Some(w) {
+                return Ok(state);
+            } else {
+                return Err(());
+            }
+        }
+
+        match (maybe_udomain, maybe_vdomain, maybe_wdomain) {
+            (Some(udomain), Some(vdomain), Some(wdomain)) => {
+                let combinations = if self.op.is_unary() {
+                    udomain.size()
+                } else {
+                    udomain.size().saturating_mul(vdomain.size())
+                };
+
+                // The constraint is not dropped until all variables converge into numbers.
+                let u = (uwalk, udomain.as_ref());
+                let v = (vwalk, vdomain.as_ref());
+                let w = (wwalk, wdomain.as_ref());
+                let state = if combinations <= MAX_SUPPORT_COMBINATIONS {
+                    self.filter_supported(state, u, v, w)?
+                } else {
+                    self.filter_bounds(state, u, v, w)?
+                };
+                super::store_or_rerun(self, state, bound)
+            }
+            // If all operators do not yet have domains, then keep the constraint until it can
+            // be used to constrain some domains.
+            _ => Ok(state.with_constraint(self)),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        if self.op.is_unary() {
+            vec![self.u.clone(), self.w.clone()]
+        } else {
+            vec![self.u.clone(), self.v.clone(), self.w.clone()]
+        }
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(ArithFdConstraint::new(
+            self.op,
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+            smap.walk_star(&self.w),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for ArithFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{absfd, divfd, maxfd, minfd, modfd, powfd};
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::infdrange;
+
+    fn pairs<I: Iterator<Item = (isize, isize)>>(iter: I) -> Vec<(isize, isize)> {
+        let mut v = iter.collect::<Vec<(isize, isize)>>();
+        v.sort_unstable();
+        v
+    }
+
+    #[test]
+    fn test_divfd_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(-7..=7)),
+            infdrange(y, &(-3..=3)),
+            divfd(x, y, -3),
+        });
+        let results = pairs(
+            query
+                .run()
+                .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap())),
+        );
+        assert_eq!(
+            results,
+            vec![(-7, 2), (-6, 2), (-3, 1), (3, -1), (6, -2), (7, -2)]
+        );
+    }
+
+    #[test]
+    fn test_divfd_2() {
+        // Division by zero has no solutions
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(0..=10)),
+            divfd(x, 0, 0),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_divfd_3() {
+        // Large domains are narrowed by bounds
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(0..=1_000_000)),
+            infdrange(y, &(0..=1_000_000)),
+            divfd(x, 1000, y),
+            y == 999,
+            x == 999_999,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 999_999);
+        assert_eq!(result.y, 999);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_modfd_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(-5..=5)),
+            infdrange(y, &(-3..=3)),
+            modfd(x, y, 2),
+        });
+        let results = pairs(
+            query
+                .run()
+                .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap())),
+        );
+        assert_eq!(results, vec![(-4, 3), (-1, 3), (2, 3), (5, 3)]);
+    }
+
+    #[test]
+    fn test_modfd_2() {
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(-5..=5)),
+            modfd(x, -3, -1),
+        });
+        let results = query
+            .run()
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(results, vec![-4, -1, 2, 5]);
+    }
+
+    #[test]
+    fn test_absfd_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(-3..=2)),
+            infdrange(y, &(2..=10)),
+            absfd(x, y),
+        });
+        let results = pairs(
+            query
+                .run()
+                .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap())),
+        );
+        assert_eq!(results, vec![(-3, 3), (-2, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_absfd_2() {
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(-1_000_000..=1_000_000)),
+            absfd(x, 5),
+        });
+        let results = query
+            .run()
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(results, vec![-5, 5]);
+    }
+
+    #[test]
+    fn test_minfd_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange([x, y], &(0..=3)),
+            minfd(x, y, 2),
+        });
+        let results = pairs(
+            query
+                .run()
+                .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap())),
+        );
+        assert_eq!(results, vec![(2, 2), (2, 3), (3, 2)]);
+    }
+
+    #[test]
+    fn test_maxfd_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange([x, y], &(0..=3)),
+            maxfd(x, y, 1),
+        });
+        let results = pairs(
+            query
+                .run()
+                .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap())),
+        );
+        assert_eq!(results, vec![(0, 1), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn test_powfd_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(-10..=10)),
+            infdrange(y, &(-2..=10)),
+            powfd(x, y, 64),
+        });
+        let results = pairs(
+            query
+                .run()
+                .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap())),
+        );
+        assert_eq!(results, vec![(-8, 2), (-2, 6), (2, 6), (4, 3), (8, 2)]);
+    }
+
+    #[test]
+    fn test_powfd_2() {
+        // Bounds propagation with large domains
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(2..=3)),
+            infdrange(y, &(0..=100_000)),
+            powfd(x, 10, y),
+        });
+        let results = pairs(
+            query
+                .run()
+                .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap())),
+        );
+        assert_eq!(results, vec![(2, 1024), (3, 59049)]);
+    }
+}
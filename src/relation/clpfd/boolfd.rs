@@ -0,0 +1,386 @@
+//! Boolean connectives over 0/1 finite domain variables
+//!
+//! The connectives are reified like the constraints in `reified`: `andfd(x, y, b)` constrains
+//! `b` to be 1 iff both `x` and `y` are 1. All operands are constrained to domain `0..=1`.
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+/// Boolean connective of `BoolFdConstraint`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Connective {
+    /// b <=> x /\ y
+    And,
+    /// b <=> x \/ y
+    Or,
+    /// b <=> !x
+    Not,
+    /// b <=> (x => y)
+    Implies,
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct BoolFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    connective: Connective,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+    b: LTerm<U, E>,
+}
+
+impl<U, E> BoolFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        connective: Connective,
+        x: LTerm<U, E>,
+        y: LTerm<U, E>,
+        b: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(BoolFd {
+            connective,
+            x,
+            y,
+            b,
+        })))
+    }
+}
+
+impl<U, E> Solve<U, E> for BoolFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, mut state: State<U, E>) -> Stream<U, E> {
+        let c = BoolFdConstraint::new(
+            self.connective,
+            self.x.clone(),
+            self.y.clone(),
+            self.b.clone(),
+        );
+        for operand in c.operands() {
+            let walk = state.smap_ref().walk(&operand).clone();
+            state = match state.process_domain(&walk, Rc::new(FiniteDomain::from(0..=1))) {
+                Ok(state) => state,
+                Err(_) => return Stream::empty(),
+            };
+        }
+
+        match c.run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Conjunction: `b` is 1 iff both `x` and `y` are 1.
+pub fn andfd<U, E, G>(x: LTerm<U, E>, y: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    BoolFd::new(Connective::And, x, y, b)
+}
+
+/// Disjunction: `b` is 1 iff `x` or `y` is 1.
+pub fn orfd<U, E, G>(x: LTerm<U, E>, y: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    BoolFd::new(Connective::Or, x, y, b)
+}
+
+/// Negation: `b` is 1 iff `x` is 0.
+pub fn notfd<U, E, G>(x: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    BoolFd::new(Connective::Not, x.clone(), x, b)
+}
+
+/// Implication: `b` is 1 iff `x` is 0 or `y` is 1.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{impliesfd, infdrange, ltfd_reif};
+/// fn main() {
+///     // If x < 2, then y < 2
+///     let query = proto_vulcan_query!(|x, y| {
+///         |b1, b2| {
+///             infdrange([x, y], &(0..=3)),
+///             ltfd_reif(x, 2, b1),
+///             ltfd_reif(y, 2, b2),
+///             impliesfd(b1, b2, 1),
+///             x == 1,
+///         }
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().y, 0);
+///     assert_eq!(iter.next().unwrap().y, 1);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn impliesfd<U, E, G>(x: LTerm<U, E>, y: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    BoolFd::new(Connective::Implies, x, y, b)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct BoolFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    connective: Connective,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+    b: LTerm<U, E>,
+}
+
+impl<U, E> BoolFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(
+        connective: Connective,
+        x: LTerm<U, E>,
+        y: LTerm<U, E>,
+        b: LTerm<U, E>,
+    ) -> Rc<dyn Constraint<U, E>> {
+        assert!(x.is_var() || x.is_number());
+        assert!(y.is_var() || y.is_number());
+        assert!(b.is_var() || b.is_number());
+        Rc::new(BoolFdConstraint {
+            connective,
+            x,
+            y,
+            b,
+        })
+    }
+
+    /// Returns the substitutions that make the connective entailed given the known truth
+    /// values, or `None` if nothing can be deduced yet.
+    #[allow(clippy::type_complexity)]
+    fn deduce(
+        &self,
+        x: Option<bool>,
+        y: Option<bool>,
+        b: Option<bool>,
+    ) -> Option<Vec<(&LTerm<U, E>, bool)>> {
+        match self.connective {
+            Connective::And => match (x, y, b) {
+                (Some(false), _, _) | (_, Some(false), _) => Some(vec![(&self.b, false)]),
+                (Some(true), Some(true), _) => Some(vec![(&self.b, true)]),
+                (_, _, Some(true)) => Some(vec![(&self.x, true), (&self.y, true)]),
+                (Some(true), None, Some(false)) => Some(vec![(&self.y, false)]),
+                (None, Some(true), Some(false)) => Some(vec![(&self.x, false)]),
+                _ => None,
+            },
+            Connective::Or => match (x, y, b) {
+                (Some(true), _, _) | (_, Some(true), _) => Some(vec![(&self.b, true)]),
+                (Some(false), Some(false), _) => Some(vec![(&self.b, false)]),
+                (_, _, Some(false)) => Some(vec![(&self.x, false), (&self.y, false)]),
+                (Some(false), None, Some(true)) => Some(vec![(&self.y, true)]),
+                (None, Some(false), Some(true)) => Some(vec![(&self.x, true)]),
+                _ => None,
+            },
+            Connective::Not => match (x, b) {
+                (Some(x), _) => Some(vec![(&self.b, !x)]),
+                (None, Some(b)) => Some(vec![(&self.x, !b)]),
+                _ => None,
+            },
+            Connective::Implies => match (x, y, b) {
+                (Some(false), _, _) | (_, Some(true), _) => Some(vec![(&self.b, true)]),
+                (Some(true), Some(false), _) => Some(vec![(&self.b, false)]),
+                (_, _, Some(false)) => Some(vec![(&self.x, true), (&self.y, false)]),
+                (Some(true), None, Some(true)) => Some(vec![(&self.y, true)]),
+                (None, Some(false), Some(true)) => Some(vec![(&self.x, false)]),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Returns the truth value of a term that walks to 0 or 1, `None` if it walks to a variable,
+/// and fails for all other terms.
+fn truth_value<U, E>(state: &State<U, E>, x: &LTerm<U, E>) -> Result<Option<bool>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match state.smap_ref().walk(x).as_ref() {
+        LTermInner::Var(_, _) => Ok(None),
+        LTermInner::Val(LValue::Number(0)) => Ok(Some(false)),
+        LTermInner::Val(LValue::Number(1)) => Ok(Some(true)),
+        _ => Err(()),
+    }
+}
+
+impl<U, E> Constraint<U, E> for BoolFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let x = truth_value(&state, &self.x)?;
+        let y = truth_value(&state, &self.y)?;
+        let b = truth_value(&state, &self.b)?;
+
+        match self.deduce(x, y, b) {
+            Some(substitutions) => {
+                // The connective is entailed once the deduced values have been substituted,
+                // and the constraint can be dropped.
+                let mut state = state;
+                for (u, value) in substitutions {
+                    state = state.unify(u, &LTerm::from(value as isize))?;
+                }
+                Ok(state)
+            }
+            None => Ok(state.with_constraint(self)),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        match self.connective {
+            Connective::Not => vec![self.x.clone(), self.b.clone()],
+            _ => vec![self.x.clone(), self.y.clone(), self.b.clone()],
+        }
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(BoolFdConstraint::new(
+            self.connective,
+            smap.walk_star(&self.x),
+            smap.walk_star(&self.y),
+            smap.walk_star(&self.b),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for BoolFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{andfd, impliesfd, notfd, orfd};
+    use crate::prelude::*;
+
+    type T = LTerm<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    fn truth_table<F>(f: F) -> Vec<(isize, isize, isize)>
+    where
+        F: Fn(T, T, T) -> Goal<DefaultUser, DefaultEngine<DefaultUser>>,
+    {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let b = LTerm::var("b");
+        let g = f(x.clone(), y.clone(), b.clone());
+        let query = proto_vulcan_query!(|q| {
+            q == [x, y, b],
+            g,
+        });
+        let mut table = query
+            .run()
+            .map(|r| {
+                let v =
+                    r.q.iter()
+                        .map(|t| t.get_number().unwrap())
+                        .collect::<Vec<isize>>();
+                (v[0], v[1], v[2])
+            })
+            .collect::<Vec<(isize, isize, isize)>>();
+        table.sort_unstable();
+        table
+    }
+
+    #[test]
+    fn test_andfd_1() {
+        let table = truth_table(|x, y, b| proto_vulcan!(andfd(x, y, b)));
+        assert_eq!(table, vec![(0, 0, 0), (0, 1, 0), (1, 0, 0), (1, 1, 1)]);
+    }
+
+    #[test]
+    fn test_orfd_1() {
+        let table = truth_table(|x, y, b| proto_vulcan!(orfd(x, y, b)));
+        assert_eq!(table, vec![(0, 0, 0), (0, 1, 1), (1, 0, 1), (1, 1, 1)]);
+    }
+
+    #[test]
+    fn test_impliesfd_1() {
+        let table = truth_table(|x, y, b| proto_vulcan!(impliesfd(x, y, b)));
+        assert_eq!(table, vec![(0, 0, 1), (0, 1, 1), (1, 0, 0), (1, 1, 1)]);
+    }
+
+    #[test]
+    fn test_notfd_1() {
+        let query = proto_vulcan_query!(|x, b| { notfd(x, b) });
+        let mut table = query
+            .run()
+            .map(|r| (r.x.get_number().unwrap(), r.b.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        table.sort_unstable();
+        assert_eq!(table, vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_boolfd_propagation_1() {
+        // b == 1 forces both conjuncts without labeling
+        let query = proto_vulcan_query!(|x, y| { andfd(x, y, 1) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 1);
+        assert_eq!(result.y, 1);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_boolfd_propagation_2() {
+        let query = proto_vulcan_query!(|x, y| {
+            orfd(x, y, 1),
+            x == 0,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 0);
+        assert_eq!(result.y, 1);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_boolfd_invalid_1() {
+        let query = proto_vulcan_query!(|x| { andfd(x, 2, 1) });
+        assert!(query.run().next().is_none());
+    }
+}
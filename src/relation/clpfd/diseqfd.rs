@@ -4,7 +4,7 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -176,6 +176,13 @@ where
     fn operands(&self) -> Vec<LTerm<U, E>> {
         vec![self.u.clone(), self.v.clone()]
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(DiseqFdConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+        ))
+    }
 }
 
 impl<U, E> std::fmt::Display for DiseqFdConstraint<U, E>
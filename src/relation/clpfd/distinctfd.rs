@@ -4,11 +4,26 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
+use std::collections::HashMap;
 use std::rc::Rc;
 
+/// Upper limit for the total size of variable domains for which the matching-based
+/// filtering is done. Constraints over wider domains are only forward checked.
+const MAX_VALUE_GRAPH_EDGES: usize = 1 << 16;
+
+/// Propagation strength of the all-different constraint.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Propagation {
+    /// Values of resolved variables are removed from the domains of the other variables.
+    ForwardChecking,
+    /// In addition to forward checking, values that cannot be part of any solution of the
+    /// constraint are removed from the domains using Régin's matching-based filtering.
+    DomainConsistent,
+}
+
 #[derive(Derivative)]
 #[derivative(Debug(bound = "U: User"))]
 pub struct DistinctFd<U, E>
@@ -17,6 +32,7 @@ where
     E: Engine<U>,
 {
     u: LTerm<U, E>,
+    propagation: Propagation,
 }
 
 impl<U, E> DistinctFd<U, E>
@@ -24,8 +40,11 @@ where
     U: User,
     E: Engine<U>,
 {
-    pub fn new<G: AnyGoal<U, E>>(u: LTerm<U, E>) -> InferredGoal<U, E, G> {
-        InferredGoal::new(G::dynamic(Rc::new(DistinctFd { u })))
+    pub fn new<G: AnyGoal<U, E>>(
+        u: LTerm<U, E>,
+        propagation: Propagation,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(DistinctFd { u, propagation })))
     }
 }
 
@@ -36,20 +55,38 @@ where
 {
     fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
         let u = self.u.clone();
-        match DistinctFdConstraint::new(u).run(state) {
+        match DistinctFdConstraint::new(u, self.propagation).run(state) {
             Ok(state) => Stream::unit(Box::new(state)),
             Err(_) => Stream::empty(),
         }
     }
 }
 
+/// All-different relation for finite domains.
+///
+/// Constrains the variables of list `u` to have pairwise distinct values. The domains are
+/// kept domain consistent: any value that cannot be part of a solution of the constraint is
+/// removed from the domains, which often resolves variables without labeling.
 pub fn distinctfd<U, E, G>(u: LTerm<U, E>) -> InferredGoal<U, E, G>
 where
     U: User,
     E: Engine<U>,
     G: AnyGoal<U, E>,
 {
-    DistinctFd::new(u)
+    DistinctFd::new(u, Propagation::DomainConsistent)
+}
+
+/// All-different relation for finite domains with forward checking only.
+///
+/// A cheaper variant of `distinctfd` that only removes values of already resolved
+/// variables from the domains of the other variables.
+pub fn distinctfd_fc<U, E, G>(u: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    DistinctFd::new(u, Propagation::ForwardChecking)
 }
 
 #[derive(Derivative)]
@@ -60,6 +97,7 @@ where
     E: Engine<U>,
 {
     u: LTerm<U, E>,
+    propagation: Propagation,
 }
 
 impl<U, E> DistinctFdConstraint<U, E>
@@ -67,9 +105,9 @@ where
     U: User,
     E: Engine<U>,
 {
-    pub fn new(u: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+    pub fn new(u: LTerm<U, E>, propagation: Propagation) -> Rc<dyn Constraint<U, E>> {
         assert!(u.is_list());
-        Rc::new(DistinctFdConstraint { u })
+        Rc::new(DistinctFdConstraint { u, propagation })
     }
 }
 
@@ -120,9 +158,10 @@ where
 
                 if no_duplicates {
                     // There are no duplicate constant constraints. Create a new constraint
-                    // to follow the fulfillment of the variable domain constraints.
-                    let c = DistinctFd2Constraint::new(self.u.clone(), x, n);
-                    Ok(state.with_constraint(c))
+                    // to follow the fulfillment of the variable domain constraints, and run it
+                    // once to propagate the current domains.
+                    let c = DistinctFd2Constraint::new(self.u.clone(), x, n, self.propagation);
+                    c.run(state)
                 } else {
                     // If there are duplicate constants in the array, then the constraint is
                     // already violated.
@@ -139,6 +178,13 @@ where
     fn operands(&self) -> Vec<LTerm<U, E>> {
         vec![self.u.clone()]
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(DistinctFdConstraint::new(
+            smap.walk_star(&self.u),
+            self.propagation,
+        ))
+    }
 }
 
 impl<U, E> std::fmt::Display for DistinctFdConstraint<U, E>
@@ -161,6 +207,7 @@ where
     u: LTerm<U, E>,
     y: LTerm<U, E>,
     n: Vec<isize>,
+    propagation: Propagation,
 }
 
 impl<U, E> DistinctFd2Constraint<U, E>
@@ -168,10 +215,20 @@ where
     U: User,
     E: Engine<U>,
 {
-    pub fn new(u: LTerm<U, E>, y: LTerm<U, E>, n: Vec<isize>) -> Rc<dyn Constraint<U, E>> {
+    pub fn new(
+        u: LTerm<U, E>,
+        y: LTerm<U, E>,
+        n: Vec<isize>,
+        propagation: Propagation,
+    ) -> Rc<dyn Constraint<U, E>> {
         assert!(u.is_list());
         assert!(y.is_list());
-        Rc::new(DistinctFd2Constraint { u, y, n })
+        Rc::new(DistinctFd2Constraint {
+            u,
+            y,
+            n,
+            propagation,
+        })
     }
 }
 
@@ -220,17 +277,34 @@ where
         // Create a new all-diff constraint with (hopefully) less unassociated variables in y and
         // more constants in n.
         mself.y = x.clone();
-        if mself.n.is_empty() {
-            Ok(state.with_constraint(self))
+        let propagation = mself.propagation;
+        let state = if mself.n.is_empty() {
+            state.with_constraint(self)
         } else {
             let ndomain = Rc::new(FiniteDomain::from(mself.n.clone()));
-            state.with_constraint(self).exclude_from_domain(&x, ndomain)
+            state
+                .with_constraint(self)
+                .exclude_from_domain(&x, ndomain)?
+        };
+
+        match propagation {
+            Propagation::ForwardChecking => Ok(state),
+            Propagation::DomainConsistent => filter_domains(state, &x),
         }
     }
 
     fn operands(&self) -> Vec<LTerm<U, E>> {
         self.u.iter().cloned().collect()
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(DistinctFd2Constraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.y),
+            self.n.clone(),
+            self.propagation,
+        ))
+    }
 }
 
 impl<U, E> std::fmt::Display for DistinctFd2Constraint<U, E>
@@ -243,13 +317,275 @@ where
     }
 }
 
+/// Removes values that cannot be part of any solution of the all-different constraint over
+/// the terms of list `x` from their domains.
+///
+/// The filtering follows Régin: the variables and their domain values form a bipartite value
+/// graph. The constraint can be satisfied only if the graph has a matching that covers all
+/// variables. Given such maximum matching, a value is supported if the edge between the
+/// variable and the value belongs to some maximum matching; i.e. it is in the matching, on an
+/// alternating cycle, or on an even alternating path starting from a free value.
+fn filter_domains<U, E>(state: State<U, E>, x: &LTerm<U, E>) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let smap = state.get_smap();
+    let dstore = state.get_dstore();
+
+    let mut vars = vec![];
+    let mut domains = vec![];
+    let mut edge_count: usize = 0;
+    for y in x {
+        let ywalk = smap.walk(y);
+        let domain = match ywalk.as_ref() {
+            LTermInner::Var(_, _) => match dstore.get(ywalk) {
+                Some(domain) => Rc::clone(domain),
+                // Matching can be done only when all variables have domains.
+                None => return Ok(state),
+            },
+            LTermInner::Val(LValue::Number(u)) => Rc::new(FiniteDomain::from(*u)),
+            _ => panic!("Invalid LTerm {:?} in constraint", ywalk),
+        };
+        if ywalk.is_var() && vars.contains(ywalk) {
+            // The same variable appears twice in the list and cannot be distinct from itself.
+            return Err(());
+        }
+        edge_count = edge_count.saturating_add(domain.size());
+        vars.push(ywalk.clone());
+        domains.push(domain);
+    }
+
+    if vars.len() < 2 || edge_count > MAX_VALUE_GRAPH_EDGES {
+        return Ok(state);
+    }
+
+    let graph = ValueGraph::new(&domains);
+    let matching = graph.maximum_matching().ok_or(())?;
+    let supported = graph.supported_values(&matching);
+
+    let mut state = state;
+    for ((var, domain), values) in vars.iter().zip(domains.iter()).zip(supported) {
+        if values.len() < domain.size() {
+            // Earlier domain updates may have resolved the variable; walk it again.
+            let varwalk = state.smap_ref().walk(var).clone();
+            state = state.process_domain(&varwalk, Rc::new(FiniteDomain::from(values)))?;
+        }
+    }
+    Ok(state)
+}
+
+/// Bipartite graph between variables and the values of their domains.
+struct ValueGraph {
+    /// Value indices adjacent to each variable
+    adjacency: Vec<Vec<usize>>,
+    values: Vec<isize>,
+}
+
+/// Maximum matching of a value graph that covers all variables.
+struct Matching {
+    /// Matched value index of each variable
+    var_value: Vec<usize>,
+    /// Matched variable index of each value, if any
+    value_var: Vec<Option<usize>>,
+}
+
+impl ValueGraph {
+    fn new(domains: &[Rc<FiniteDomain>]) -> ValueGraph {
+        let mut index = HashMap::new();
+        let mut values = vec![];
+        let adjacency = domains
+            .iter()
+            .map(|domain| {
+                domain
+                    .iter()
+                    .map(|d| {
+                        *index.entry(d).or_insert_with(|| {
+                            values.push(d);
+                            values.len() - 1
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        ValueGraph { adjacency, values }
+    }
+
+    /// Finds a matching that covers all variables with augmenting paths, or `None` if such
+    /// matching does not exist.
+    fn maximum_matching(&self) -> Option<Matching> {
+        let mut value_var = vec![None; self.values.len()];
+        for var in 0..self.adjacency.len() {
+            let mut visited = vec![false; self.values.len()];
+            if !self.augment(var, &mut visited, &mut value_var) {
+                return None;
+            }
+        }
+
+        let mut var_value = vec![0; self.adjacency.len()];
+        for (value, var) in value_var.iter().enumerate() {
+            if let Some(var) = var {
+                var_value[*var] = value;
+            }
+        }
+        Some(Matching {
+            var_value,
+            value_var,
+        })
+    }
+
+    fn augment(
+        &self,
+        var: usize,
+        visited: &mut Vec<bool>,
+        value_var: &mut Vec<Option<usize>>,
+    ) -> bool {
+        for &value in self.adjacency[var].iter() {
+            if !visited[value] {
+                visited[value] = true;
+                let augmented = match value_var[value] {
+                    Some(other) => self.augment(other, visited, value_var),
+                    None => true,
+                };
+                if augmented {
+                    value_var[value] = Some(var);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns sorted supported values for each variable.
+    fn supported_values(&self, matching: &Matching) -> Vec<Vec<isize>> {
+        // Directed graph where matched edges go from variables to values, and free edges from
+        // values to variables. Variable `i` is node `i` and value `j` is node `nvars + j`.
+        let nvars = self.adjacency.len();
+        let mut successors = vec![vec![]; nvars + self.values.len()];
+        for (var, adjacent) in self.adjacency.iter().enumerate() {
+            for &value in adjacent.iter() {
+                if matching.var_value[var] == value {
+                    successors[var].push(nvars + value);
+                } else {
+                    successors[nvars + value].push(var);
+                }
+            }
+        }
+
+        // Nodes on even alternating paths starting from free values
+        let mut reachable = vec![false; successors.len()];
+        let mut queue = matching
+            .value_var
+            .iter()
+            .enumerate()
+            .filter(|(_, var)| var.is_none())
+            .map(|(value, _)| nvars + value)
+            .collect::<Vec<usize>>();
+        for node in queue.iter() {
+            reachable[*node] = true;
+        }
+        while let Some(node) = queue.pop() {
+            for &next in successors[node].iter() {
+                if !reachable[next] {
+                    reachable[next] = true;
+                    queue.push(next);
+                }
+            }
+        }
+
+        let component = strongly_connected_components(&successors);
+
+        self.adjacency
+            .iter()
+            .enumerate()
+            .map(|(var, adjacent)| {
+                let mut supported = adjacent
+                    .iter()
+                    .filter(|&&value| {
+                        matching.var_value[var] == value
+                            || reachable[nvars + value]
+                            || component[var] == component[nvars + value]
+                    })
+                    .map(|&value| self.values[value])
+                    .collect::<Vec<isize>>();
+                supported.sort_unstable();
+                supported
+            })
+            .collect()
+    }
+}
+
+/// Tarjan's algorithm for strongly connected components. Returns component index of each node.
+fn strongly_connected_components(successors: &[Vec<usize>]) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+    let n = successors.len();
+    let mut index = vec![UNVISITED; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut component = vec![UNVISITED; n];
+    let mut stack = vec![];
+    let mut next_index = 0;
+    let mut next_component = 0;
+
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // Explicit call stack of (node, next successor position) to avoid deep recursion.
+        let mut call_stack = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&(node, position)) = call_stack.last() {
+            if position < successors[node].len() {
+                call_stack.last_mut().unwrap().1 += 1;
+                let next = successors[node][position];
+                if index[next] == UNVISITED {
+                    index[next] = next_index;
+                    lowlink[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    call_stack.push((next, 0));
+                } else if on_stack[next] {
+                    lowlink[node] = lowlink[node].min(index[next]);
+                }
+            } else {
+                let _ = call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[node]);
+                }
+                if lowlink[node] == index[node] {
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component[member] = next_component;
+                        if member == node {
+                            break;
+                        }
+                    }
+                    next_component += 1;
+                }
+            }
+        }
+    }
+    component
+}
+
 #[cfg(test)]
 mod tests {
-    use super::distinctfd;
+    use super::{distinctfd, distinctfd_fc, DistinctFdConstraint, Propagation};
+    use crate::engine::DefaultEngine;
     use crate::prelude::*;
     use crate::relation::clpfd::diseqfd::diseqfd;
     use crate::relation::clpfd::infd::{infd, infdrange};
     use crate::relation::clpfd::ltefd::ltefd;
+    use crate::state::{FiniteDomain, State};
+    use std::rc::Rc;
 
     #[test]
     fn test_distinctfd_1() {
@@ -338,4 +674,83 @@ mod tests {
         let mut iter = query.run();
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_distinctfd_8() {
+        // Pigeonhole: x and y use up values 1 and 2, so z must be 3 without any labeling.
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let z = LTerm::var("z");
+        let state = State::<DefaultUser, DefaultEngine<DefaultUser>>::new(Default::default())
+            .process_domain(&x, Rc::new(FiniteDomain::from(vec![1, 2])))
+            .unwrap()
+            .process_domain(&y, Rc::new(FiniteDomain::from(vec![1, 2])))
+            .unwrap()
+            .process_domain(&z, Rc::new(FiniteDomain::from(1..=3)))
+            .unwrap();
+
+        let c = DistinctFdConstraint::new(lterm!([x, y, z]), Propagation::DomainConsistent);
+        let state = c.run(state).unwrap();
+        assert_eq!(state.smap_ref().walk(&z), &3);
+
+        let state = State::<DefaultUser, DefaultEngine<DefaultUser>>::new(Default::default())
+            .process_domain(&x, Rc::new(FiniteDomain::from(vec![1, 2])))
+            .unwrap()
+            .process_domain(&y, Rc::new(FiniteDomain::from(vec![1, 2])))
+            .unwrap()
+            .process_domain(&z, Rc::new(FiniteDomain::from(1..=3)))
+            .unwrap();
+
+        // Forward checking cannot deduce the value of z
+        let c = DistinctFdConstraint::new(lterm!([x, y, z]), Propagation::ForwardChecking);
+        let state = c.run(state).unwrap();
+        assert!(state.smap_ref().walk(&z).is_var());
+        assert_eq!(state.dstore_ref().get(&z).unwrap().size(), 3);
+    }
+
+    #[test]
+    fn test_distinctfd_9() {
+        // Three variables over two values fails already in propagation
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let z = LTerm::var("z");
+        let mut state = State::<DefaultUser, DefaultEngine<DefaultUser>>::new(Default::default());
+        for v in [&x, &y, &z].iter() {
+            state = state
+                .process_domain(v, Rc::new(FiniteDomain::from(vec![1, 2])))
+                .unwrap();
+        }
+        let c = DistinctFdConstraint::new(lterm!([x, y, z]), Propagation::DomainConsistent);
+        assert!(c.run(state).is_err());
+    }
+
+    #[test]
+    fn test_distinctfd_10() {
+        // Values 1 and 2 are taken by a and b, which leaves {3, 4} for c and d.
+        let query = proto_vulcan_query!(|q| {
+            |a, b, c, d| {
+                infd([a, b], &[1, 2]),
+                infd([c, d], &[1, 2, 3, 4]),
+                distinctfd([a, b, c, d]),
+                ltefd(c, d),
+                q == [a, b, c, d],
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3, 4]));
+        assert_eq!(iter.next().unwrap().q, lterm!([2, 1, 3, 4]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_fc_1() {
+        let query = proto_vulcan_query!(|q| {
+            |x, y, z| {
+                infdrange([x, y, z], &(0..=2)),
+                distinctfd_fc([x, y, z]),
+                q == [x, y, z],
+            }
+        });
+        assert_eq!(query.run().count(), 6);
+    }
 }
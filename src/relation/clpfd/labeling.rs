@@ -0,0 +1,474 @@
+//! Labeling of finite domain variables
+//!
+//! Without explicit labeling, the domains of result variables are enumerated implicitly at
+//! reification, one variable at a time in order. `labeling(options, vars)` assigns values to
+//! the variables under control of search strategy options:
+//!
+//! * Variable selection: `Leftmost` (default), `FirstFail`, `MostConstrained`, `Min`, `Max`
+//! * Value order: `Up` (default), `Down`
+//! * Branching: `Step` (default), `Enum`, `Bisect`
+//!
+//! If an option is given more than once in the same category, the last one applies.
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, DFSGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{FiniteDomain, State};
+use crate::stream::{LazyStream, Stream};
+use crate::user::User;
+use std::rc::Rc;
+
+/// Labeling search strategy option.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LabelingOption {
+    /// Label the variables in the order they are given.
+    Leftmost,
+    /// Label the variable with the smallest domain first.
+    FirstFail,
+    /// Label the variable with the smallest domain first, breaking ties by selecting the
+    /// variable that is involved in the most constraints.
+    MostConstrained,
+    /// Label the variable with the smallest lower bound first.
+    Min,
+    /// Label the variable with the largest upper bound first.
+    Max,
+    /// Try values in ascending order.
+    Up,
+    /// Try values in descending order.
+    Down,
+    /// For value `v` of variable `x`, branch into `x == v` and `x != v`.
+    Step,
+    /// Branch into `x == v` for every value `v` in the domain of `x`.
+    Enum,
+    /// Split the domain of `x` in half and branch into the lower and upper halves.
+    Bisect,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum VariableSelection {
+    Leftmost,
+    FirstFail,
+    MostConstrained,
+    Min,
+    Max,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Branching {
+    Step,
+    Enum,
+    Bisect,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct LabelingOptions {
+    selection: VariableSelection,
+    descending: bool,
+    branching: Branching,
+}
+
+impl From<&[LabelingOption]> for LabelingOptions {
+    fn from(options: &[LabelingOption]) -> LabelingOptions {
+        let mut labeling_options = LabelingOptions {
+            selection: VariableSelection::Leftmost,
+            descending: false,
+            branching: Branching::Step,
+        };
+        for option in options {
+            match option {
+                LabelingOption::Leftmost => {
+                    labeling_options.selection = VariableSelection::Leftmost
+                }
+                LabelingOption::FirstFail => {
+                    labeling_options.selection = VariableSelection::FirstFail
+                }
+                LabelingOption::MostConstrained => {
+                    labeling_options.selection = VariableSelection::MostConstrained
+                }
+                LabelingOption::Min => labeling_options.selection = VariableSelection::Min,
+                LabelingOption::Max => labeling_options.selection = VariableSelection::Max,
+                LabelingOption::Up => labeling_options.descending = false,
+                LabelingOption::Down => labeling_options.descending = true,
+                LabelingOption::Step => labeling_options.branching = Branching::Step,
+                LabelingOption::Enum => labeling_options.branching = Branching::Enum,
+                LabelingOption::Bisect => labeling_options.branching = Branching::Bisect,
+            }
+        }
+        labeling_options
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+pub struct Labeling<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    options: LabelingOptions,
+    vars: LTerm<U, E>,
+}
+
+impl<U, E> Labeling<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        options: &[LabelingOption],
+        vars: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Labeling {
+            options: LabelingOptions::from(options),
+            vars,
+        })))
+    }
+
+    /// Returns the unbound variables to be labeled, with their domains, or `None` if some of
+    /// the terms is not a number or a variable.
+    #[allow(clippy::type_complexity)]
+    fn unbound_vars(&self, state: &State<U, E>) -> Option<Vec<(LTerm<U, E>, Rc<FiniteDomain>)>> {
+        let vars = state.smap_ref().walk_star(&self.vars);
+        let terms = if vars.is_list() {
+            vars.iter().cloned().collect()
+        } else {
+            vec![vars]
+        };
+
+        let mut unbound = vec![];
+        for term in terms {
+            match term.as_ref() {
+                LTermInner::Var(_, _) => match state.dstore_ref().get(&term) {
+                    Some(domain) => unbound.push((term.clone(), Rc::clone(domain))),
+                    None => panic!(
+                        "Error: Variable {:?} not bound to any domain in labeling.",
+                        term
+                    ),
+                },
+                LTermInner::Val(LValue::Number(_)) => (),
+                _ => return None,
+            }
+        }
+        Some(unbound)
+    }
+
+    /// Number of constraints in the store that refer to `x`.
+    fn constraint_count(state: &State<U, E>, x: &LTerm<U, E>) -> usize {
+        state
+            .cstore_ref()
+            .iter()
+            .filter(|c| {
+                c.operands()
+                    .iter()
+                    .any(|operand| state.smap_ref().walk(operand) == x)
+            })
+            .count()
+    }
+
+    /// Selects the next variable to label. Ties are broken by the order of the variables.
+    fn select<'a>(
+        &self,
+        state: &State<U, E>,
+        unbound: &'a [(LTerm<U, E>, Rc<FiniteDomain>)],
+    ) -> &'a (LTerm<U, E>, Rc<FiniteDomain>) {
+        let mut selected = &unbound[0];
+        match self.options.selection {
+            VariableSelection::Leftmost => (),
+            VariableSelection::FirstFail => {
+                for candidate in &unbound[1..] {
+                    if candidate.1.size() < selected.1.size() {
+                        selected = candidate;
+                    }
+                }
+            }
+            VariableSelection::MostConstrained => {
+                let mut selected_count = Labeling::constraint_count(state, &selected.0);
+                for candidate in &unbound[1..] {
+                    if candidate.1.size() < selected.1.size() {
+                        selected = candidate;
+                        selected_count = Labeling::constraint_count(state, &selected.0);
+                    } else if candidate.1.size() == selected.1.size() {
+                        let count = Labeling::constraint_count(state, &candidate.0);
+                        if count > selected_count {
+                            selected = candidate;
+                            selected_count = count;
+                        }
+                    }
+                }
+            }
+            VariableSelection::Min => {
+                for candidate in &unbound[1..] {
+                    if candidate.1.min() < selected.1.min() {
+                        selected = candidate;
+                    }
+                }
+            }
+            VariableSelection::Max => {
+                for candidate in &unbound[1..] {
+                    if candidate.1.max() > selected.1.max() {
+                        selected = candidate;
+                    }
+                }
+            }
+        }
+        selected
+    }
+
+    /// Splits the domain into the branches to be searched, in search order.
+    fn branches(&self, domain: &FiniteDomain) -> Vec<Rc<FiniteDomain>> {
+        match self.options.branching {
+            Branching::Step => {
+                let value = if self.options.descending {
+                    domain.max()
+                } else {
+                    domain.min()
+                };
+                let value_domain = FiniteDomain::from(value);
+                match domain.diff(&value_domain) {
+                    Some(rest) => vec![Rc::new(value_domain), Rc::new(rest)],
+                    None => vec![Rc::new(value_domain)],
+                }
+            }
+            Branching::Enum => {
+                let mut values = domain
+                    .iter()
+                    .map(|d| Rc::new(FiniteDomain::from(d)))
+                    .collect::<Vec<Rc<FiniteDomain>>>();
+                if self.options.descending {
+                    values.reverse();
+                }
+                values
+            }
+            Branching::Bisect => {
+                let (min, max) = (domain.min(), domain.max());
+                // Computed in wider type to avoid overflow at the ends of `isize`.
+                let mid = ((min as i128 + max as i128).div_euclid(2)) as isize;
+                let lower = domain.intersect(FiniteDomain::from(min..=mid));
+                let upper = domain.intersect(FiniteDomain::from(mid + 1..=max));
+                let mut halves = lower
+                    .into_iter()
+                    .chain(upper)
+                    .map(Rc::new)
+                    .collect::<Vec<Rc<FiniteDomain>>>();
+                if self.options.descending {
+                    halves.reverse();
+                }
+                halves
+            }
+        }
+    }
+}
+
+impl<U, E> Solve<U, E> for Labeling<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let unbound = match self.unbound_vars(&state) {
+            Some(unbound) => unbound,
+            None => return Stream::empty(),
+        };
+
+        if unbound.is_empty() {
+            return Stream::unit(Box::new(state));
+        }
+
+        // The branches are searched depth-first, in order.
+        let (x, domain) = self.select(&state, &unbound).clone();
+        let mut stream = Stream::empty();
+        for branch_domain in self.branches(&domain).into_iter().rev() {
+            let branch = match state.clone().process_domain(&x, branch_domain) {
+                Ok(state) => {
+                    Stream::pause_dfs(Box::new(state), DFSGoal::dynamic(Rc::new(self.clone())))
+                }
+                Err(_) => Stream::empty(),
+            };
+            stream = Stream::mplus_dfs(branch, LazyStream::delay(stream));
+        }
+        stream
+    }
+}
+
+/// Assigns values to finite domain variables using a search strategy.
+///
+/// The variables `vars` can be a list of variables or a single variable. All unbound
+/// variables must have domains.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, labeling, ltfd, LabelingOption};
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, y| {
+///         infdrange([x, y], &(0..=2)),
+///         ltfd(x, y),
+///         labeling(&[LabelingOption::FirstFail, LabelingOption::Down], [x, y]),
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, 1);
+///     assert_eq!(result.y, 2);
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, 0);
+///     assert_eq!(result.y, 2);
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, 0);
+///     assert_eq!(result.y, 1);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn labeling<U, E, G>(options: &[LabelingOption], vars: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Labeling::new(options, vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{labeling, LabelingOption};
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::{infd, infdrange};
+    use crate::relation::clpfd::ltfd::ltfd;
+    use crate::relation::clpfd::plusfd::plusfd;
+
+    #[test]
+    fn test_labeling_1() {
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(1..=4)),
+            labeling(&[], [x]),
+        });
+        let iter = query.run();
+        let values = iter
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(values, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_labeling_2() {
+        let query = proto_vulcan_query!(|x| {
+            infd(x, &[1, 3, 5, 7]),
+            labeling(&[LabelingOption::Down], x),
+        });
+        let iter = query.run();
+        let values = iter
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(values, vec![7, 5, 3, 1]);
+    }
+
+    #[test]
+    fn test_labeling_3() {
+        for branching in &[
+            LabelingOption::Step,
+            LabelingOption::Enum,
+            LabelingOption::Bisect,
+        ] {
+            for order in &[LabelingOption::Up, LabelingOption::Down] {
+                let query = proto_vulcan_query!(|x| {
+                    infdrange(x, &(-5..=5)),
+                    labeling(&[*branching, *order], x),
+                });
+                let iter = query.run();
+                let values = iter
+                    .map(|r| r.x.get_number().unwrap())
+                    .collect::<Vec<isize>>();
+                let mut expected = (-5..=5).collect::<Vec<isize>>();
+                if *order == LabelingOption::Down {
+                    expected.reverse();
+                }
+                assert_eq!(values, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_labeling_4() {
+        // First-fail labels y with the smaller domain first.
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(0..=2)),
+            infdrange(y, &(0..=1)),
+            labeling(&[LabelingOption::FirstFail], [x, y]),
+        });
+        let iter = query.run();
+        let values = iter
+            .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        assert_eq!(values, vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_labeling_5() {
+        // Max selects the variable with the largest upper bound first.
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(0..=1)),
+            infdrange(y, &(5..=6)),
+            labeling(&[LabelingOption::Max], [x, y]),
+        });
+        let iter = query.run();
+        let values = iter
+            .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        assert_eq!(values, vec![(0, 5), (1, 5), (0, 6), (1, 6)]);
+    }
+
+    #[test]
+    fn test_labeling_6() {
+        // Most-constrained breaks the domain size tie in favor of z.
+        let query = proto_vulcan_query!(|x, z| {
+            |w| {
+                infdrange([x, z, w], &(0..=1)),
+                plusfd(z, w, 1),
+                labeling(&[LabelingOption::MostConstrained], [x, z]),
+            }
+        });
+        let iter = query.run();
+        let values = iter
+            .map(|r| (r.x.get_number().unwrap(), r.z.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        assert_eq!(values, vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_labeling_7() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange([x, y], &(0..=3)),
+            ltfd(x, y),
+            y == 1,
+            labeling(&[LabelingOption::Bisect], [x, y]),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 0);
+        assert_eq!(result.y, 1);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_labeling_8() {
+        let query = proto_vulcan_query!(|x| {
+            x == "foo",
+            labeling(&[], x),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_labeling_9() {
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(isize::MAX - 2..=isize::MAX)),
+            labeling(&[LabelingOption::Bisect, LabelingOption::Down], x),
+        });
+        let iter = query.run();
+        let values = iter
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(values, vec![isize::MAX, isize::MAX - 1, isize::MAX - 2]);
+    }
+}
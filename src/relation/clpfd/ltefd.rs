@@ -3,7 +3,7 @@ use crate::engine::Engine;
 use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::LTerm;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, SResult, State};
+use crate::state::{Constraint, SMap, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -147,6 +147,13 @@ where
     fn operands(&self) -> Vec<LTerm<U, E>> {
         vec![self.u.clone(), self.v.clone()]
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(LessThanOrEqualFdConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+        ))
+    }
 }
 
 impl<U, E> std::fmt::Display for LessThanOrEqualFdConstraint<U, E>
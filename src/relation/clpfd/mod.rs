@@ -4,13 +4,255 @@
 //! `ltfd`, `plusfd`, `minusfd` and `timesfd`. Domains are assigned to variables with `infd` or
 //! `infdrange`. See `n-queens`-example for code using finite-domain constraints.
 //!
+//! The all-different constraint `distinctfd` uses matching-based filtering that keeps the
+//! domains consistent; `distinctfd_fc` is a cheaper variant that only does forward checking.
+//!
+//! Constraints can be reified into 0/1 variables with `eqfd_reif`, `diseqfd_reif`, `ltfd_reif`
+//! and `ltefd_reif`, and the truth values combined with `andfd`, `orfd`, `notfd` and `impliesfd`.
+//!
+//! Further arithmetic is provided by `divfd`, `modfd`, `absfd`, `minfd`, `maxfd` and `powfd`.
+//!
+//! ## Arithmetic expressions
+//! Within `proto_vulcan!` the constraints can also be written as arithmetic expressions, that are
+//! lowered into the relations above. The relational operators are `#=`, `#!=`, `#<`, `#<=`, `#>`
+//! and `#>=`, and the expressions can use `+`, `-`, `*`, `/`, `%`, `^`, `abs(e)`, `min(e, e)` and
+//! `max(e, e)` on variables, integer literals and `{expr}`-quoted Rust expressions.
+//!
+//! The expressions are finite-domain only: they are not lowered into the CLP(Z) relations, and
+//! `plusz` and `timesz` must be used directly for arbitrary-precision integers. The values of
+//! subexpressions are held in fresh temporary variables with the domain of all `isize` values,
+//! which the constraints narrow from the domains of the operands. As with the relations, the
+//! variables should be given domains before the expression is constrained; a temporary of an
+//! operand without a domain keeps the full domain, and is enumerated at reification.
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::infdrange;
+//! fn main() {
+//!     let query = proto_vulcan_query!(|x, y, z| {
+//!         infdrange([x, y], &(0..=3)),
+//!         infdrange(z, &(0..=100)),
+//!         z #= (x + y) * 2,
+//!         x #< y,
+//!         z #!= 6,
+//!     });
+//!     for result in query.run() {
+//!         let x = result.x.get_number().unwrap();
+//!         let y = result.y.get_number().unwrap();
+//!         let z = result.z.get_number().unwrap();
+//!         assert_eq!(z, (x + y) * 2);
+//!         assert!(x < y && z != 6);
+//!     }
+//!     assert_eq!(query.run().count(), 4);
+//! }
+//! ```
+//!
+//! By default, the domains of result variables are enumerated in ascending order at reification.
+//! The `labeling` relation gives control over the order in which variables and values are searched.
+//!
 
+pub mod arithfd;
+pub mod boolfd;
 pub mod diseqfd;
 pub mod distinctfd;
 pub mod domfd;
 pub mod infd;
+pub mod labeling;
 pub mod ltefd;
 pub mod ltfd;
 pub mod minusfd;
 pub mod plusfd;
+pub mod reified;
 pub mod timesfd;
+
+use crate::engine::Engine;
+use crate::lterm::LTerm;
+use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::user::User;
+use std::cmp::{max, min};
+use std::rc::Rc;
+
+/// Number of operands that are bound to values.
+pub(crate) fn bound_operands<U, E>(state: &State<U, E>, operands: &[LTerm<U, E>]) -> usize
+where
+    U: User,
+    E: Engine<U>,
+{
+    operands
+        .iter()
+        .filter(|x| !state.smap_ref().walk(x).is_var())
+        .count()
+}
+
+/// Converts bounds computed in wider type into a domain, or `None` if the bounds are empty
+/// or outside the range of `isize`.
+fn bounded_domain(lo: i128, hi: i128) -> Option<FiniteDomain> {
+    let lo = max(lo, isize::MIN as i128);
+    let hi = min(hi, isize::MAX as i128);
+    if lo <= hi {
+        Some(FiniteDomain::from(lo as isize..=hi as isize))
+    } else {
+        None
+    }
+}
+
+/// Narrows the domain of `x` to bounds that are computed in `i128`, so that the arithmetic on
+/// the bounds of `isize` domains cannot overflow. Fails if no value of `isize` is within the
+/// bounds.
+pub(crate) fn narrow_bounds<U, E>(
+    state: State<U, E>,
+    x: &LTerm<U, E>,
+    lo: i128,
+    hi: i128,
+) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let domain = bounded_domain(lo, hi).ok_or(())?;
+    state.process_domain(x, Rc::new(domain))
+}
+
+/// Stores the constraint after it has narrowed the domains of its operands, or runs it again
+/// if narrowing bound more of the operands than the `bound` before it. The constraint is not in
+/// the store while it is running, so it is not run by the bindings that it causes itself.
+pub(crate) fn store_or_rerun<U, E, C>(
+    constraint: Rc<C>,
+    state: State<U, E>,
+    bound: usize,
+) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    C: Constraint<U, E>,
+{
+    if bound_operands(&state, &constraint.operands()) > bound {
+        constraint.run(state)
+    } else {
+        Ok(state.with_constraint(constraint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::infd::{infd, infdrange};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_arith_eq_1() {
+        let query = proto_vulcan_query!(|x, y, z| {
+            infdrange([x, y], &(0..=2)),
+            infdrange(z, &(0..=100)),
+            z #= (x + y) * 2,
+        });
+        let results = query
+            .run()
+            .map(|r| {
+                (
+                    r.x.get_number().unwrap(),
+                    r.y.get_number().unwrap(),
+                    r.z.get_number().unwrap(),
+                )
+            })
+            .collect::<Vec<(isize, isize, isize)>>();
+        assert_eq!(results.len(), 9);
+        assert!(results.iter().all(|(x, y, z)| *z == (x + y) * 2));
+    }
+
+    #[test]
+    fn test_arith_eq_2() {
+        // Both sides are expressions, and precedence of the operators is respected.
+        let query = proto_vulcan_query!(|x, y, z| {
+            infdrange([x, y], &(0..=3)),
+            infdrange(z, &(-3..=3)),
+            x * 2 - y #= -z + 3 * 2 ^ 2 % 5,
+            x #>= 1,
+        });
+        let mut results = query
+            .run()
+            .map(|r| {
+                (
+                    r.x.get_number().unwrap(),
+                    r.y.get_number().unwrap(),
+                    r.z.get_number().unwrap(),
+                )
+            })
+            .collect::<Vec<(isize, isize, isize)>>();
+        results.sort_unstable();
+        // 3 * 2 ^ 2 % 5 == (3 * (2 ^ 2)) % 5 == 2
+        assert_eq!(
+            results,
+            vec![
+                (1, 0, 0),
+                (1, 1, 1),
+                (1, 2, 2),
+                (1, 3, 3),
+                (2, 0, -2),
+                (2, 1, -1),
+                (2, 2, 0),
+                (2, 3, 1),
+                (3, 1, -3),
+                (3, 2, -2),
+                (3, 3, -1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arith_eq_3() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange([x, y], &(-5..=5)),
+            abs(x) #= max(y, 3) - min(y, 1),
+            y #> {2 + 1},
+        });
+        let mut results = query
+            .run()
+            .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        results.sort_unstable();
+        assert_eq!(results, vec![(-4, 5), (-3, 4), (3, 4), (4, 5)]);
+    }
+
+    #[test]
+    fn test_arith_compare_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infd([x, y], &[1, 2, 3]),
+            x + 1 #< y,
+            x #!= 3,
+            y #<= 3,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 1);
+        assert_eq!(result.y, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_arith_compare_2() {
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(0..=10)),
+            x / 3 #> 2,
+            x #!= 10,
+        });
+        let results = query
+            .run()
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(results, vec![9]);
+    }
+
+    #[test]
+    fn test_arith_literal_division() {
+        // Integer literals are divided, instead of forming rational literals.
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(0..=10)),
+            x #= 7 / 2,
+        });
+        let results = query
+            .run()
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(results, vec![3]);
+    }
+}
@@ -4,7 +4,7 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -90,6 +90,7 @@ where
     fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
         let smap = state.get_smap();
         let dstore = state.get_dstore();
+        let bound = super::bound_operands(&state, &self.operands());
 
         let uwalk = smap.walk(&self.u);
         let singleton_udomain;
@@ -127,8 +128,12 @@ where
         // If all operators are bound to numbers, then we can drop the constraint or fail if
         // constraint is not fulfilled.
         if uwalk.is_number() && vwalk.is_number() && wwalk.is_number() {
-            if uwalk.get_number().unwrap() + vwalk.get_number().unwrap()
-                == wwalk.get_number().unwrap()
+            // The sum overflows only if it is not equal to any number.
+            if uwalk
+                .get_number()
+                .unwrap()
+                .checked_add(vwalk.get_number().unwrap())
+                == wwalk.get_number()
             {
                 return Ok(state);
             } else {
@@ -138,12 +143,9 @@ where
 
         match (maybe_udomain, maybe_vdomain, maybe_wdomain) {
             (Some(udomain), Some(vdomain), Some(wdomain)) => {
-                let umin = udomain.min();
-                let umax = udomain.max();
-                let vmin = vdomain.min();
-                let vmax = vdomain.max();
-                let wmin = wdomain.min();
-                let wmax = wdomain.max();
+                let (umin, umax) = (udomain.min() as i128, udomain.max() as i128);
+                let (vmin, vmax) = (vdomain.min() as i128, vdomain.max() as i128);
+                let (wmin, wmax) = (wdomain.min() as i128, wdomain.max() as i128);
                 // The constraint is: u + v = w
                 //
                 // Given domains for u and v, we can then deduce that the domain of w must be
@@ -153,26 +155,10 @@ where
                 // Same application of constraining domain is done for the other two variables.
                 //
                 // The constraint is not dropped until all variables converge into numbers.
-                Ok(state
-                    .process_domain(
-                        &wwalk,
-                        Rc::new(FiniteDomain::from(
-                            umin.saturating_add(vmin)..=umax.saturating_add(vmax),
-                        )),
-                    )?
-                    .process_domain(
-                        &uwalk,
-                        Rc::new(FiniteDomain::from(
-                            wmin.saturating_sub(vmax)..=wmax.saturating_sub(vmin),
-                        )),
-                    )?
-                    .process_domain(
-                        &vwalk,
-                        Rc::new(FiniteDomain::from(
-                            wmin.saturating_sub(umax)..=wmax.saturating_sub(umin),
-                        )),
-                    )?
-                    .with_constraint(self))
+                let state = super::narrow_bounds(state, wwalk, umin + vmin, umax + vmax)?;
+                let state = super::narrow_bounds(state, uwalk, wmin - vmax, wmax - vmin)?;
+                let state = super::narrow_bounds(state, vwalk, wmin - umax, wmax - umin)?;
+                super::store_or_rerun(self, state, bound)
             }
             // If all operators do not yet have domains, then keep the constraint until it can
             // be used to constrain some domains.
@@ -183,6 +169,14 @@ where
     fn operands(&self) -> Vec<LTerm<U, E>> {
         vec![self.u.clone(), self.v.clone(), self.w.clone()]
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(PlusFdConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+            smap.walk_star(&self.w),
+        ))
+    }
 }
 
 impl<U, E> std::fmt::Display for PlusFdConstraint<U, E>
@@ -249,4 +243,36 @@ mod tests {
         });
         assert_eq!(expected.len(), 0);
     }
+
+    #[test]
+    fn test_plusfd_3() {
+        // Sums at the end of isize
+        let query = proto_vulcan_query!(|x, y, z| {
+            infdrange(x, &(isize::MAX - 2..=isize::MAX)),
+            infdrange(y, &(1..=5)),
+            infdrange(z, &(isize::MIN..=isize::MAX)),
+            plusfd(x, y, z),
+        });
+        let results = query
+            .run()
+            .map(|r| (r.x.get_number().unwrap(), r.y.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|(x, y)| x.checked_add(*y).is_some()));
+
+        // The sum is outside the range of isize.
+        let query = proto_vulcan_query!(|x, y, z| {
+            infdrange(x, &(isize::MAX - 1..=isize::MAX)),
+            infdrange(y, &(2..=3)),
+            infdrange(z, &(isize::MIN..=isize::MAX)),
+            plusfd(x, y, z),
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|z| {
+            infdrange(z, &(isize::MIN..=isize::MAX)),
+            plusfd({isize::MAX}, 1, z),
+        });
+        assert!(query.run().next().is_none());
+    }
 }
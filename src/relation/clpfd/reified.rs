@@ -0,0 +1,364 @@
+//! Reified finite domain constraints
+//!
+//! A reified constraint associates the truth value of a finite domain constraint with a 0/1
+//! variable `b`: `b` is 1 if and only if the constraint holds. The boolean variable can be
+//! constrained further with the connectives of `boolfd`, or summed with `plusfd` to count how
+//! many conditions are satisfied.
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::relation::clpfd::diseqfd::DiseqFdConstraint;
+use crate::relation::clpfd::ltefd::LessThanOrEqualFdConstraint;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+/// Finite domain constraints that can be reified.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReifiedRelation {
+    /// u == v
+    Eq,
+    /// u != v
+    Diseq,
+    /// u < v
+    Lt,
+    /// u <= v
+    Lte,
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ReifiedFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    relation: ReifiedRelation,
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    b: LTerm<U, E>,
+}
+
+impl<U, E> ReifiedFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        relation: ReifiedRelation,
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        b: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(ReifiedFd { relation, u, v, b })))
+    }
+}
+
+impl<U, E> Solve<U, E> for ReifiedFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let c = ReifiedFdConstraint::new(
+            self.relation,
+            self.u.clone(),
+            self.v.clone(),
+            self.b.clone(),
+        );
+        let bwalk = state.smap_ref().walk(&self.b).clone();
+        match state
+            .process_domain(&bwalk, Rc::new(FiniteDomain::from(0..=1)))
+            .and_then(|state| c.run(state))
+        {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Reified equality: `b` is 1 iff `u == v`.
+pub fn eqfd_reif<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ReifiedFd::new(ReifiedRelation::Eq, u, v, b)
+}
+
+/// Reified disequality: `b` is 1 iff `u != v`.
+pub fn diseqfd_reif<U, E, G>(
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    b: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ReifiedFd::new(ReifiedRelation::Diseq, u, v, b)
+}
+
+/// Reified less-than: `b` is 1 iff `u < v`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, ltfd_reif};
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, b| {
+///         infdrange(x, &(0..=3)),
+///         ltfd_reif(x, 2, b),
+///     });
+///     let mut iter = query.run();
+///     for (x, b) in &[(0, 1), (1, 1), (2, 0), (3, 0)] {
+///         let result = iter.next().unwrap();
+///         assert_eq!(result.x, *x);
+///         assert_eq!(result.b, *b);
+///     }
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn ltfd_reif<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ReifiedFd::new(ReifiedRelation::Lt, u, v, b)
+}
+
+/// Reified less-than-or-equal: `b` is 1 iff `u <= v`.
+pub fn ltefd_reif<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ReifiedFd::new(ReifiedRelation::Lte, u, v, b)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ReifiedFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    relation: ReifiedRelation,
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    b: LTerm<U, E>,
+}
+
+impl<U, E> ReifiedFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(
+        relation: ReifiedRelation,
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        b: LTerm<U, E>,
+    ) -> Rc<dyn Constraint<U, E>> {
+        assert!(u.is_var() || u.is_number());
+        assert!(v.is_var() || v.is_number());
+        assert!(b.is_var() || b.is_number());
+        Rc::new(ReifiedFdConstraint { relation, u, v, b })
+    }
+
+    /// Returns `Some(true)` if the relation holds for all values of the domains,
+    /// `Some(false)` if it holds for none of them, and `None` if it cannot be decided yet.
+    fn entailment(&self, udomain: &FiniteDomain, vdomain: &FiniteDomain) -> Option<bool> {
+        let equal =
+            udomain.is_singleton() && vdomain.is_singleton() && udomain.min() == vdomain.min();
+        match self.relation {
+            ReifiedRelation::Eq if equal => Some(true),
+            ReifiedRelation::Eq if udomain.is_disjoint(vdomain) => Some(false),
+            ReifiedRelation::Diseq if equal => Some(false),
+            ReifiedRelation::Diseq if udomain.is_disjoint(vdomain) => Some(true),
+            ReifiedRelation::Lt if udomain.max() < vdomain.min() => Some(true),
+            ReifiedRelation::Lt if udomain.min() >= vdomain.max() => Some(false),
+            ReifiedRelation::Lte if udomain.max() <= vdomain.min() => Some(true),
+            ReifiedRelation::Lte if udomain.min() > vdomain.max() => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Adds the relation, or its negation if `holds` is false, to the state.
+    fn post(&self, state: State<U, E>, holds: bool) -> SResult<U, E> {
+        let u = self.u.clone();
+        let v = self.v.clone();
+        match (self.relation, holds) {
+            (ReifiedRelation::Eq, true) | (ReifiedRelation::Diseq, false) => state.unify(&u, &v),
+            (ReifiedRelation::Eq, false) | (ReifiedRelation::Diseq, true) => {
+                DiseqFdConstraint::new(u, v).run(state)
+            }
+            (ReifiedRelation::Lt, true) => DiseqFdConstraint::new(u.clone(), v.clone())
+                .run(state)
+                .and_then(|state| LessThanOrEqualFdConstraint::new(u, v).run(state)),
+            (ReifiedRelation::Lt, false) => LessThanOrEqualFdConstraint::new(v, u).run(state),
+            (ReifiedRelation::Lte, true) => LessThanOrEqualFdConstraint::new(u, v).run(state),
+            (ReifiedRelation::Lte, false) => DiseqFdConstraint::new(v.clone(), u.clone())
+                .run(state)
+                .and_then(|state| LessThanOrEqualFdConstraint::new(v, u).run(state)),
+        }
+    }
+}
+
+/// Returns the domain of a term that walks to a variable with domain, or the singleton domain
+/// of a term that walks to a number.
+fn get_domain<U, E>(state: &State<U, E>, x: &LTerm<U, E>) -> Option<Rc<FiniteDomain>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let xwalk = state.smap_ref().walk(x);
+    match xwalk.as_ref() {
+        LTermInner::Var(_, _) => state.dstore_ref().get(xwalk).cloned(),
+        LTermInner::Val(LValue::Number(x)) => Some(Rc::new(FiniteDomain::from(*x))),
+        _ => None,
+    }
+}
+
+impl<U, E> Constraint<U, E> for ReifiedFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let bwalk = state.smap_ref().walk(&self.b).clone();
+        match bwalk.as_ref() {
+            // Truth value is known; replace the reified constraint with the constraint itself
+            // or its negation.
+            LTermInner::Val(LValue::Number(1)) => return self.post(state, true),
+            LTermInner::Val(LValue::Number(0)) => return self.post(state, false),
+            LTermInner::Var(_, _) => (),
+            _ => return Err(()),
+        }
+
+        match (get_domain(&state, &self.u), get_domain(&state, &self.v)) {
+            (Some(udomain), Some(vdomain)) => match self.entailment(&udomain, &vdomain) {
+                // The domains decide the truth value of the relation.
+                Some(holds) => state.unify(&bwalk, &LTerm::from(holds as isize)),
+                None => Ok(state.with_constraint(self)),
+            },
+            // Keep the constraint until both operands have domains.
+            _ => Ok(state.with_constraint(self)),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone(), self.b.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(ReifiedFdConstraint::new(
+            self.relation,
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+            smap.walk_star(&self.b),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for ReifiedFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diseqfd_reif, eqfd_reif, ltefd_reif, ltfd_reif};
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::infdrange;
+    use crate::relation::clpfd::plusfd::plusfd;
+
+    #[test]
+    fn test_reified_1() {
+        // Truth value is deduced from the domains
+        let query = proto_vulcan_query!(|b| {
+            |x, y| {
+                infdrange(x, &(0..=2)),
+                infdrange(y, &(3..=5)),
+                ltfd_reif(x, y, b),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().b, 1);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reified_2() {
+        // Known truth value posts the negated constraint
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(0..=5)),
+            ltefd_reif(x, 3, 0),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, 4);
+        assert_eq!(iter.next().unwrap().x, 5);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reified_3() {
+        let query = proto_vulcan_query!(|x, b| {
+            infdrange(x, &(1..=3)),
+            eqfd_reif(x, 2, b),
+        });
+        let mut results = query
+            .run()
+            .map(|r| (r.x.get_number().unwrap(), r.b.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        results.sort_unstable();
+        assert_eq!(results, vec![(1, 0), (2, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn test_reified_4() {
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(1..=3)),
+            diseqfd_reif(x, 2, 0),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reified_5() {
+        // Count satisfied conditions: exactly two of x < 2, x == 3 and x != 1 hold.
+        let query = proto_vulcan_query!(|x| {
+            |b1, b2, b3, s, n| {
+                infdrange(x, &(0..=4)),
+                infdrange([s, n], &(0..=3)),
+                ltfd_reif(x, 2, b1),
+                eqfd_reif(x, 3, b2),
+                diseqfd_reif(x, 1, b3),
+                plusfd(b1, b2, s),
+                plusfd(s, b3, n),
+                n == 2,
+            }
+        });
+        let mut results = query
+            .run()
+            .map(|r| r.x.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        results.sort_unstable();
+        assert_eq!(results, vec![0, 3]);
+    }
+}
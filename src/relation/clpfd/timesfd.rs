@@ -4,9 +4,10 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
+use std::cmp::{max, min};
 use std::rc::Rc;
 
 #[derive(Derivative)]
@@ -90,6 +91,7 @@ where
     fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
         let smap = state.get_smap();
         let dstore = state.get_dstore();
+        let bound = super::bound_operands(&state, &self.operands());
 
         let uwalk = smap.walk(&self.u);
         let singleton_udomain;
@@ -127,8 +129,12 @@ where
         // If all operators are bound to numbers, then we can drop the constraint or fail if
         // constraint is not fulfilled.
         if uwalk.is_number() && vwalk.is_number() && wwalk.is_number() {
-            if uwalk.get_number().unwrap() * vwalk.get_number().unwrap()
-                == wwalk.get_number().unwrap()
+            // The product overflows only if it is not equal to any number.
+            if uwalk
+                .get_number()
+                .unwrap()
+                .checked_mul(vwalk.get_number().unwrap())
+                == wwalk.get_number()
             {
                 return Ok(state);
             } else {
@@ -138,46 +144,32 @@ where
 
         match (maybe_udomain, maybe_vdomain, maybe_wdomain) {
             (Some(udomain), Some(vdomain), Some(wdomain)) => {
-                let umin = udomain.min();
-                let umax = udomain.max();
-                let vmin = vdomain.min();
-                let vmax = vdomain.max();
-                let wmin = wdomain.min();
-                let wmax = wdomain.max();
+                let (umin, umax) = (udomain.min() as i128, udomain.max() as i128);
+                let (vmin, vmax) = (vdomain.min() as i128, vdomain.max() as i128);
+                let (wmin, wmax) = (wdomain.min() as i128, wdomain.max() as i128);
                 // The constraint is: u * v = w  <=>  u = w / v  <=>  v = w / u
                 //
-                // Given domains for u and v, we can then deduce that the domain of w must be
-                // in range [umin - vmax .. umax + vmin]. The constraining domain is built and
-                // intersected with the current domain of w in .process_domain()-call.
+                // Given domains for u and v, the extremes of the product are found at the
+                // corners of the domains, whatever the signs of the operands are:
+                //   w = u * v  =>  [min(umin * vmin, ..) .. max(umax * vmax, ..)]
                 //
-                // Same application of constraining domain is done for the other two variables.
-                //   w = u * v  =>  [umin * vmin .. umax * vmax]
-                //   u = w / v  =>  [wmin / vmax .. wmax / vmin]
-                //   v = w / u  =>  [wmin / umax .. wmax / umin]
+                // The quotients are bounded the same way by the non-zero parts of the
+                // divisor's domain:
+                //   u = w / v  =>  [min(wmin / vmin, ..) .. max(wmax / vmax, ..)]
+                //   v = w / u  =>  [min(wmin / umin, ..) .. max(wmax / umax, ..)]
                 //
                 // The constraint is not dropped until all variables converge into numbers.
-                Ok(state
-                    .process_domain(
-                        &wwalk,
-                        Rc::new(FiniteDomain::from(
-                            umin.saturating_mul(vmin)..=umax.saturating_mul(vmax),
-                        )),
-                    )?
-                    .process_domain(
-                        &uwalk,
-                        Rc::new(FiniteDomain::from(
-                            wmin.checked_div(vmax).unwrap_or(umin)
-                                ..=wmax.checked_div(vmin).unwrap_or(umax),
-                        )),
-                    )?
-                    .process_domain(
-                        &vwalk,
-                        Rc::new(FiniteDomain::from(
-                            wmin.checked_div(umax).unwrap_or(vmin)
-                                ..=wmax.checked_div(umin).unwrap_or(vmax),
-                        )),
-                    )?
-                    .with_constraint(self))
+                let (lo, hi) = product_bounds((umin, umax), (vmin, vmax));
+                let state = super::narrow_bounds(state, wwalk, lo, hi)?;
+                let state = match quotient_bounds((wmin, wmax), (vmin, vmax)) {
+                    Some((lo, hi)) => super::narrow_bounds(state, uwalk, lo, hi)?,
+                    None => state,
+                };
+                let state = match quotient_bounds((wmin, wmax), (umin, umax)) {
+                    Some((lo, hi)) => super::narrow_bounds(state, vwalk, lo, hi)?,
+                    None => state,
+                };
+                super::store_or_rerun(self, state, bound)
             }
             // If all operators do not yet have domains, then keep the constraint until it can
             // be used to constrain some domains.
@@ -188,6 +180,66 @@ where
     fn operands(&self) -> Vec<LTerm<U, E>> {
         vec![self.u.clone(), self.v.clone(), self.w.clone()]
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(TimesFdConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+            smap.walk_star(&self.w),
+        ))
+    }
+}
+
+/// Bounds of `u * v` for `u` and `v` within the given bounds.
+fn product_bounds((umin, umax): (i128, i128), (vmin, vmax): (i128, i128)) -> (i128, i128) {
+    let products = [umin * vmin, umin * vmax, umax * vmin, umax * vmax];
+    let lo = products.iter().copied().min().unwrap();
+    let hi = products.iter().copied().max().unwrap();
+    (lo, hi)
+}
+
+/// Bounds of the exact integer quotients `w / v` for `w` and `v` within the given bounds.
+/// Returns `None` if the quotient is not bounded, which is the case when both `w` and `v` can
+/// be zero.
+fn quotient_bounds((wmin, wmax): (i128, i128), (vmin, vmax): (i128, i128)) -> Option<(i128, i128)> {
+    if wmin <= 0 && 0 <= wmax && vmin <= 0 && 0 <= vmax {
+        return None;
+    }
+
+    // The quotient is monotonic in both operands when the sign of the divisor is fixed, so
+    // the extremes are found at the corners of the negative and positive parts of `v`.
+    let parts = [(vmin, min(vmax, -1)), (max(vmin, 1), vmax)];
+    let mut bounds: Option<(i128, i128)> = None;
+    for (a, b) in parts.iter().copied().filter(|(a, b)| a <= b) {
+        for (w, v) in [(wmin, a), (wmin, b), (wmax, a), (wmax, b)].iter().copied() {
+            let (lo, hi) = (ceil_div(w, v), floor_div(w, v));
+            bounds = Some(match bounds {
+                Some((blo, bhi)) => (min(blo, lo), max(bhi, hi)),
+                None => (lo, hi),
+            });
+        }
+    }
+    // If the divisor can only be zero while the product cannot be zero, there are no
+    // quotients, and the empty bounds fail the narrowing.
+    Some(bounds.unwrap_or((1, 0)))
+}
+
+fn floor_div(a: i128, b: i128) -> i128 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+fn ceil_div(a: i128, b: i128) -> i128 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) == (b < 0) {
+        q + 1
+    } else {
+        q
+    }
 }
 
 impl<U, E> std::fmt::Display for TimesFdConstraint<U, E>
@@ -205,6 +257,7 @@ mod tests {
     use super::timesfd;
     use crate::prelude::*;
     use crate::relation::clpfd::infd::infdrange;
+    use crate::relation::clpfd::labeling::labeling;
 
     #[test]
     fn test_timesfd_1() {
@@ -222,4 +275,72 @@ mod tests {
         assert_eq!(iter.next().unwrap().q, lterm!([6, 1]));
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_timesfd_2() {
+        // Labeling `w` first binds `v` while the constraint is narrowing the domains, after
+        // which the constraint must still be checked.
+        let query = proto_vulcan_query!(|v, w| {
+            infdrange(v, &(0..=6)),
+            infdrange(w, &(0..=36)),
+            timesfd(4, v, w),
+            labeling(&[], [w, v]),
+        });
+        let results = query
+            .run()
+            .map(|r| (r.v.get_number().unwrap(), r.w.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        assert_eq!(results.len(), 7);
+        assert!(results.iter().all(|(v, w)| 4 * v == *w));
+    }
+
+    #[test]
+    fn test_timesfd_3() {
+        // Operands of any sign
+        let query = proto_vulcan_query!(|u, v| {
+            infdrange([u, v], &(-3..=3)),
+            timesfd(u, v, -6),
+        });
+        let mut results = query
+            .run()
+            .map(|r| (r.u.get_number().unwrap(), r.v.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        results.sort();
+        assert_eq!(results, vec![(-3, 2), (-2, 3), (2, -3), (3, -2)]);
+
+        let query = proto_vulcan_query!(|u, v| {
+            infdrange(u, &(-3..=-1)),
+            infdrange(v, &(-4..=5)),
+            timesfd(u, v, 12),
+        });
+        let mut results = query
+            .run()
+            .map(|r| (r.u.get_number().unwrap(), r.v.get_number().unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        results.sort();
+        assert_eq!(results, vec![(-3, -4)]);
+    }
+
+    #[test]
+    fn test_timesfd_4() {
+        // Products at the ends of isize
+        let query = proto_vulcan_query!(|u, v, w| {
+            infdrange(u, &(isize::MAX / 2..=isize::MAX)),
+            infdrange(v, &(2..=3)),
+            infdrange(w, &(isize::MIN..=isize::MAX)),
+            timesfd(u, v, w),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.u, isize::MAX / 2);
+        assert_eq!(result.v, 2);
+        assert_eq!(result.w, isize::MAX - 1);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|w| {
+            infdrange(w, &(isize::MIN..=isize::MAX)),
+            timesfd({isize::MIN}, -1, w),
+        });
+        assert!(query.run().next().is_none());
+    }
 }
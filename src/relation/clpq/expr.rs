@@ -0,0 +1,376 @@
+//! Linear expressions over rationals
+use crate::engine::Engine;
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::state::simplex::Rational;
+use crate::state::SMap;
+use crate::user::User;
+use num_bigint::BigInt;
+use num_traits::{Num, One, Zero};
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Arithmetic expression over terms and rational constants
+///
+/// The expression is linear if every product has a constant factor, and every divisor is
+/// a constant. Terms that are bound to numbers are constants.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+pub enum LinearExpr<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    Const(Rational),
+    Term(LTerm<U, E>),
+    Neg(Box<LinearExpr<U, E>>),
+    Add(Box<LinearExpr<U, E>>, Box<LinearExpr<U, E>>),
+    Sub(Box<LinearExpr<U, E>>, Box<LinearExpr<U, E>>),
+    Mul(Box<LinearExpr<U, E>>, Box<LinearExpr<U, E>>),
+    Div(Box<LinearExpr<U, E>>, Box<LinearExpr<U, E>>),
+}
+
+/// Linear combination `sum(a * x) + constant` of variables
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+pub struct LinearCombination<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub coeffs: Vec<(LTerm<U, E>, Rational)>,
+    pub constant: Rational,
+}
+
+impl<U, E> LinearCombination<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn constant(constant: Rational) -> LinearCombination<U, E> {
+        LinearCombination {
+            coeffs: vec![],
+            constant,
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    fn add(mut self, other: LinearCombination<U, E>) -> LinearCombination<U, E> {
+        for (x, a) in other.coeffs {
+            match self.coeffs.iter_mut().find(|(y, _)| *y == x) {
+                Some((_, b)) => *b += a,
+                None => self.coeffs.push((x, a)),
+            }
+        }
+        self.coeffs.retain(|(_, a)| !a.is_zero());
+        self.constant += other.constant;
+        self
+    }
+
+    fn scale(mut self, k: &Rational) -> LinearCombination<U, E> {
+        if k.is_zero() {
+            return LinearCombination::constant(Rational::zero());
+        }
+        for (_, a) in self.coeffs.iter_mut() {
+            *a *= k;
+        }
+        self.constant *= k;
+        self
+    }
+}
+
+impl<U, E> LinearExpr<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn term(t: LTerm<U, E>) -> LinearExpr<U, E> {
+        LinearExpr::Term(t)
+    }
+
+    pub fn integer(n: isize) -> LinearExpr<U, E> {
+        LinearExpr::Const(Rational::from_integer(BigInt::from(n)))
+    }
+
+    /// Exact value of a decimal literal such as `0.25` or `1.5e3`.
+    pub fn decimal(s: &str) -> LinearExpr<U, E> {
+        let s = s.trim_end_matches("f64").trim_end_matches("f32");
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(i) => (
+                &s[..i],
+                s[i + 1..].parse::<i32>().expect("invalid exponent"),
+            ),
+            None => (s, 0),
+        };
+        let (int_part, frac_part) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+            None => (mantissa, ""),
+        };
+        let digits = format!("{}{}", int_part, frac_part).replace('_', "");
+        let numer = BigInt::from_str_radix(&digits, 10).expect("invalid decimal literal");
+        let exponent = exponent - frac_part.len() as i32;
+        let ten = Rational::from_integer(BigInt::from(10));
+        LinearExpr::Const(Rational::from_integer(numer) * ten.pow(exponent))
+    }
+
+    /// Terms of the expression.
+    pub fn terms(&self) -> Vec<LTerm<U, E>> {
+        match self {
+            LinearExpr::Const(_) => vec![],
+            LinearExpr::Term(t) => vec![t.clone()],
+            LinearExpr::Neg(u) => u.terms(),
+            LinearExpr::Add(u, v)
+            | LinearExpr::Sub(u, v)
+            | LinearExpr::Mul(u, v)
+            | LinearExpr::Div(u, v) => {
+                let mut terms = u.terms();
+                terms.extend(v.terms());
+                terms
+            }
+        }
+    }
+
+    /// Replaces the terms of the expression with their values in the substitution map.
+    pub fn walk_star(&self, smap: &SMap<U, E>) -> LinearExpr<U, E> {
+        let walk = |u: &LinearExpr<U, E>| Box::new(u.walk_star(smap));
+        match self {
+            LinearExpr::Const(c) => LinearExpr::Const(c.clone()),
+            LinearExpr::Term(t) => LinearExpr::Term(smap.walk_star(t)),
+            LinearExpr::Neg(u) => LinearExpr::Neg(walk(u)),
+            LinearExpr::Add(u, v) => LinearExpr::Add(walk(u), walk(v)),
+            LinearExpr::Sub(u, v) => LinearExpr::Sub(walk(u), walk(v)),
+            LinearExpr::Mul(u, v) => LinearExpr::Mul(walk(u), walk(v)),
+            LinearExpr::Div(u, v) => LinearExpr::Div(walk(u), walk(v)),
+        }
+    }
+
+    /// Linear combination of the expression with the terms walked in the substitution map.
+    /// Returns `Ok(None)` if the expression is not linear, and `Err(())` if a term is bound to
+    /// a non-number or a constant divisor is zero.
+    pub fn linearize(&self, smap: &SMap<U, E>) -> Result<Option<LinearCombination<U, E>>, ()> {
+        let linear = match self {
+            LinearExpr::Const(c) => LinearCombination::constant(c.clone()),
+            LinearExpr::Term(t) => {
+                let t = smap.walk(t);
+                match t.as_ref() {
+                    LTermInner::Var(_, _) => LinearCombination {
+                        coeffs: vec![(t.clone(), Rational::one())],
+                        constant: Rational::zero(),
+                    },
+                    LTermInner::Val(LValue::Number(n)) => {
+                        LinearCombination::constant(Rational::from_integer(BigInt::from(*n)))
+                    }
+                    LTermInner::Val(LValue::Rational(r)) => LinearCombination::constant(r.clone()),
+                    _ => return Err(()),
+                }
+            }
+            LinearExpr::Neg(u) => match u.linearize(smap)? {
+                Some(u) => u.scale(&-Rational::one()),
+                None => return Ok(None),
+            },
+            LinearExpr::Add(u, v) => match (u.linearize(smap)?, v.linearize(smap)?) {
+                (Some(u), Some(v)) => u.add(v),
+                _ => return Ok(None),
+            },
+            LinearExpr::Sub(u, v) => match (u.linearize(smap)?, v.linearize(smap)?) {
+                (Some(u), Some(v)) => u.add(v.scale(&-Rational::one())),
+                _ => return Ok(None),
+            },
+            LinearExpr::Mul(u, v) => match (u.linearize(smap)?, v.linearize(smap)?) {
+                (Some(u), Some(v)) if u.is_constant() => v.scale(&u.constant),
+                (Some(u), Some(v)) if v.is_constant() => u.scale(&v.constant),
+                _ => return Ok(None),
+            },
+            LinearExpr::Div(u, v) => match (u.linearize(smap)?, v.linearize(smap)?) {
+                (Some(_), Some(v)) if v.is_constant() && v.constant.is_zero() => return Err(()),
+                (Some(u), Some(v)) if v.is_constant() => u.scale(&v.constant.recip()),
+                _ => return Ok(None),
+            },
+        };
+        Ok(Some(linear))
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            LinearExpr::Add(_, _) | LinearExpr::Sub(_, _) => 0,
+            LinearExpr::Mul(_, _) | LinearExpr::Div(_, _) => 1,
+            LinearExpr::Neg(_) => 2,
+            LinearExpr::Const(_) | LinearExpr::Term(_) => 3,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+impl<U, E> fmt::Display for LinearExpr<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (u, op, v) = match self {
+            LinearExpr::Const(c) => return write!(f, "{}", c),
+            LinearExpr::Term(t) => return write!(f, "{}", t),
+            LinearExpr::Neg(u) => {
+                write!(f, "-")?;
+                return u.fmt_operand(f, 3);
+            }
+            LinearExpr::Add(u, v) => (u, "+", v),
+            LinearExpr::Sub(u, v) => (u, "-", v),
+            LinearExpr::Mul(u, v) => (u, "*", v),
+            LinearExpr::Div(u, v) => (u, "/", v),
+        };
+        let precedence = self.precedence();
+        u.fmt_operand(f, precedence)?;
+        write!(f, " {} ", op)?;
+        // The right operand binds tighter, since the operators are left-associative.
+        v.fmt_operand(f, precedence + 1)
+    }
+}
+
+impl<U, E> From<LTerm<U, E>> for LinearExpr<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(t: LTerm<U, E>) -> LinearExpr<U, E> {
+        LinearExpr::Term(t)
+    }
+}
+
+impl<U, E> From<&LTerm<U, E>> for LinearExpr<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(t: &LTerm<U, E>) -> LinearExpr<U, E> {
+        LinearExpr::Term(t.clone())
+    }
+}
+
+impl<U, E> From<isize> for LinearExpr<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(n: isize) -> LinearExpr<U, E> {
+        LinearExpr::integer(n)
+    }
+}
+
+impl<U, E> From<Rational> for LinearExpr<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(c: Rational) -> LinearExpr<U, E> {
+        LinearExpr::Const(c)
+    }
+}
+
+impl<U, E> Neg for LinearExpr<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    type Output = LinearExpr<U, E>;
+
+    fn neg(self) -> LinearExpr<U, E> {
+        LinearExpr::Neg(Box::new(self))
+    }
+}
+
+macro_rules! impl_linear_op {
+    ($trait:ident, $method:ident, $variant:ident) => {
+        impl<U, E, T> $trait<T> for LinearExpr<U, E>
+        where
+            U: User,
+            E: Engine<U>,
+            T: Into<LinearExpr<U, E>>,
+        {
+            type Output = LinearExpr<U, E>;
+
+            fn $method(self, other: T) -> LinearExpr<U, E> {
+                LinearExpr::$variant(Box::new(self), Box::new(other.into()))
+            }
+        }
+    };
+}
+
+impl_linear_op!(Add, add, Add);
+impl_linear_op!(Sub, sub, Sub);
+impl_linear_op!(Mul, mul, Mul);
+impl_linear_op!(Div, div, Div);
+
+#[cfg(test)]
+mod tests {
+    use super::LinearExpr;
+    use crate::prelude::*;
+    use crate::state::simplex::Rational;
+    use crate::state::SMap;
+    use num_bigint::BigInt;
+
+    fn q(n: isize, d: isize) -> Rational {
+        Rational::new(BigInt::from(n), BigInt::from(d))
+    }
+
+    #[test]
+    fn test_linear_expr_decimal() {
+        let expr: LinearExpr<DefaultUser, DefaultEngine<DefaultUser>> = LinearExpr::decimal("0.25");
+        assert_eq!(format!("{}", expr), "1/4");
+        let expr: LinearExpr<DefaultUser, DefaultEngine<DefaultUser>> =
+            LinearExpr::decimal("1.5e2");
+        assert_eq!(format!("{}", expr), "150");
+        let expr: LinearExpr<DefaultUser, DefaultEngine<DefaultUser>> = LinearExpr::decimal("2e-1");
+        assert_eq!(format!("{}", expr), "1/5");
+    }
+
+    #[test]
+    fn test_linear_expr_linearize() {
+        let x: LTerm<DefaultUser, DefaultEngine<DefaultUser>> = LTerm::var("x");
+        let y = LTerm::var("y");
+        let smap = SMap::new();
+
+        // (2 * x - y) / 4 + 1
+        let expr = (LinearExpr::integer(2) * &x - &y) / 4 + 1;
+        let linear = expr.linearize(&smap).unwrap().unwrap();
+        assert_eq!(
+            linear.coeffs,
+            vec![(x.clone(), q(1, 2)), (y.clone(), q(-1, 4))]
+        );
+        assert_eq!(linear.constant, q(1, 1));
+
+        // x * y is not linear until either of the variables is bound
+        let expr = LinearExpr::from(&x) * &y;
+        assert!(expr.linearize(&smap).unwrap().is_none());
+        let mut smap = SMap::new();
+        smap.extend(y.clone(), LTerm::from(3));
+        let linear = expr.linearize(&smap).unwrap().unwrap();
+        assert_eq!(linear.coeffs, vec![(x.clone(), q(3, 1))]);
+
+        // Division by zero fails
+        let expr = LinearExpr::from(&x) / 0;
+        assert!(expr.linearize(&smap).is_err());
+    }
+
+    #[test]
+    fn test_linear_expr_display() {
+        let x: LTerm<DefaultUser, DefaultEngine<DefaultUser>> = LTerm::var("x");
+        let y = LTerm::var("y");
+        let expr =
+            LinearExpr::integer(2) * (LinearExpr::from(&x) + &y) - (LinearExpr::from(&x) - 1);
+        assert_eq!(
+            format!("{}", expr),
+            format!("2 * ({} + {}) - ({} - 1)", x, y, x)
+        );
+    }
+}
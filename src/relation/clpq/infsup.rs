@@ -0,0 +1,167 @@
+//! Infimum and supremum of terms under linear constraints
+use super::expr::LinearExpr;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::State;
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct InfSup<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+    value: LTerm<U, E>,
+    sup: bool,
+}
+
+impl<U, E> InfSup<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        x: LTerm<U, E>,
+        value: LTerm<U, E>,
+        sup: bool,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(InfSup { x, value, sup })))
+    }
+}
+
+impl<U, E> Solve<U, E> for InfSup<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let linear = match LinearExpr::from(&self.x).linearize(state.smap_ref()) {
+            Ok(Some(linear)) => linear,
+            _ => return Stream::empty(),
+        };
+
+        let bound = if linear.coeffs.is_empty() {
+            linear.constant
+        } else {
+            let (x, _) = &linear.coeffs[0];
+            let mut tableau = state.qstore_ref().clone();
+            let col = tableau.column(x);
+            let bound = if self.sup {
+                tableau.maximize(col)
+            } else {
+                tableau.minimize(col)
+            };
+            match bound {
+                Some(bound) => bound.real,
+                None => return Stream::empty(),
+            }
+        };
+
+        match state.unify(&self.value, &LTerm::from(bound)) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Infimum of `x` under the linear constraints. The goal fails if `x` is unbounded from below.
+/// The variable is not constrained by the result.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{inf, sup};
+/// fn main() {
+///     let query = proto_vulcan_query!(|lo, hi| {
+///         |x, y| {
+///             { 2 * x + y <= 10, x - y >= 1, y >= 0 },
+///             inf(x, lo),
+///             sup(x, hi),
+///         }
+///     });
+///     let result = query.run().next().unwrap();
+///     assert_eq!(result.lo, 1);
+///     assert_eq!(result.hi, 5);
+/// }
+/// ```
+pub fn inf<U, E, G>(x: LTerm<U, E>, value: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    InfSup::new(x, value, false)
+}
+
+/// Supremum of `x` under the linear constraints. The goal fails if `x` is unbounded from above.
+/// The variable is not constrained by the result.
+pub fn sup<U, E, G>(x: LTerm<U, E>, value: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    InfSup::new(x, value, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inf, sup};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_infsup_1() {
+        // Maximize profit 3x + 2y subject to resource limits.
+        let query = proto_vulcan_query!(|p, x, y| {
+            { x + y <= 4, x + 3 * y <= 6, x >= 0, y >= 0, p == 3 * x + 2 * y },
+            sup(p, p),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.p, 12);
+        assert_eq!(result.x, 4);
+        assert_eq!(result.y, 0);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_infsup_2() {
+        // Strict bounds give the infimum even if it is not attained, and unbounded terms fail.
+        let query = proto_vulcan_query!(|lo| {
+            |x| {
+                { x > 1 / 2 },
+                inf(x, lo),
+            }
+        });
+        assert_eq!(format!("{}", query.run().next().unwrap().lo), "1/2");
+
+        let query = proto_vulcan_query!(|hi| {
+            |x| {
+                { x > 0 },
+                sup(x, hi),
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_infsup_3() {
+        let query = proto_vulcan_query!(|lo, hi| {
+            |x| {
+                x == 7,
+                inf(x, lo),
+                sup(x, hi),
+            }
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.lo, 7);
+        assert_eq!(result.hi, 7);
+    }
+}
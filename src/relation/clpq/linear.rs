@@ -0,0 +1,293 @@
+//! Linear constraints over rationals
+use super::expr::LinearExpr;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::solver::{Solve, Solver};
+use crate::state::simplex::LinearRelation;
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Linear<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    lhs: LinearExpr<U, E>,
+    relation: LinearRelation,
+    rhs: LinearExpr<U, E>,
+}
+
+impl<U, E> Linear<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        lhs: LinearExpr<U, E>,
+        relation: LinearRelation,
+        rhs: LinearExpr<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Linear { lhs, relation, rhs })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Linear<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let c = LinearConstraint::new(self.lhs.clone(), self.relation, self.rhs.clone());
+        match c.run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Linear constraint `lhs REL rhs` over rationals.
+///
+/// In `proto_vulcan!` the constraints are written in braces, for example
+/// `{ 2 * x + y <= 10, x > 0 }`.
+pub fn linear<U, E, G>(
+    lhs: LinearExpr<U, E>,
+    relation: LinearRelation,
+    rhs: LinearExpr<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Linear::new(lhs, relation, rhs)
+}
+
+/// Linear constraint
+///
+/// A linear constraint is added to the simplex tableau of the state, after which it is no
+/// longer kept in the constraint store. A non-linear constraint stays in the store until
+/// enough of its variables are bound for it to become linear. At reification, the bounds of
+/// the tableau are added back to the store as linear constraints.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct LinearConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    lhs: LinearExpr<U, E>,
+    relation: LinearRelation,
+    rhs: LinearExpr<U, E>,
+}
+
+impl<U, E> LinearConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(
+        lhs: LinearExpr<U, E>,
+        relation: LinearRelation,
+        rhs: LinearExpr<U, E>,
+    ) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(LinearConstraint { lhs, relation, rhs })
+    }
+}
+
+impl<U, E> Constraint<U, E> for LinearConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let expr = self.lhs.clone() - self.rhs.clone();
+        match expr.linearize(state.smap_ref())? {
+            Some(linear) => super::post(state, &linear, self.relation),
+            None => Ok(state.with_constraint(self)),
+        }
+    }
+
+    fn operands(&self) -> Vec<crate::lterm::LTerm<U, E>> {
+        let mut operands = self.lhs.terms();
+        operands.extend(self.rhs.terms());
+        operands
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(LinearConstraint::new(
+            self.lhs.walk_star(smap),
+            self.relation,
+            self.rhs.walk_star(smap),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for LinearConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let relation = match self.relation {
+            LinearRelation::Eq => "==",
+            LinearRelation::Lt => "<",
+            LinearRelation::Le => "<=",
+            LinearRelation::Gt => ">",
+            LinearRelation::Ge => ">=",
+        };
+        write!(f, "{{{} {} {}}}", self.lhs, relation, self.rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_linear_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            { x + y == 10, x - y == 2 },
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 6);
+        assert_eq!(result.y, 4);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_linear_2() {
+        // Infeasible constraints fail.
+        let query = proto_vulcan_query!(|x, y| {
+            { 2 * x + y <= 10, x >= 3, y > 4 },
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_linear_3() {
+        // Bounds meeting at a single point determine the variable.
+        let query = proto_vulcan_query!(|x, y| {
+            { 2 * x + y <= 10, x >= 3, y >= 4 },
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 3);
+        assert_eq!(result.y, 4);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_linear_4() {
+        // Rational solutions
+        let query = proto_vulcan_query!(|x| {
+            { 3 * x == 1 },
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(format!("{}", result.x), "1/3");
+
+        let query = proto_vulcan_query!(|x| {
+            { x / 4 == 0.5 },
+        });
+        assert_eq!(query.run().next().unwrap().x, 2);
+    }
+
+    #[test]
+    fn test_linear_5() {
+        // Unification with numbers and variables propagates to the tableau.
+        let query = proto_vulcan_query!(|x, y, z| {
+            { x + y + z == 12, x - z == 0 },
+            y == z,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 4);
+        assert_eq!(result.y, 4);
+        assert_eq!(result.z, 4);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x, y| {
+            { x + y <= 5 },
+            conde {
+                [x == 3, y == 2],
+                [x == 3, y == 3],
+                [x == "a"],
+            },
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 3);
+        assert_eq!(result.y, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_linear_6() {
+        // Non-linear constraints are delayed until they become linear.
+        let query = proto_vulcan_query!(|x, y| {
+            { x * y == 12 },
+            y == 3,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 4);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x, y| {
+            { x * y == 12 },
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(
+            format!("{}", result.x),
+            format!(
+                "{0}  where  {{ {{{0} * {1} == 12}} }}",
+                *result.x, *result.y
+            )
+        );
+    }
+
+    #[test]
+    fn test_linear_7() {
+        // A bound on a variable determines the variables connected to it through other
+        // constraints, but not the unconnected ones.
+        let query = proto_vulcan_query!(|x, y, z, w| {
+            { x - y == 0, y + z == 5, w >= 1 },
+            { x <= 2, x >= 2 },
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.y, 2);
+        assert_eq!(result.z, 3);
+        assert!(result.w.is_var());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_linear_8() {
+        // The constraints on unbound variables are shown in the answer.
+        let query = proto_vulcan_query!(|x, y| {
+            { x + y <= 10, x >= 2 },
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        let x = format!("{}", result.x);
+        let y = format!("{}", result.y);
+        assert!(x.contains(" <= 10}") && x.contains(" >= 2}"));
+        assert!(y.contains(" <= 10}") && !y.contains(" >= 2}"));
+        assert!(iter.next().is_none());
+
+        // Bound variables are replaced with their values.
+        let query = proto_vulcan_query!(|x, y| {
+            { x + y <= 10, x >= 2 },
+            x == 4,
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.x, 4);
+        assert!(format!("{}", result.y).ends_with(" <= 6} }"));
+    }
+}
@@ -0,0 +1,208 @@
+//! # CLP(Q)
+//! Proto-vulcan implements linear constraints over rational numbers. The constraints are
+//! written in braces, and separated with commas:
+//! ```text
+//! { 2 * x + y <= 10, x - y > 1.5 }
+//! ```
+//! The relations are `==`, `<`, `<=`, `>` and `>=`, and the expressions are built from terms,
+//! integer and decimal literals, and the operators `+`, `-`, `*` and `/`. Quoted Rust
+//! expressions `{expr}` are terms.
+//!
+//! The constraints are solved incrementally with a simplex tableau that is stored in the
+//! state. Variables whose value is determined by the constraints are bound to it. Values that
+//! are integers are `Number`s, and the others are exact `Rational`s. Constraints that are not
+//! linear are delayed until enough of their variables are bound. The bounds of the tableau on
+//! unbound variables are shown in the answer as linear constraints.
+//!
+//! * `inf(x, v)`: `v` is the infimum of `x`.
+//! * `sup(x, v)`: `v` is the supremum of `x`.
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! fn main() {
+//!     let query = proto_vulcan_query!(|x, y| {
+//!         { x + y == 10, x - y == 3 },
+//!     });
+//!     let result = query.run().next().unwrap();
+//!     assert_eq!(format!("{}", result.x), "13/2");
+//!     assert_eq!(format!("{}", result.y), "7/2");
+//! }
+//! ```
+use crate::engine::Engine;
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::state::simplex::{LinearRelation, Rational};
+use crate::state::{SMap, SResult, State};
+use crate::user::User;
+use num_bigint::BigInt;
+use num_traits::{One, Signed};
+use std::collections::HashMap;
+
+pub mod expr;
+pub mod infsup;
+pub mod linear;
+
+#[doc(inline)]
+pub use expr::LinearExpr;
+
+/// Adds the linear constraint `linear REL 0` to the tableau of the state.
+fn post<U, E>(
+    mut state: State<U, E>,
+    linear: &expr::LinearCombination<U, E>,
+    relation: LinearRelation,
+) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let tableau = state.qstore_to_mut();
+    let coeffs = linear
+        .coeffs
+        .iter()
+        .map(|(x, a)| (tableau.column(x), a.clone()))
+        .collect::<Vec<(usize, Rational)>>();
+    tableau.add_constraint(&coeffs, &linear.constant, relation)?;
+    let cols = coeffs
+        .into_iter()
+        .map(|(col, _)| col)
+        .collect::<Vec<usize>>();
+    bind_fixed(state, &cols)
+}
+
+/// Binds the unbound variables of the tableau whose value is determined by the constraints.
+/// Only the variables connected to the columns `cols`, whose bounds have changed, are checked.
+fn bind_fixed<U, E>(mut state: State<U, E>, cols: &[usize]) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let tableau = state.qstore_ref();
+    let connected = tableau.connected(cols);
+    let fixed = tableau
+        .vars()
+        .filter(|(x, col)| connected.contains(col) && state.smap_ref().walk(x).is_var())
+        .filter_map(|(x, col)| {
+            let value = match tableau.fixed_value(col) {
+                Some(value) => value,
+                None => {
+                    let inf = tableau.minimize(col)?;
+                    match tableau.maximize(col) {
+                        Some(sup) if inf == sup => inf.real,
+                        _ => return None,
+                    }
+                }
+            };
+            Some((x.clone(), col, value))
+        })
+        .collect::<Vec<(LTerm<U, E>, usize, Rational)>>();
+
+    // The values are fixed in the tableau before unification, so that binding the variables
+    // does not search for determined variables again.
+    for (_, col, value) in fixed.iter() {
+        state.qstore_to_mut().assert_value(*col, value)?;
+    }
+    for (x, _, value) in fixed {
+        state = state.unify(&x, &LTerm::from(value))?;
+    }
+    Ok(state)
+}
+
+/// Adds the bounds of the tableau to the constraint store as linear constraints, so that they
+/// are shown in the reified answer. The bound variables of a constraint are replaced with
+/// their values, and constraints without unbound variables are left out.
+pub(crate) fn reify_tableau<U, E>(mut state: State<U, E>) -> State<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let tableau = state.qstore_ref();
+    let vars = tableau
+        .vars()
+        .map(|(x, col)| (col, x.clone()))
+        .collect::<HashMap<usize, LTerm<U, E>>>();
+    let mut constraints = vec![];
+    for (coeffs, relation, c) in tableau.bounds() {
+        let mut lhs: Option<LinearExpr<U, E>> = None;
+        let mut rhs = c;
+        for (col, a) in coeffs {
+            let x = match vars.get(&col) {
+                Some(x) => state.smap_ref().walk(x).clone(),
+                None => continue,
+            };
+            match x.as_ref() {
+                LTermInner::Var(_, _) => {
+                    lhs = Some(match lhs {
+                        None if a.is_one() => LinearExpr::from(x),
+                        None if (-&a).is_one() => -LinearExpr::from(x),
+                        None => LinearExpr::from(a) * x,
+                        Some(lhs) if a.is_one() => lhs + x,
+                        Some(lhs) if a.is_negative() && (-&a).is_one() => lhs - x,
+                        Some(lhs) if a.is_negative() => lhs - LinearExpr::from(-a) * x,
+                        Some(lhs) => lhs + LinearExpr::from(a) * x,
+                    });
+                }
+                LTermInner::Val(LValue::Number(n)) => rhs -= a * BigInt::from(*n),
+                LTermInner::Val(LValue::Rational(value)) => rhs -= a * value,
+                _ => (),
+            }
+        }
+        if let Some(lhs) = lhs {
+            constraints.push(linear::LinearConstraint::new(lhs, relation, rhs.into()));
+        }
+    }
+    for constraint in constraints {
+        state = state.with_constraint(constraint);
+    }
+    state
+}
+
+/// Processes the extension to substitution for the variables of the tableau.
+pub(crate) fn process_extension<U, E>(
+    mut state: State<U, E>,
+    extension: &SMap<U, E>,
+) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut changed = vec![];
+    for (x, v) in extension.iter() {
+        let col = match state.qstore_ref().lookup(x) {
+            Some(col) => col,
+            None => continue,
+        };
+        let v = state.smap_ref().walk(v).clone();
+        match v.as_ref() {
+            LTermInner::Var(_, _) => match state.qstore_ref().lookup(&v) {
+                Some(vcol) => {
+                    let coeffs = [(col, Rational::one()), (vcol, -Rational::one())];
+                    state.qstore_to_mut().add_constraint(
+                        &coeffs,
+                        &Rational::from_integer(BigInt::from(0)),
+                        LinearRelation::Eq,
+                    )?;
+                    changed.push(col);
+                }
+                None => state.qstore_to_mut().rename(x, &v),
+            },
+            LTermInner::Val(LValue::Number(n)) => {
+                let value = Rational::from_integer(BigInt::from(*n));
+                if state.qstore_to_mut().assert_value(col, &value)? {
+                    changed.push(col);
+                }
+            }
+            LTermInner::Val(LValue::Rational(value)) => {
+                if state.qstore_to_mut().assert_value(col, value)? {
+                    changed.push(col);
+                }
+            }
+            _ => return Err(()),
+        }
+    }
+
+    if changed.is_empty() {
+        Ok(state)
+    } else {
+        bind_fixed(state, &changed)
+    }
+}
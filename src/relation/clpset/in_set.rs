@@ -0,0 +1,175 @@
+//! Set membership constraint
+use super::narrow;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct InSet<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+    s: LTerm<U, E>,
+}
+
+impl<U, E> InSet<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(x: LTerm<U, E>, s: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(InSet { x, s })))
+    }
+}
+
+impl<U, E> Solve<U, E> for InSet<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match InSetConstraint::new(self.x.clone(), self.s.clone()).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Constrains the number `x` to be an element of the set `s`. While `x` is not known, its
+/// finite domain is limited to the upper bound of `s`.
+pub fn in_set<U, E, G>(x: LTerm<U, E>, s: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    InSet::new(x, s)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct InSetConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+    s: LTerm<U, E>,
+}
+
+impl<U, E> InSetConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(x: LTerm<U, E>, s: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(InSetConstraint { x, s })
+    }
+}
+
+impl<U, E> Constraint<U, E> for InSetConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let sdomain = match state.get_set_domain(&self.s)? {
+            Some(sdomain) => sdomain,
+            None => return Ok(state.with_constraint(self)),
+        };
+
+        let xwalk = state.smap_ref().walk(&self.x).clone();
+        match xwalk.as_ref() {
+            LTermInner::Val(LValue::Number(x)) => {
+                let mut glb = sdomain.glb().clone();
+                let _ = glb.insert(*x);
+                let (state, _) = narrow(state, &self.s, glb, sdomain.lub().clone())?;
+                Ok(state)
+            }
+            LTermInner::Var(_, _) => {
+                if sdomain.lub().is_empty() {
+                    return Err(());
+                }
+                let lub = sdomain.lub().iter().copied().collect::<Vec<isize>>();
+                let state = state.process_domain(&xwalk, Rc::new(FiniteDomain::from(lub)))?;
+                if state.smap_ref().walk(&xwalk).is_var() {
+                    Ok(state.with_constraint(self))
+                } else {
+                    self.run(state)
+                }
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.x.clone(), self.s.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(InSetConstraint::new(
+            smap.walk_star(&self.x),
+            smap.walk_star(&self.s),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for InSetConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::in_set;
+    use crate::prelude::*;
+    use crate::relation::clpset::setdom::setdom;
+
+    #[test]
+    fn test_in_set_1() {
+        let query = proto_vulcan_query!(|s| {
+            setdom(s, &[1], &[1, 2, 3]),
+            in_set(3, s),
+            in_set(2, s),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().s, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|s| {
+            setdom(s, &[], &[1, 2]),
+            in_set(3, s),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_in_set_2() {
+        // An unknown element gets the upper bound of the set as its domain.
+        let query = proto_vulcan_query!(|x| {
+            in_set(x, [2, 4, 6]),
+            x == 4,
+        });
+        assert_eq!(query.run().next().unwrap().x, 4);
+
+        let query = proto_vulcan_query!(|x| {
+            in_set(x, [2, 4, 6]),
+            x == 3,
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -0,0 +1,194 @@
+//! Intersection constraint
+use super::{narrow, rerun_or_store};
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Intersection<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> Intersection<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        w: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Intersection { u, v, w })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Intersection<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match IntersectionConstraint::new(self.u.clone(), self.v.clone(), self.w.clone()).run(state)
+        {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Constrains the set `w` to be the intersection of the sets `u` and `v`. If `w` does not have a
+/// domain, it is given one from the domains of `u` and `v`.
+pub fn intersection<U, E, G>(
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Intersection::new(u, v, w)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct IntersectionConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> IntersectionConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(IntersectionConstraint { u, v, w })
+    }
+}
+
+impl<U, E> Constraint<U, E> for IntersectionConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let (udomain, vdomain) = match (
+            state.get_set_domain(&self.u)?,
+            state.get_set_domain(&self.v)?,
+        ) {
+            (Some(udomain), Some(vdomain)) => (udomain, vdomain),
+            _ => return Ok(state.with_constraint(self)),
+        };
+        let wdomain = state.get_set_domain(&self.w)?;
+
+        // w is within [glb(u) & glb(v) .. lub(u) & lub(v)]
+        let (mut state, mut changed) = narrow(
+            state,
+            &self.w,
+            udomain.glb() & vdomain.glb(),
+            udomain.lub() & vdomain.lub(),
+        )?;
+
+        // Both u and v contain the elements of w. An element that v must contain, but that is
+        // not in w, is not in u, and vice versa.
+        if let Some(wdomain) = wdomain {
+            let (s, uchanged) = narrow(
+                state,
+                &self.u,
+                udomain.glb() | wdomain.glb(),
+                udomain.lub() - &(vdomain.glb() - wdomain.lub()),
+            )?;
+            let (s, vchanged) = narrow(
+                s,
+                &self.v,
+                vdomain.glb() | wdomain.glb(),
+                vdomain.lub() - &(udomain.glb() - wdomain.lub()),
+            )?;
+            state = s;
+            changed = changed || uchanged || vchanged;
+        }
+
+        let (u, v, w) = (self.u.clone(), self.v.clone(), self.w.clone());
+        rerun_or_store(self, state, changed, &[&u, &v, &w])
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone(), self.w.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(IntersectionConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+            smap.walk_star(&self.w),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for IntersectionConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::intersection;
+    use crate::prelude::*;
+    use crate::relation::clpset::setdom::setdom;
+
+    #[test]
+    fn test_intersection_1() {
+        let query = proto_vulcan_query!(|w| {
+            intersection([1, 2, 4], [2, 3, 4], w),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().w, lterm!([2, 4]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_intersection_2() {
+        // An element that is in v but not in the intersection cannot be in u.
+        let query = proto_vulcan_query!(|u| {
+            setdom(u, &[1], &[1, 2, 3]),
+            intersection(u, [2, 3], [3]),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().u, lterm!([1, 3]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_intersection_3() {
+        let query = proto_vulcan_query!(|u| {
+            setdom(u, &[], &[1, 2]),
+            intersection(u, [2, 3], [1]),
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -0,0 +1,98 @@
+//! # CLP(Set)
+//! Proto-vulcan implements constraints over finite sets of numbers. A set value is a list of
+//! distinct numbers in ascending order, such as `[1, 3, 5]`. The domain of a set variable is
+//! given by bounds: the set contains every element of its lower bound, and no element that is
+//! outside of its upper bound.
+//!
+//! * `setdom(s, glb, lub)`: declares the bounds of the set `s`.
+//! * `subset(s1, s2)`: `s1` is a subset of `s2`.
+//! * `union(s1, s2, s3)`: `s3` is the union of `s1` and `s2`.
+//! * `intersection(s1, s2, s3)`: `s3` is the intersection of `s1` and `s2`.
+//! * `in_set(x, s)`: the number `x` is an element of `s`.
+//! * `set_card(s, n)`: `s` has `n` elements.
+//!
+//! The constraints narrow the bounds of the sets without branching, and a set variable is bound
+//! when its bounds meet. Set variables whose bounds have not met are enumerated at reification,
+//! like the finite domain variables, into the sets within their bounds. The cardinality and the
+//! elements are finite domain variables that can be labeled.
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::{set_card, setdom, union};
+//! fn main() {
+//!     let query = proto_vulcan_query!(|a, b| {
+//!         setdom(a, &[1], &[1, 2, 3]),
+//!         setdom(b, &[], &[3, 4]),
+//!         union(a, b, [1, 3, 4]),
+//!         set_card(a, 2),
+//!     });
+//!     let mut iter = query.run();
+//!     let result = iter.next().unwrap();
+//!     assert_eq!(result.a, lterm!([1, 3]));
+//!     assert_eq!(result.b, lterm!([4]));
+//!     let result = iter.next().unwrap();
+//!     assert_eq!(result.a, lterm!([1, 3]));
+//!     assert_eq!(result.b, lterm!([3, 4]));
+//!     assert!(iter.next().is_none());
+//! }
+//! ```
+use crate::engine::Engine;
+use crate::lterm::LTerm;
+use crate::state::{Constraint, SResult, SetDomain, State};
+use crate::user::User;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+pub mod in_set;
+pub mod intersection;
+pub mod set_card;
+pub mod setdom;
+pub mod subset;
+pub mod union;
+
+/// Narrows the set domain of `x` to the bounds `glb` and `lub`. Returns the state and `true` if
+/// the domain of `x` changed.
+fn narrow<U, E>(
+    state: State<U, E>,
+    x: &LTerm<U, E>,
+    glb: BTreeSet<isize>,
+    lub: BTreeSet<isize>,
+) -> Result<(State<U, E>, bool), ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let domain = SetDomain::new(glb, lub).ok_or(())?;
+    if let Some(old_domain) = state.get_set_domain(x)? {
+        if old_domain.intersect(&domain).as_ref() == Some(old_domain.as_ref()) {
+            return Ok((state, false));
+        }
+    }
+    Ok((state.process_set_domain(x, Rc::new(domain))?, true))
+}
+
+/// Runs the constraint again if it narrowed a domain, since the constraint is not in the store
+/// while it is running. Otherwise the constraint is dropped if all of the `sets` are fixed, or
+/// stored.
+fn rerun_or_store<U, E, C>(
+    constraint: Rc<C>,
+    state: State<U, E>,
+    changed: bool,
+    sets: &[&LTerm<U, E>],
+) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    C: Constraint<U, E>,
+{
+    if changed {
+        return constraint.run(state);
+    }
+    for s in sets {
+        match state.get_set_domain(s)? {
+            Some(domain) if domain.is_fixed() => (),
+            _ => return Ok(state.with_constraint(constraint)),
+        }
+    }
+    Ok(state)
+}
@@ -0,0 +1,194 @@
+//! Set cardinality constraint
+use super::{narrow, rerun_or_store};
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, FiniteDomain, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Card<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    s: LTerm<U, E>,
+    n: LTerm<U, E>,
+}
+
+impl<U, E> Card<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(s: LTerm<U, E>, n: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Card { s, n })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Card<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match CardConstraint::new(self.s.clone(), self.n.clone()).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Constrains the set `s` to have `n` elements. The number of elements is a finite domain
+/// variable that is limited by the bounds of `s`. When `n` is at either end of its range, the
+/// set is bound to the corresponding bound.
+pub fn set_card<U, E, G>(s: LTerm<U, E>, n: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Card::new(s, n)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct CardConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    s: LTerm<U, E>,
+    n: LTerm<U, E>,
+}
+
+impl<U, E> CardConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(s: LTerm<U, E>, n: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(CardConstraint { s, n })
+    }
+}
+
+impl<U, E> Constraint<U, E> for CardConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let sdomain = match state.get_set_domain(&self.s)? {
+            Some(sdomain) => sdomain,
+            None => return Ok(state.with_constraint(self)),
+        };
+
+        let min_card = sdomain.glb().len() as isize;
+        let max_card = sdomain.lub().len() as isize;
+        let state =
+            state.process_domain(&self.n, Rc::new(FiniteDomain::from(min_card..=max_card)))?;
+
+        let nwalk = state.smap_ref().walk(&self.n).clone();
+        let (nmin, nmax) = match nwalk.as_ref() {
+            LTermInner::Val(LValue::Number(n)) => (*n, *n),
+            LTermInner::Var(_, _) => match state.dstore_ref().get(&nwalk) {
+                Some(domain) => (domain.min(), domain.max()),
+                None => (min_card, max_card),
+            },
+            _ => return Err(()),
+        };
+
+        // A set that can have no more elements than it must have is its lower bound, and a set
+        // that must have all of the elements that it may have is its upper bound.
+        let (state, changed) = if nmax == min_card {
+            narrow(state, &self.s, sdomain.glb().clone(), sdomain.glb().clone())?
+        } else if nmin == max_card {
+            narrow(state, &self.s, sdomain.lub().clone(), sdomain.lub().clone())?
+        } else {
+            (state, false)
+        };
+
+        let s = self.s.clone();
+        rerun_or_store(self, state, changed, &[&s])
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.s.clone(), self.n.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(CardConstraint::new(
+            smap.walk_star(&self.s),
+            smap.walk_star(&self.n),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for CardConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::set_card;
+    use crate::prelude::*;
+    use crate::relation::clpset::setdom::setdom;
+
+    #[test]
+    fn test_set_card_1() {
+        let query = proto_vulcan_query!(|n| {
+            set_card([1, 2, 3], n),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().n, 3);
+        assert!(iter.next().is_none());
+
+        // A list that is not in ascending order is not a set.
+        let query = proto_vulcan_query!(|n| {
+            set_card([3, 1, 2], n),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_set_card_2() {
+        let query = proto_vulcan_query!(|s| {
+            setdom(s, &[2], &[1, 2, 3]),
+            set_card(s, 3),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().s, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|s| {
+            setdom(s, &[2], &[1, 2, 3]),
+            set_card(s, 0),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_set_card_3() {
+        // The cardinality is narrowed when the set is.
+        let query = proto_vulcan_query!(|s, n| {
+            setdom(s, &[], &[1, 2, 3]),
+            set_card(s, n),
+            s == [1],
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.n, 1);
+        assert!(iter.next().is_none());
+    }
+}
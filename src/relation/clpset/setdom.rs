@@ -0,0 +1,124 @@
+//! Set domains
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::{SetDomain, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct SetDom<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    s: LTerm<U, E>,
+    domain: Rc<SetDomain>,
+}
+
+impl<U, E> SetDom<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(s: LTerm<U, E>, domain: SetDomain) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(SetDom {
+            s,
+            domain: Rc::new(domain),
+        })))
+    }
+}
+
+impl<U, E> Solve<U, E> for SetDom<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        // Constraints that are waiting for the domain of the set are run with it.
+        match state
+            .process_set_domain(&self.s, Rc::clone(&self.domain))
+            .and_then(|state| state.run_constraints())
+        {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Constrains the set `s` to contain all elements of `glb`, and no elements that are not in
+/// `lub`. Fails if `glb` is not a subset of `lub`.
+pub fn setdom<U, E, G>(s: LTerm<U, E>, glb: &[isize], lub: &[isize]) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    match SetDomain::new(glb.iter().copied().collect(), lub.iter().copied().collect()) {
+        Some(domain) => SetDom::new(s, domain),
+        None => InferredGoal::new(G::fail()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::setdom;
+    use crate::prelude::*;
+    use crate::relation::set_card;
+
+    #[test]
+    fn test_setdom_1() {
+        let query = proto_vulcan_query!(|s| {
+            setdom(s, &[1, 2], &[1, 2, 3]),
+            setdom(s, &[], &[2, 1]),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().s, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_setdom_2() {
+        let query = proto_vulcan_query!(|s| {
+            setdom(s, &[1], &[1, 2]),
+            conde {
+                s == [],
+                s == [1, 2],
+                s == [2],
+                s == [1, "a"],
+                s == [2, 1],
+            },
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().s, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_setdom_3() {
+        // Sets whose bounds have not met are enumerated at reification.
+        let query = proto_vulcan_query!(|s| {
+            setdom(s, &[1], &[1, 2, 3]),
+        });
+        let mut results = query
+            .run()
+            .map(|r| r.s.to_string())
+            .collect::<Vec<String>>();
+        results.sort();
+        assert_eq!(results, vec!["[1, 2, 3]", "[1, 2]", "[1, 3]", "[1]"]);
+
+        let query = proto_vulcan_query!(|s| {
+            setdom(s, &[], &[1, 2, 3]),
+            set_card(s, 2),
+        });
+        let mut results = query
+            .run()
+            .map(|r| r.s.to_string())
+            .collect::<Vec<String>>();
+        results.sort();
+        assert_eq!(results, vec!["[1, 2]", "[1, 3]", "[2, 3]"]);
+    }
+}
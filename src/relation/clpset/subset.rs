@@ -0,0 +1,161 @@
+//! Subset constraint
+use super::{narrow, rerun_or_store};
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Subset<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+}
+
+impl<U, E> Subset<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Subset { u, v })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Subset<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match SubsetConstraint::new(self.u.clone(), self.v.clone()).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Constrains the set `u` to be a subset of the set `v`. If `u` does not have a domain, it is
+/// given the subsets of the upper bound of `v`.
+pub fn subset<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Subset::new(u, v)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct SubsetConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+}
+
+impl<U, E> SubsetConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, v: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(SubsetConstraint { u, v })
+    }
+}
+
+impl<U, E> Constraint<U, E> for SubsetConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let vdomain = match state.get_set_domain(&self.v)? {
+            Some(domain) => domain,
+            None => return Ok(state.with_constraint(self)),
+        };
+        let udomain = state.get_set_domain(&self.u)?;
+
+        // u is within [{} .. lub(v)], and v is within [glb(u) .. lub(v)].
+        let (state, uchanged) = narrow(state, &self.u, BTreeSet::new(), vdomain.lub().clone())?;
+        let (state, vchanged) = match udomain {
+            Some(udomain) => narrow(state, &self.v, udomain.glb().clone(), vdomain.lub().clone())?,
+            None => (state, false),
+        };
+        let (u, v) = (self.u.clone(), self.v.clone());
+        rerun_or_store(self, state, uchanged || vchanged, &[&u, &v])
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(SubsetConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for SubsetConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::subset;
+    use crate::prelude::*;
+    use crate::relation::clpset::setdom::setdom;
+
+    #[test]
+    fn test_subset_1() {
+        let query = proto_vulcan_query!(|a, b| {
+            setdom(b, &[], &[1, 2]),
+            subset(a, b),
+            subset([1, 2], a),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.a, lterm!([1, 2]));
+        assert_eq!(result.b, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+
+        // Lists with duplicates or in other orders are not sets.
+        let query = proto_vulcan_query!(|a| {
+            setdom(a, &[], &[1, 2]),
+            conde {
+                subset([2, 1], a),
+                subset([1, 1], a),
+            },
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_subset_2() {
+        let query = proto_vulcan_query!(|a| {
+            setdom(a, &[3], &[1, 2, 3]),
+            subset(a, [1, 2]),
+        });
+        assert!(query.run().next().is_none());
+    }
+}
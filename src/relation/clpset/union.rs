@@ -0,0 +1,192 @@
+//! Union constraint
+use super::{narrow, rerun_or_store};
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Union<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> Union<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        w: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Union { u, v, w })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Union<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match UnionConstraint::new(self.u.clone(), self.v.clone(), self.w.clone()).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Constrains the set `w` to be the union of the sets `u` and `v`. If `w` does not have a
+/// domain, it is given one from the domains of `u` and `v`.
+pub fn union<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Union::new(u, v, w)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct UnionConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> UnionConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(UnionConstraint { u, v, w })
+    }
+}
+
+impl<U, E> Constraint<U, E> for UnionConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let (udomain, vdomain) = match (
+            state.get_set_domain(&self.u)?,
+            state.get_set_domain(&self.v)?,
+        ) {
+            (Some(udomain), Some(vdomain)) => (udomain, vdomain),
+            _ => return Ok(state.with_constraint(self)),
+        };
+        let wdomain = state.get_set_domain(&self.w)?;
+
+        // w is within [glb(u) | glb(v) .. lub(u) | lub(v)]
+        let (mut state, mut changed) = narrow(
+            state,
+            &self.w,
+            udomain.glb() | vdomain.glb(),
+            udomain.lub() | vdomain.lub(),
+        )?;
+
+        // The elements of w that v cannot contain are in u, and vice versa. Neither u nor v
+        // contains elements that are not in w.
+        if let Some(wdomain) = wdomain {
+            let (s, uchanged) = narrow(
+                state,
+                &self.u,
+                udomain.glb() | &(wdomain.glb() - vdomain.lub()),
+                udomain.lub() & wdomain.lub(),
+            )?;
+            let (s, vchanged) = narrow(
+                s,
+                &self.v,
+                vdomain.glb() | &(wdomain.glb() - udomain.lub()),
+                vdomain.lub() & wdomain.lub(),
+            )?;
+            state = s;
+            changed = changed || uchanged || vchanged;
+        }
+
+        let (u, v, w) = (self.u.clone(), self.v.clone(), self.w.clone());
+        rerun_or_store(self, state, changed, &[&u, &v, &w])
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone(), self.w.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(UnionConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+            smap.walk_star(&self.w),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for UnionConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::union;
+    use crate::prelude::*;
+    use crate::relation::clpset::setdom::setdom;
+
+    #[test]
+    fn test_union_1() {
+        let query = proto_vulcan_query!(|w| {
+            union([1, 2], [2, 3], w),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().w, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_union_2() {
+        // Elements of the union that cannot be in one set must be in the other.
+        let query = proto_vulcan_query!(|u, v| {
+            setdom(u, &[], &[1, 2, 3]),
+            setdom(v, &[], &[4]),
+            union(u, v, [1, 2, 3, 4]),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.u, lterm!([1, 2, 3]));
+        assert_eq!(result.v, lterm!([4]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_union_3() {
+        let query = proto_vulcan_query!(|u| {
+            setdom(u, &[5], &[1, 5]),
+            union(u, [2], [1, 2]),
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -1,2 +1,43 @@
+//! # CLP(Z)
+//! Constraints over unbounded integers. The operands are `LValue::Number` values, and results
+//! that do not fit in `isize` are represented as `LValue::BigInt`, so the arithmetic does not
+//! overflow. Integers within the range of `isize` are always numbers, which keeps the
+//! representation of each value unique for unification.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::timesz;
+//! fn main() {
+//!     let query = proto_vulcan_query!(|q| {
+//!         |x| {
+//!             timesz(4611686018427387904, 4, x),
+//!             timesz(x, 2, q),
+//!         }
+//!     });
+//!     let result = query.run().next().unwrap();
+//!     assert_eq!(result.q.to_string(), "36893488147419103232");
+//! }
+//! ```
 pub mod plusz;
 pub mod timesz;
+
+use crate::engine::Engine;
+use crate::lterm::LTerm;
+use crate::user::User;
+use num_bigint::BigInt;
+
+/// Returns the integer value of a grounded operand, or `None` for a variable. Operands that
+/// are grounded to something other than an integer fail the constraint.
+fn operand<U, E>(t: &LTerm<U, E>) -> Result<Option<BigInt>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    if t.is_var() {
+        Ok(None)
+    } else {
+        t.get_bigint().map(Some).ok_or(())
+    }
+}
@@ -1,10 +1,10 @@
+use super::operand;
 use crate::engine::Engine;
 /// Constrains u + v = w
 use crate::goal::{AnyGoal, InferredGoal};
-use crate::lterm::{LTerm, LTermInner};
-use crate::lvalue::LValue;
+use crate::lterm::LTerm;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, SResult, State};
+use crate::state::{Constraint, SMap, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -76,9 +76,9 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_var() || u.is_number());
-        assert!(v.is_var() || v.is_number());
-        assert!(w.is_var() || w.is_number());
+        assert!(u.is_var() || u.get_bigint().is_some());
+        assert!(v.is_var() || v.get_bigint().is_some());
+        assert!(w.is_var() || w.get_bigint().is_some());
         Rc::new(PlusZConstraint { u, v, w })
     }
 }
@@ -93,68 +93,48 @@ where
         let vwalk = state.smap_ref().walk(&self.v).clone();
         let wwalk = state.smap_ref().walk(&self.w).clone();
 
-        match (uwalk.as_ref(), vwalk.as_ref(), wwalk.as_ref()) {
-            (
-                LTermInner::Val(LValue::Number(u)),
-                LTermInner::Val(LValue::Number(v)),
-                LTermInner::Val(LValue::Number(w)),
-            ) => {
+        match (operand(&uwalk)?, operand(&vwalk)?, operand(&wwalk)?) {
+            (Some(u), Some(v), Some(w)) => {
                 /* All operands grounded. */
-                if u * v == *w {
+                if u + v == w {
                     Ok(state)
                 } else {
                     Err(())
                 }
             }
-            (
-                LTermInner::Val(LValue::Number(u)),
-                LTermInner::Val(LValue::Number(v)),
-                LTermInner::Var(_, _),
-            ) => {
+            (Some(u), Some(v), None) => {
                 /* u and v grounded */
-                state
-                    .smap_to_mut()
-                    .extend(wwalk.clone(), LTerm::from(u + v));
+                state.smap_to_mut().extend(wwalk, LTerm::from(u + v));
                 state.run_constraints()
             }
-            (
-                LTermInner::Val(LValue::Number(u)),
-                LTermInner::Var(_, _),
-                LTermInner::Val(LValue::Number(w)),
-            ) => {
+            (Some(u), None, Some(w)) => {
                 /* u and w grounded */
-                state
-                    .smap_to_mut()
-                    .extend(vwalk.clone(), LTerm::from(w - u));
+                state.smap_to_mut().extend(vwalk, LTerm::from(w - u));
                 state.run_constraints()
             }
-            (
-                LTermInner::Var(_, _),
-                LTermInner::Val(LValue::Number(v)),
-                LTermInner::Val(LValue::Number(w)),
-            ) => {
+            (None, Some(v), Some(w)) => {
                 /* v and w grounded */
-                state
-                    .smap_to_mut()
-                    .extend(uwalk.clone(), LTerm::from(w - v));
+                state.smap_to_mut().extend(uwalk, LTerm::from(w - v));
                 state.run_constraints()
             }
-            (LTermInner::Var(_, _), LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_)))
-            | (LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _))
-            | (LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _), LTermInner::Var(_, _)) => {
+            _ => {
                 /* Not enough terms grounded to verify constraint. */
                 Ok(state.with_constraint(self))
             }
-            _ => {
-                /* Some operands grounded to terms of invalid type. */
-                Err(())
-            }
         }
     }
 
     fn operands(&self) -> Vec<LTerm<U, E>> {
         vec![self.u.clone(), self.v.clone(), self.w.clone()]
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(PlusZConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+            smap.walk_star(&self.w),
+        ))
+    }
 }
 
 impl<U, E> std::fmt::Display for PlusZConstraint<U, E>
@@ -195,4 +175,38 @@ mod test {
         assert_eq!(iter.next().unwrap().q, 6);
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_plusz_3() {
+        // All operands grounded
+        let query = proto_vulcan_query!(|q| { plusz(2, 3, 5) });
+        assert_eq!(query.run().count(), 1);
+
+        let query = proto_vulcan_query!(|q| { plusz(2, 3, 6) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_plusz_4() {
+        // Sums beyond the range of isize do not overflow, and results within the range of
+        // isize unify with numbers.
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                plusz({isize::MAX}, {isize::MAX}, x),
+                plusz(x, q, 1),
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.q.to_string(), "-18446744073709551613");
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                plusz({isize::MAX}, 1, x),
+                plusz(x, -1, q),
+            }
+        });
+        assert_eq!(query.run().next().unwrap().q, isize::MAX);
+    }
 }
@@ -1,12 +1,14 @@
+use super::operand;
 use crate::engine::Engine;
 /// Constrains u * v = w
 use crate::goal::{AnyGoal, InferredGoal};
-use crate::lterm::{LTerm, LTermInner};
-use crate::lvalue::LValue;
+use crate::lterm::LTerm;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, SResult, State};
+use crate::state::{Constraint, SMap, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
+use num_bigint::BigInt;
+use num_traits::Zero;
 use std::rc::Rc;
 
 #[derive(Derivative)]
@@ -75,9 +77,9 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_var() || u.is_number());
-        assert!(v.is_var() || v.is_number());
-        assert!(w.is_var() || w.is_number());
+        assert!(u.is_var() || u.get_bigint().is_some());
+        assert!(v.is_var() || v.get_bigint().is_some());
+        assert!(w.is_var() || w.get_bigint().is_some());
         Rc::new(TimesZConstraint { u, v, w })
     }
 }
@@ -92,68 +94,74 @@ where
         let vwalk = state.smap_ref().walk(&self.v).clone();
         let wwalk = state.smap_ref().walk(&self.w).clone();
 
-        match (uwalk.as_ref(), vwalk.as_ref(), wwalk.as_ref()) {
-            (
-                LTermInner::Val(LValue::Number(u)),
-                LTermInner::Val(LValue::Number(v)),
-                LTermInner::Val(LValue::Number(w)),
-            ) => {
+        match (operand(&uwalk)?, operand(&vwalk)?, operand(&wwalk)?) {
+            (Some(u), Some(v), Some(w)) => {
                 /* All operands grounded. */
-                if u * v == *w {
+                if u * v == w {
                     Ok(state)
                 } else {
                     Err(())
                 }
             }
-            (
-                LTermInner::Val(LValue::Number(u)),
-                LTermInner::Val(LValue::Number(v)),
-                LTermInner::Var(_, _),
-            ) => {
+            (Some(u), Some(v), None) => {
                 /* u and v grounded */
-                state
-                    .smap_to_mut()
-                    .extend(wwalk.clone(), LTerm::from(u * v));
+                state.smap_to_mut().extend(wwalk, LTerm::from(u * v));
                 state.run_constraints()
             }
-            (
-                LTermInner::Val(LValue::Number(u)),
-                LTermInner::Var(_, _),
-                LTermInner::Val(LValue::Number(w)),
-            ) => {
+            (Some(u), None, Some(w)) => {
                 /* u and w grounded */
-                state
-                    .smap_to_mut()
-                    .extend(vwalk.clone(), LTerm::from(w / u));
-                state.run_constraints()
+                match divide(&w, &u)? {
+                    Some(v) => {
+                        state.smap_to_mut().extend(vwalk, LTerm::from(v));
+                        state.run_constraints()
+                    }
+                    None => Ok(state),
+                }
             }
-            (
-                LTermInner::Var(_, _),
-                LTermInner::Val(LValue::Number(v)),
-                LTermInner::Val(LValue::Number(w)),
-            ) => {
+            (None, Some(v), Some(w)) => {
                 /* v and w grounded */
-                state
-                    .smap_to_mut()
-                    .extend(uwalk.clone(), LTerm::from(w / v));
-                state.run_constraints()
+                match divide(&w, &v)? {
+                    Some(u) => {
+                        state.smap_to_mut().extend(uwalk, LTerm::from(u));
+                        state.run_constraints()
+                    }
+                    None => Ok(state),
+                }
             }
-            (LTermInner::Var(_, _), LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_)))
-            | (LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _))
-            | (LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _), LTermInner::Var(_, _)) => {
+            _ => {
                 /* Not enough terms grounded to verify constraint. */
                 Ok(state.with_constraint(self))
             }
-            _ => {
-                /* Some operands grounded to terms of invalid type. */
-                Err(())
-            }
         }
     }
 
     fn operands(&self) -> Vec<LTerm<U, E>> {
         vec![self.u.clone(), self.v.clone(), self.w.clone()]
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(TimesZConstraint::new(
+            smap.walk_star(&self.u),
+            smap.walk_star(&self.v),
+            smap.walk_star(&self.w),
+        ))
+    }
+}
+
+/// Solves `x` from `k * x = w`. Fails if there is no integer solution, and returns `None` if
+/// every integer is a solution.
+fn divide(w: &BigInt, k: &BigInt) -> Result<Option<BigInt>, ()> {
+    if k.is_zero() {
+        if w.is_zero() {
+            Ok(None)
+        } else {
+            Err(())
+        }
+    } else if (w % k).is_zero() {
+        Ok(Some(w / k))
+    } else {
+        Err(())
+    }
 }
 
 impl<U, E> std::fmt::Display for TimesZConstraint<U, E>
@@ -170,6 +178,7 @@ where
 mod test {
     use super::timesz;
     use crate::prelude::*;
+    use num_bigint::BigInt;
 
     #[test]
     fn test_timesz_1() {
@@ -194,4 +203,32 @@ mod test {
         assert_eq!(iter.next().unwrap().q, 4);
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_timesz_3() {
+        // Division without an integer result fails, and zero divides only zero.
+        let query = proto_vulcan_query!(|q| { timesz(3, q, 7) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { timesz(q, 0, 7) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { timesz(0, q, 0) });
+        let result = query.run().next().unwrap();
+        assert!(result.q.is_any());
+    }
+
+    #[test]
+    fn test_timesz_4() {
+        // 25! does not fit in isize.
+        let factorial: BigInt = "15511210043330985984000000".parse().unwrap();
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                timesz({factorial.clone()}, 1, x),
+                timesz(q, 25, x),
+            }
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.q.to_string(), "620448401733239439360000");
+    }
 }
@@ -0,0 +1,490 @@
+//! # Term comparison
+//! Relations over the [standard order of terms](crate::order).
+//!
+//! * `term_lt(a, b)`: `a` is before `b` in the standard order
+//! * `compareo(order, a, b)`: `order` is one of the atoms `<`, `=` or `>`, such that
+//!   `a order b` in the standard order
+//! * `msort_terms(l, s)`: `s` is the list `l` sorted in the standard order. Duplicates are kept.
+//! * `sort(l, s)`: `s` is the list `l` sorted in the standard order, without duplicates
+//!
+//! The order of two distinct variables depends on what they will be bound to, and the
+//! relations are constraints that wait until the order of the terms is decided. A comparison
+//! that is still undecided when the answer is reified is shown in the answer. Unlike
+//! `msorto`, which compares integers with finite domain constraints, the sort relations take
+//! any terms, but the list must be a proper list before it is sorted.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::{compareo, sort};
+//! fn main() {
+//!     let query = proto_vulcan_query!(|order, s| {
+//!         compareo(order, [1, 'b], [1, 'a]),
+//!         sort(['c, 2, "s", 'a, 2], s),
+//!     });
+//!     let result = query.run().next().unwrap();
+//!     assert_eq!(result.order, Atom::from(">"));
+//!     assert_eq!(result.s, lterm!([2, 'a, 'c, "s"]));
+//! }
+//! ```
+use crate::atom::Atom;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::order::decided_cmp;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// Comparison relations
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+enum CompareOp<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    TermLt(LTerm<U, E>, LTerm<U, E>),
+    Compare(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>),
+    Sort(LTerm<U, E>, LTerm<U, E>, bool),
+}
+
+impl<U, E> CompareOp<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn terms(&self) -> Vec<&LTerm<U, E>> {
+        match self {
+            CompareOp::TermLt(a, b) => vec![a, b],
+            CompareOp::Compare(order, a, b) => vec![order, a, b],
+            CompareOp::Sort(l, s, _) => vec![l, s],
+        }
+    }
+
+    fn walk_star(&self, smap: &SMap<U, E>) -> CompareOp<U, E> {
+        match self {
+            CompareOp::TermLt(a, b) => CompareOp::TermLt(smap.walk_star(a), smap.walk_star(b)),
+            CompareOp::Compare(order, a, b) => {
+                CompareOp::Compare(smap.walk_star(order), smap.walk_star(a), smap.walk_star(b))
+            }
+            CompareOp::Sort(l, s, dedup) => {
+                CompareOp::Sort(smap.walk_star(l), smap.walk_star(s), *dedup)
+            }
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct CompareO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    op: CompareOp<U, E>,
+}
+
+impl<U, E> CompareO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn new<G: AnyGoal<U, E>>(op: CompareOp<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(CompareO { op })))
+    }
+}
+
+impl<U, E> Solve<U, E> for CompareO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let constraint = Rc::new(CompareConstraint {
+            op: self.op.clone(),
+        });
+        match constraint.run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// A relation that succeeds when `a` is before `b` in the standard order of terms.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::term_lt;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x| {
+///         term_lt(x, 'b),
+///         conde {
+///             x == 'c,
+///             x == 'a,
+///             x == [],
+///         },
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().x, Atom::from("a"));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn term_lt<U, E, G>(a: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    CompareO::new(CompareOp::TermLt(a, b))
+}
+
+/// A relation where `order` is the atom `<`, `=` or `>` according to the order of `a` and
+/// `b` in the standard order of terms.
+pub fn compareo<U, E, G>(
+    order: LTerm<U, E>,
+    a: LTerm<U, E>,
+    b: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    CompareO::new(CompareOp::Compare(order, a, b))
+}
+
+/// A relation where `s` is the list `l` sorted in the standard order of terms. The sort is
+/// stable, and duplicates are kept.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::msort_terms;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, s| {
+///         msort_terms([[x], 'b, 1, 'b], s),
+///         x == 2,
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().s, lterm!([1, 'b, 'b, [2]]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn msort_terms<U, E, G>(l: LTerm<U, E>, s: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    CompareO::new(CompareOp::Sort(l, s, false))
+}
+
+/// A relation where `s` is the list `l` sorted in the standard order of terms, with the
+/// duplicates removed.
+pub fn sort<U, E, G>(l: LTerm<U, E>, s: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    CompareO::new(CompareOp::Sort(l, s, true))
+}
+
+fn order_atom<U, E>(ordering: Ordering) -> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let name = match ordering {
+        Ordering::Less => "<",
+        Ordering::Equal => "=",
+        Ordering::Greater => ">",
+    };
+    LTerm::from(Atom::from(name))
+}
+
+/// Returns `false` if the term can not be one of the order atoms.
+fn is_order<U, E>(order: &LTerm<U, E>) -> bool
+where
+    U: User,
+    E: Engine<U>,
+{
+    match order.as_ref() {
+        LTermInner::Var(_, _) => true,
+        LTermInner::Val(LValue::Atom(atom)) => ["<", "=", ">"].contains(&atom.as_str()),
+        _ => false,
+    }
+}
+
+/// Returns the elements of the list, or `None` if the list is not a proper list yet.
+/// Returns an error if the list is not a list.
+pub(crate) fn list_elements<U, E>(
+    smap: &SMap<U, E>,
+    l: &LTerm<U, E>,
+) -> Result<Option<Vec<LTerm<U, E>>>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut elements = vec![];
+    let mut l = smap.walk(l);
+    loop {
+        match l.as_ref() {
+            LTermInner::Empty => return Ok(Some(elements)),
+            LTermInner::Cons(head, tail) => {
+                elements.push(head.clone());
+                l = smap.walk(tail);
+            }
+            LTermInner::Var(_, _) => return Ok(None),
+            _ => return Err(()),
+        }
+    }
+}
+
+/// Sorts the terms, or returns `None` if the order of some terms is not decided yet.
+fn sort_terms<U, E>(
+    smap: &SMap<U, E>,
+    mut terms: Vec<LTerm<U, E>>,
+    dedup: bool,
+) -> Option<Vec<LTerm<U, E>>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let undecided = Cell::new(false);
+    terms.sort_by(|a, b| {
+        decided_cmp(smap, a, b).unwrap_or_else(|| {
+            undecided.set(true);
+            Ordering::Equal
+        })
+    });
+    if undecided.get() {
+        return None;
+    }
+    if dedup {
+        terms.dedup_by(|a, b| decided_cmp(smap, a, b) == Some(Ordering::Equal));
+    }
+    Some(terms)
+}
+
+/// Term comparison constraint
+///
+/// The constraint is stored until the order of the compared terms is decided.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct CompareConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    op: CompareOp<U, E>,
+}
+
+impl<U, E> CompareConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn wait(&self, state: State<U, E>) -> SResult<U, E> {
+        let op = self.op.walk_star(state.smap_ref());
+        Ok(state.with_constraint(Rc::new(CompareConstraint { op })))
+    }
+}
+
+impl<U, E> Constraint<U, E> for CompareConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let smap = state.smap_ref();
+        match &self.op {
+            CompareOp::TermLt(a, b) => match decided_cmp(smap, a, b) {
+                Some(Ordering::Less) => Ok(state),
+                Some(_) => Err(()),
+                None => self.wait(state),
+            },
+            CompareOp::Compare(order, a, b) => {
+                if !is_order(smap.walk(order)) {
+                    return Err(());
+                }
+                match decided_cmp(smap, a, b) {
+                    Some(ordering) => state.unify(order, &order_atom(ordering)),
+                    None => self.wait(state),
+                }
+            }
+            CompareOp::Sort(l, s, dedup) => match list_elements(smap, l)? {
+                Some(elements) => match sort_terms(smap, elements, *dedup) {
+                    Some(sorted) => state.unify(s, &LTerm::from_vec(sorted)),
+                    None => self.wait(state),
+                },
+                None => self.wait(state),
+            },
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        self.op
+            .terms()
+            .into_iter()
+            .flat_map(|t| t.anyvars())
+            .collect()
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(Rc::new(CompareConstraint {
+            op: self.op.walk_star(smap),
+        }))
+    }
+}
+
+impl<U, E> std::fmt::Display for CompareConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.op {
+            CompareOp::TermLt(a, b) => write!(f, "term_lt({}, {})", a, b),
+            CompareOp::Compare(order, a, b) => write!(f, "compareo({}, {}, {})", order, a, b),
+            CompareOp::Sort(l, s, false) => write!(f, "msort_terms({}, {})", l, s),
+            CompareOp::Sort(l, s, true) => write!(f, "sort({}, {})", l, s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compareo, msort_terms, sort, term_lt};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_term_lt_1() {
+        let query = proto_vulcan_query!(|q| { term_lt(1, 'a) });
+        assert!(query.run().next().is_some());
+
+        let query = proto_vulcan_query!(|q| { term_lt([1, 2], [1, 1]) });
+        assert!(query.run().next().is_none());
+
+        // A term is not before itself
+        let query = proto_vulcan_query!(|x| { term_lt(x, x) });
+        assert!(query.run().next().is_none());
+
+        // The comparison waits until the variables are bound
+        let query = proto_vulcan_query!(|x, y| {
+            term_lt([x, 1], [y, 2]),
+            conde {
+                [x == 1, y == 1],
+                [x == 2, y == 1],
+                [x == 1, y == 2],
+            },
+        });
+        let answers = query
+            .run()
+            .map(|result| format!("{} {}", result.x, result.y))
+            .collect::<Vec<String>>();
+        assert_eq!(answers, vec!["1 1", "1 2"]);
+    }
+
+    #[test]
+    fn test_term_lt_2() {
+        // An undecided comparison is shown in the answer
+        let query = proto_vulcan_query!(|x, y| { term_lt(x, y) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result.x.is_var());
+        assert!(format!("{}", result.x).contains("term_lt("));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x| {
+            x != 1,
+            term_lt(x, 2),
+        });
+        let answer = format!("{}", query.run().next().unwrap().x);
+        assert!(answer.contains("term_lt("));
+        assert!(answer.contains("!= 1"));
+    }
+
+    #[test]
+    fn test_compareo_1() {
+        let query = proto_vulcan_query!(|o1, o2, o3| {
+            compareo(o1, "a", 'a),
+            compareo(o2, [1, [2]], [1, [2]]),
+            compareo(o3, 1.5, 2),
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.o1, Atom::from(">"));
+        assert_eq!(result.o2, Atom::from("="));
+        assert_eq!(result.o3, Atom::from("<"));
+
+        // An order that is not an order atom fails before the terms are known
+        let query = proto_vulcan_query!(|x, y| { compareo('lt, x, y) });
+        assert!(query.run().next().is_none());
+
+        let greater: LTerm = LTerm::from(Atom::from(">"));
+        let query = proto_vulcan_query!(|o, x| {
+            compareo(o, x, 'b),
+            o == greater,
+            conde {
+                x == 'a,
+                x == 'c,
+            },
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, Atom::from("c"));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_sort_1() {
+        let query = proto_vulcan_query!(|s| { msort_terms([3, 'a, [], 1, 3, "x"], s) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().s, lterm!([1, 3, 3, 'a, "x", []]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|s| { sort([3, 'a, [], 1, 3, "x", 'a], s) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().s, lterm!([1, 3, 'a, "x", []]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|s| { sort([], s) });
+        assert_eq!(query.run().next().unwrap().s, lterm!([]));
+
+        let query = proto_vulcan_query!(|s| { sort(1, s) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_sort_2() {
+        // The sort waits for the tail of the list and for the order of the elements
+        let query = proto_vulcan_query!(|l, x, s| {
+            sort([2 | l], s),
+            l == [x, 1],
+            x == 2,
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().s, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x| {
+            msort_terms([x, 2], [2, 3]),
+            conde {
+                x == 1,
+                x == 3,
+            },
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, 3);
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::State;
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct CopyTermO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    t: LTerm<U, E>,
+    copy: LTerm<U, E>,
+}
+
+impl<U, E> CopyTermO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(t: LTerm<U, E>, copy: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(CopyTermO { t, copy })))
+    }
+}
+
+impl<U, E> Solve<U, E> for CopyTermO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.copy_term(&self.t, &self.copy) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// A relation where `copy` is a copy of the term `t` with its variables renamed to fresh
+/// variables. The constraints on the variables of `t`, and their finite domains and set
+/// domains, are copied to the fresh variables. The copy is made of the value of `t` when the
+/// goal is run; bindings of the variables of `t` after that do not affect the copy.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::copy_termo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, a, b| {
+///         x != 1,
+///         copy_termo([x, x], [a, b]),
+///         conde {
+///             a == 1,
+///             a == 2,
+///         },
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.a, 2);
+///     assert_eq!(result.b, 2);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn copy_termo<U, E, G>(t: LTerm<U, E>, copy: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    CopyTermO::new(t, copy)
+}
+
+#[cfg(test)]
+mod test {
+    use super::copy_termo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_copy_termo_1() {
+        // The copy does not share variables with the original
+        let query = proto_vulcan_query!(|x, y, z| {
+            copy_termo([x, y, x], [1, 2, z]),
+            x == 3,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 3);
+        assert_eq!(result.z, 1);
+        assert!(iter.next().is_none());
+
+        // Later bindings do not affect the copy
+        let query = proto_vulcan_query!(|x, c| {
+            copy_termo([x, 'a], c),
+            x == 3,
+            c == [4, 'a],
+        });
+        assert!(query.run().next().is_some());
+    }
+
+    #[test]
+    fn test_copy_termo_2() {
+        use crate::relation::symbolo;
+
+        // Disequality and type constraints are copied
+        let query = proto_vulcan_query!(|x, c| {
+            x != [1, 2],
+            copy_termo(x, c),
+            c == [1, 2],
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x, c| {
+            symbolo(x),
+            copy_termo(x, c),
+            c == 1,
+        });
+        assert!(query.run().next().is_none());
+
+        // The copy of a constraint between a copied and an uncopied variable refers to the
+        // uncopied variable
+        let query = proto_vulcan_query!(|x, y, c| {
+            x != y,
+            copy_termo(x, c),
+            c == 5,
+            y == 5,
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[cfg(feature = "clpfd")]
+    #[test]
+    fn test_copy_termo_3() {
+        use crate::relation::infdrange;
+
+        let query = proto_vulcan_query!(|x, c| {
+            infdrange(x, &(0..=3)),
+            copy_termo(x, c),
+            x == 1,
+            conde {
+                c == 5,
+                c == 2,
+            },
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.c, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[cfg(feature = "clpfd")]
+    #[test]
+    fn test_copy_termo_4() {
+        use crate::relation::{distinctfd, infdrange, ltfd};
+
+        // Finite domain propagators are copied
+        let query = proto_vulcan_query!(|x, y, a, b| {
+            infdrange([x, y], &(0..=5)),
+            ltfd(x, y),
+            copy_termo([x, y], [a, b]),
+            a == 5,
+            b == 0,
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x, y, a, b| {
+            infdrange([x, y], &(0..=1)),
+            distinctfd([x, y]),
+            copy_termo([x, y], [a, b]),
+            a == 1,
+        });
+        let answers = query
+            .run()
+            .map(|result| format!("{} {} {}", result.x, result.y, result.b))
+            .collect::<Vec<String>>();
+        assert_eq!(answers.len(), 2);
+        assert!(answers.contains(&String::from("0 1 0")));
+        assert!(answers.contains(&String::from("1 0 0")));
+
+        // The copied propagator does not constrain the original variables
+        let query = proto_vulcan_query!(|x, y, a, b| {
+            infdrange([x, y], &(0..=5)),
+            ltfd(x, y),
+            copy_termo([x, y], [a, b]),
+            a == 3,
+            x == 4,
+        });
+        let mut answers = query
+            .run()
+            .map(|result| format!("{} {}", result.y, result.b))
+            .collect::<Vec<String>>();
+        answers.sort();
+        assert_eq!(answers, vec!["5 4", "5 5"]);
+    }
+
+    #[test]
+    fn test_copy_termo_5() {
+        use crate::relation::string_length;
+
+        // Constraints of other relations are copied as well
+        let query = proto_vulcan_query!(|s, c| {
+            string_length(s, 2),
+            copy_termo(s, c),
+            c == "abc",
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|s, c| {
+            string_length(s, 2),
+            copy_termo(s, c),
+            c == "ab",
+        });
+        assert_eq!(query.run().count(), 1);
+    }
+}
@@ -176,6 +176,10 @@ where
     fn operands(&self) -> Vec<LTerm<U, E>> {
         self.0.operands()
     }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(DisequalityConstraint::new(self.walk_star(smap)))
+    }
 }
 
 impl<U, E> std::fmt::Display for DisequalityConstraint<U, E>
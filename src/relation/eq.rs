@@ -104,4 +104,50 @@ mod test {
         let mut iter = query.run();
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_eq_5() {
+        // Floats are distinct from numbers
+        let query = proto_vulcan_query!(|q| { q == [1.5, 2], q == [1.5, 2.0] });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                q == [x, 2],
+                x == 0.25,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([0.25, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[cfg(feature = "rational")]
+    #[test]
+    fn test_eq_6() {
+        use num_bigint::BigInt;
+        use num_rational::BigRational;
+
+        // Rational literals, where integral rationals are numbers
+        let query = proto_vulcan_query!(|x, y, z| {
+            [x, y, z] == [1/2, -3/4, 4/2],
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(
+            result.x.get_rational(),
+            Some(BigRational::new(BigInt::from(1), BigInt::from(2)))
+        );
+        assert_eq!(
+            result.y.get_rational(),
+            Some(BigRational::new(BigInt::from(-3), BigInt::from(4)))
+        );
+        assert_eq!(result.z, 2);
+        assert_eq!(format!("{}", result.y), "-3/4");
+
+        let query = proto_vulcan_query!(|q| { q == 1/2, q == 2/4 });
+        assert!(query.run().next().is_some());
+
+        let query = proto_vulcan_query!(|q| { q == 1/2, q == 0.5 });
+        assert!(query.run().next().is_none());
+    }
 }
@@ -0,0 +1,193 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::relation::append;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+/// A relation where `out` is the proper list of the non-list elements of the nested list `s`,
+/// in order. A term `s` that is not a list flattens to `[s]`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::flatteno;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         flatteno([1, [2, [], [3]], 4], q)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3, 4]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn flatteno<U, E, G>(s: LTerm<U, E>, out: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match s {
+            [] => out == [],
+            [head | tail] => |head_out, tail_out| {
+                flatteno(head, head_out),
+                flatteno(tail, tail_out),
+                append(head_out, tail_out, out),
+            },
+            _ => {
+                not_list(s),
+                out == [s],
+            }
+        }
+    )
+}
+
+/// A relation that succeeds for terms that are not lists.
+fn not_list<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    NotList::new(x)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+struct NotList<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+}
+
+impl<U, E> NotList<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn new<G: AnyGoal<U, E>>(x: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(NotList { x })))
+    }
+}
+
+impl<U, E> Solve<U, E> for NotList<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match Rc::new(NotListConstraint { x: self.x.clone() }).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Constraint that a term is not a list. The constraint is stored for a variable until the
+/// variable is bound.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+struct NotListConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+}
+
+impl<U, E> Constraint<U, E> for NotListConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        match state.smap_ref().walk(&self.x).as_ref() {
+            LTermInner::Empty | LTermInner::Cons(_, _) => Err(()),
+            LTermInner::Var(_, _) => Ok(state.with_constraint(self)),
+            _ => Ok(state),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.x.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(Rc::new(NotListConstraint {
+            x: smap.walk_star(&self.x),
+        }))
+    }
+}
+
+impl<U, E> std::fmt::Display for NotListConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "not_list({})", self.x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{flatteno, NotListConstraint};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_flatteno_1() {
+        let query = proto_vulcan_query!(|q| { flatteno([[1, 2], [[]], [[3]]], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { flatteno(5, q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([5]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { flatteno([1, [2]], [1, 3]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_flatteno_2() {
+        // An unbound term flattens to itself when it is not a list.
+        let query = proto_vulcan_query!(|x, y| { flatteno([x, 1], [2, y]) });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.y, 1);
+
+        let query = proto_vulcan_query!(|x| { flatteno(x, [x]) });
+        assert!(query.run().next().unwrap().x.is_any());
+    }
+
+    #[test]
+    fn test_flatteno_3() {
+        // Nested lists that flatten to the given list. There are infinitely many of them,
+        // because empty lists can be added anywhere.
+        let query = proto_vulcan_query!(|q| {
+            q == [_, _],
+            flatteno(q, [1, 2]),
+        });
+        for result in query.run().take(10) {
+            let l: LTerm = (*result.q).clone();
+            let query = proto_vulcan_query!(|r| { flatteno(l, r) });
+            assert_eq!(query.run().next().unwrap().r, lterm!([1, 2]));
+        }
+    }
+
+    #[test]
+    fn test_not_list_display() {
+        let c: NotListConstraint<DefaultUser, DefaultEngine<DefaultUser>> =
+            NotListConstraint { x: lterm!(5) };
+        assert_eq!(format!("{}", c), "not_list(5)");
+    }
+}
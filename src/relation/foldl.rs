@@ -0,0 +1,112 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+use std::rc::Rc;
+
+type Relation3<U, E, G> =
+    Rc<dyn Fn(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G>>;
+
+/// A relation that folds the list `l` from the left with the relation `f`. The relation
+/// `f(x, acc0, acc1)` relates an element `x` and the accumulator `acc0` to the next
+/// accumulator `acc1`; `v0` is the initial and `v` the final accumulator.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{foldl, infdrange, plusfd};
+/// fn main() {
+///     let query = proto_vulcan_query!(|sum| {
+///         foldl(
+///             |x, acc0, acc1| proto_vulcan!([infdrange(acc1, &(0..=10)), plusfd(x, acc0, acc1)]),
+///             [1, 2, 3],
+///             0,
+///             sum
+///         )
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().sum, 6);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn foldl<U, E, G, F>(
+    f: F,
+    l: LTerm<U, E>,
+    v0: LTerm<U, E>,
+    v: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    F: Fn(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G> + 'static,
+{
+    foldl_rec(Rc::new(f), l, v0, v)
+}
+
+fn foldl_rec<U, E, G>(
+    f: Relation3<U, E, G>,
+    l: LTerm<U, E>,
+    v0: LTerm<U, E>,
+    v: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [] => v0 == v,
+            [x | xs] => |v1| {
+                f(x, v0, v1),
+                foldl_rec({Rc::clone(&f)}, xs, v1, v),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::foldl;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_foldl_1() {
+        // Reverse a list by consing each element onto the accumulator.
+        let query = proto_vulcan_query!(|q| {
+            foldl(
+                |x, acc0, acc1| proto_vulcan!(acc1 == [x | acc0]),
+                [1, 2, 3],
+                [],
+                q,
+            )
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([3, 2, 1]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            foldl(|x, acc0, acc1| proto_vulcan!(acc1 == [x | acc0]), [], 0, q)
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 0);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_foldl_2() {
+        // Elements of a list of unknown length
+        let query = proto_vulcan_query!(|q| {
+            foldl(
+                |x, acc0, acc1| proto_vulcan!(acc1 == [x | acc0]),
+                q,
+                [],
+                [1, 2],
+            )
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([2, 1]));
+    }
+}
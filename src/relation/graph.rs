@@ -0,0 +1,376 @@
+//! # Graph relations
+//! Relations over directed graphs. The path relations take the edges of the graph as a
+//! relation `edge(x, y)` that succeeds when there is an edge from node `x` to node `y`. The
+//! edge relation can be a table of facts, or `edgeo` over a graph that is represented as an
+//! adjacency list: a list of two-element lists `[node, successors]`, where `successors` is
+//! the list of the nodes that have an edge from `node`.
+//!
+//! * `edgeo(graph, x, y)`: the adjacency list `graph` has an edge from `x` to `y`
+//! * `patho(edge, x, y, path)`: `path` is a list of nodes from `x` to `y` along the edges,
+//!   in which no node is visited twice
+//! * `reachableo(edge, x, y)`: there is a path from `x` to `y`; each reachable node `y` is
+//!   given once
+//! * `shortest_patho(edge, x, y, path, n)`: `path` is one of the shortest paths from `x` to
+//!   `y`, and it has `n` edges
+//!
+//! The paths are acyclic: each step constrains the next node to be different from the
+//! visited nodes, so the search terminates on finite graphs also when the graph has cycles.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::{edgeo, patho};
+//! fn main() {
+//!     let graph: LTerm = lterm!([['a, ['b, 'c]], ['b, ['c]], ['c, ['a]]]);
+//!     let query = proto_vulcan_query!(|path| {
+//!         patho(move |x, y| edgeo(graph.clone(), x, y), 'a, 'c, path)
+//!     });
+//!     let mut answers = query
+//!         .run()
+//!         .map(|result| format!("{}", result.path))
+//!         .collect::<Vec<String>>();
+//!     answers.sort();
+//!     assert_eq!(answers, vec!["[a, b, c]", "[a, c]"]);
+//! }
+//! ```
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, Goal};
+use crate::lterm::LTerm;
+use crate::operator::conde;
+#[cfg(feature = "clpfd")]
+use crate::operator::condu;
+#[cfg(feature = "clpfd")]
+use crate::relation::ltfd;
+use crate::relation::member;
+use crate::solver::Solver;
+use crate::state::State;
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+type EdgeRelation<U, E> = Rc<dyn Fn(LTerm<U, E>, LTerm<U, E>) -> Goal<U, E>>;
+
+/// A relation that succeeds when the graph given as the adjacency list `graph` has an edge
+/// from node `x` to node `y`.
+pub fn edgeo<U, E>(graph: LTerm<U, E>, x: LTerm<U, E>, y: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan!(|successors| {
+        member([x, successors], graph),
+        member(y, successors),
+    })
+}
+
+/// Constrains `x` to be different from each element of the list `l`.
+fn not_membero<U, E>(x: LTerm<U, E>, l: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [] => ,
+            [y | rest] => {
+                x != y,
+                not_membero(x, rest),
+            }
+        }
+    )
+}
+
+/// A relation where `path` is a list of nodes from `x` to `y` such that there is an edge
+/// between consecutive nodes, and no node is visited twice.
+pub fn patho<U, E, F>(edge: F, x: LTerm<U, E>, y: LTerm<U, E>, path: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    F: Fn(LTerm<U, E>, LTerm<U, E>) -> Goal<U, E> + 'static,
+{
+    let visited = LTerm::singleton(x.clone());
+    patho_rec(Rc::new(edge), x, y, path, visited)
+}
+
+fn patho_rec<U, E>(
+    edge: EdgeRelation<U, E>,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+    path: LTerm<U, E>,
+    visited: LTerm<U, E>,
+) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [x == y, path == [x]],
+        |z, rest| {
+            x != y,
+            edge(x, z),
+            not_membero(z, visited),
+            path == [x | rest],
+            patho_rec({Rc::clone(&edge)}, z, y, rest, [z | visited]),
+        },
+    })
+}
+
+/// The nodes that have an edge from `x` in `state`.
+fn successors<U, E>(
+    solver: &Solver<U, E>,
+    state: &State<U, E>,
+    edge: &EdgeRelation<U, E>,
+    x: LTerm<U, E>,
+) -> Vec<LTerm<U, E>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let z = LTerm::var("z");
+    let mut stream = solver.start(&edge(x, z.clone()), state.clone());
+    let mut nodes = vec![];
+    while let Some(state) = solver.peek(&mut stream) {
+        nodes.push(state.smap_ref().walk_star(&z));
+        stream = match std::mem::replace(&mut stream, Stream::Empty) {
+            Stream::Cons(_, lazy) => Stream::Lazy(lazy),
+            _ => Stream::Empty,
+        };
+    }
+    nodes
+}
+
+/// A relation where `y` is a node that is reachable from `x`, including `x` itself. The
+/// relation succeeds once for each reachable node.
+///
+/// The nodes are found in breadth-first order before any of them is returned, so the node
+/// `x` must be known and the graph must be finite. The edge relation should bind the
+/// successors of a known node.
+pub fn reachableo<U, E, F>(edge: F, x: LTerm<U, E>, y: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    F: Fn(LTerm<U, E>, LTerm<U, E>) -> Goal<U, E> + 'static,
+{
+    let edge: EdgeRelation<U, E> = Rc::new(edge);
+    proto_vulcan!(fngoal move |solver, state| {
+        let mut nodes = vec![state.smap_ref().walk_star(&x)];
+        let mut i = 0;
+        while i < nodes.len() {
+            for z in successors(solver, &state, &edge, nodes[i].clone()) {
+                if !nodes.contains(&z) {
+                    nodes.push(z);
+                }
+            }
+            i += 1;
+        }
+        let nodes = LTerm::from_vec(nodes);
+        let g: Goal<U, E> = proto_vulcan!(member(y, nodes));
+        g.solve(solver, state)
+    })
+}
+
+/// Paths of exactly `k` edges
+#[cfg(feature = "clpfd")]
+fn bounded_patho<U, E>(
+    edge: EdgeRelation<U, E>,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+    path: LTerm<U, E>,
+    visited: LTerm<U, E>,
+    k: isize,
+) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    if k == 0 {
+        proto_vulcan!([x == y, path == [x]])
+    } else {
+        proto_vulcan_closure!(|z, rest| {
+            edge(x, z),
+            not_membero(z, visited),
+            path == [x | rest],
+            bounded_patho({Rc::clone(&edge)}, z, y, rest, [z | visited], {k - 1}),
+        })
+    }
+}
+
+/// A relation where `path` is one of the shortest paths from `x` to `y`, and `n` is the
+/// number of edges in it.
+///
+/// The paths are searched in the order of their length, and `n` must have a finite domain
+/// that bounds the search; when there is no path with a length in the domain, the relation
+/// fails after trying the lengths up to the upper bound of the domain. The nodes `x` and `y`
+/// should be known, because the search commits to the length of the first path found.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, shortest_patho};
+/// fn edge<U: User, E: Engine<U>>(x: LTerm<U, E>, y: LTerm<U, E>) -> Goal<U, E> {
+///     proto_vulcan!(match [x, y] {
+///         ['a, 'b] | ['b, 'c] | ['c, 'd] | ['a, 'd] | ['d, 'e] => ,
+///     })
+/// }
+/// fn main() {
+///     let query = proto_vulcan_query!(|path, n| {
+///         infdrange(n, &(0..=10)),
+///         shortest_patho(|x, y| edge(x, y), 'a, 'e, path, n),
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.path, lterm!(['a, 'd, 'e]));
+///     assert_eq!(result.n, 2);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+#[cfg(feature = "clpfd")]
+pub fn shortest_patho<U, E, F>(
+    edge: F,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+    path: LTerm<U, E>,
+    n: LTerm<U, E>,
+) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    F: Fn(LTerm<U, E>, LTerm<U, E>) -> Goal<U, E> + 'static,
+{
+    shortest_from(Rc::new(edge), x, y, path, n, 0)
+}
+
+#[cfg(feature = "clpfd")]
+fn shortest_from<U, E>(
+    edge: EdgeRelation<U, E>,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+    path: LTerm<U, E>,
+    n: LTerm<U, E>,
+    k: isize,
+) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let len = LTerm::from(k);
+    proto_vulcan_closure!(condu {
+        [
+            |p| {
+                n == len,
+                bounded_patho({Rc::clone(&edge)}, x, y, p, [x], {k}),
+            },
+            n == len,
+            bounded_patho({Rc::clone(&edge)}, x, y, path, [x], {k}),
+        ],
+        [
+            ltfd(len, n),
+            shortest_from({Rc::clone(&edge)}, x, y, path, n, {k + 1}),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{edgeo, patho, reachableo};
+    use crate::prelude::*;
+
+    fn edge<U: User, E: Engine<U>>(x: LTerm<U, E>, y: LTerm<U, E>) -> Goal<U, E> {
+        proto_vulcan!(match [x, y] {
+            [1, 2] | [2, 3] | [3, 1] | [3, 4] | [5, 6] => ,
+        })
+    }
+
+    #[test]
+    fn test_edgeo_1() {
+        let graph: LTerm = lterm!([[1, [2, 3]], [2, [3]], [3, []]]);
+        let query = proto_vulcan_query!(|x, y| { edgeo(graph, x, y) });
+        let answers = query
+            .run()
+            .map(|result| format!("{} {}", result.x, result.y))
+            .collect::<Vec<String>>();
+        assert_eq!(answers, vec!["1 2", "1 3", "2 3"]);
+    }
+
+    #[test]
+    fn test_patho_1() {
+        // Cycle 1 -> 2 -> 3 -> 1
+        let query = proto_vulcan_query!(|path| { patho(|x, y| edge(x, y), 1, 4, path) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().path, lterm!([1, 2, 3, 4]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|path| { patho(|x, y| edge(x, y), 1, 1, path) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().path, lterm!([1]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|path| { patho(|x, y| edge(x, y), 1, 5, path) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_reachableo_1() {
+        let query = proto_vulcan_query!(|q| { reachableo(|x, y| edge(x, y), 2, q) });
+        let mut answers = query
+            .run()
+            .map(|result| format!("{}", result.q))
+            .collect::<Vec<String>>();
+        answers.sort();
+        assert_eq!(answers, vec!["1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn test_reachableo_2() {
+        // Node 4 is reachable along two paths, and node 1 is on a cycle.
+        let graph: LTerm = lterm!([[1, [2, 3]], [2, [4]], [3, [4]], [4, [1]]]);
+        let query =
+            proto_vulcan_query!(|q| { reachableo(move |x, y| edgeo(graph.clone(), x, y), 1, q) });
+        let mut answers = query
+            .run()
+            .map(|result| format!("{}", result.q))
+            .collect::<Vec<String>>();
+        answers.sort();
+        assert_eq!(answers, vec!["1", "2", "3", "4"]);
+
+        let query = proto_vulcan_query!(|q| { reachableo(|x, y| edge(x, y), 1, 4) });
+        assert_eq!(query.run().count(), 1);
+
+        let query = proto_vulcan_query!(|q| { reachableo(|x, y| edge(x, y), 1, 5) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[cfg(feature = "clpfd")]
+    #[test]
+    fn test_shortest_patho_1() {
+        use super::shortest_patho;
+        use crate::relation::infdrange;
+
+        let graph: LTerm = lterm!([[1, [2, 3]], [2, [4]], [3, [4]], [4, [1]]]);
+        let query = proto_vulcan_query!(|path, n| {
+            infdrange(n, &(0..=10)),
+            shortest_patho(move |x, y| edgeo(graph.clone(), x, y), 1, 4, path, n),
+        });
+        let mut answers = query
+            .run()
+            .map(|result| format!("{} {}", result.path, result.n))
+            .collect::<Vec<String>>();
+        answers.sort();
+        assert_eq!(answers, vec!["[1, 2, 4] 2", "[1, 3, 4] 2"]);
+
+        // No path within the bounds
+        let query = proto_vulcan_query!(|path, n| {
+            infdrange(n, &(0..=1)),
+            shortest_patho(|x, y| edge(x, y), 1, 4, path, n),
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|path, n| {
+            infdrange(n, &(0..=10)),
+            shortest_patho(|x, y| edge(x, y), 1, 5, path, n),
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::engine::Engine;
+use crate::goal::Goal;
+use crate::lterm::LTerm;
+use crate::operator::condu;
+use crate::user::User;
+use std::rc::Rc;
+
+type Relation1<U, E> = Rc<dyn Fn(LTerm<U, E>) -> Goal<U, E>>;
+
+/// A relation where `included` is the list of the elements of `l` for which the relation `f`
+/// succeeds, in order.
+///
+/// The list `l` can be partially instantiated, but whether `f` succeeds for an element is
+/// decided with `condu`, which commits to the first answer of the decision. An element that is
+/// not instantiated enough for `f` to decide is bound by the first answer of `f`: for example
+/// `include(|x| x == 1, [y], q)` has the single answer where `y` is `1` and `q` is `[1]`, and
+/// the answers where `y` is not `1` and `q` is `[]` are lost.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{include, numbero};
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         include(|x| proto_vulcan!(numbero(x)), [1, "a", 2, 'b'], q)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn include<U, E, F>(f: F, l: LTerm<U, E>, included: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    F: Fn(LTerm<U, E>) -> Goal<U, E> + 'static,
+{
+    include_rec(Rc::new(f), l, included)
+}
+
+fn include_rec<U, E>(f: Relation1<U, E>, l: LTerm<U, E>, included: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [] => included == [],
+            [x | xs] => |rest| {
+                condu {
+                    [f(x), included == [x | rest]],
+                    [included == rest],
+                },
+                include_rec({Rc::clone(&f)}, xs, rest),
+            }
+        }
+    )
+}
+
+/// A relation where `excluded` is the list of the elements of `l` for which the relation `f`
+/// fails, in order.
+///
+/// As with `include`, whether `f` succeeds for an element is decided with `condu`, and an
+/// element that is not instantiated enough for `f` to decide is bound by the first answer of
+/// `f`, so that the answers where `f` fails for the element are lost.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::exclude;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         exclude(|x| proto_vulcan!(x == 1), [1, 2, 1, 3], q)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([2, 3]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn exclude<U, E, F>(f: F, l: LTerm<U, E>, excluded: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    F: Fn(LTerm<U, E>) -> Goal<U, E> + 'static,
+{
+    exclude_rec(Rc::new(f), l, excluded)
+}
+
+fn exclude_rec<U, E>(f: Relation1<U, E>, l: LTerm<U, E>, excluded: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [] => excluded == [],
+            [x | xs] => |rest| {
+                condu {
+                    [f(x), excluded == rest],
+                    [excluded == [x | rest]],
+                },
+                exclude_rec({Rc::clone(&f)}, xs, rest),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{exclude, include};
+    use crate::prelude::*;
+    use crate::relation::member;
+
+    #[test]
+    fn test_include_1() {
+        let query = proto_vulcan_query!(|q| {
+            include(|x| proto_vulcan!(member(x, [2, 3])), [1, 2, 3, 4, 3], q)
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([2, 3, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            include(|x| proto_vulcan!(member(x, [2, 3])), [1, 2], [1, 2])
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_include_2() {
+        // Partially instantiated lists
+        let query = proto_vulcan_query!(|x, y| {
+            include(
+                |z| proto_vulcan!(member(z, [2, 3])),
+                [1, 2, 3, 4, 3],
+                [x | y],
+            )
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.y, lterm!([3, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q, t| {
+            q == [1, 2 | t],
+            t == [3, 1],
+            include(|x| proto_vulcan!(x == 1), q, [1, 1]),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3, 1]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_include_3() {
+        // The relation succeeds more than once for an element
+        let query = proto_vulcan_query!(|q| {
+            include(|x| proto_vulcan!(member(x, [1, 1])), [1, 2, 1], q)
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 1]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_include_4() {
+        // An unbound element is committed to the first answer of the relation, and the answer
+        // where the element is excluded is not found.
+        let query = proto_vulcan_query!(|y, q| { include(|x| proto_vulcan!(x == 1), [y], q) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.y, 1);
+        assert_eq!(result.q, lterm!([1]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|y, q| { exclude(|x| proto_vulcan!(x == 1), [y], q) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.y, 1);
+        assert_eq!(result.q, lterm!([]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_exclude_1() {
+        let query = proto_vulcan_query!(|q| {
+            exclude(|x| proto_vulcan!(member(x, [2, 3])), [1, 2, 3, 4, 3], q)
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 4]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { exclude(|x| proto_vulcan!(x == 1), [], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+    }
+}
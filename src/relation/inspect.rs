@@ -0,0 +1,534 @@
+//! # Term inspection
+//! Relations that take terms apart and put them together without knowing their types, in
+//! the spirit of the Prolog built-ins `functor/3`, `arg/3` and `=../2`.
+//!
+//! * `functoro(term, name, arity)`: `term` has the name `name` and `arity` arguments
+//! * `argo(n, term, arg)`: `arg` is the `n`th argument of `term`, counting from one
+//! * `univo(term, list)`: `list` is the name of `term` followed by its arguments
+//!
+//! The name of a compound is its type name as an atom, and the arguments are its fields.
+//! Non-empty lists have the name `'[|]'` and two arguments, the head and the tail. Other
+//! terms are atomic: their name is the term itself, and they have no arguments.
+//!
+//! A compound can be constructed from its name and arguments when its type has been
+//! registered with [`register_compound`](crate::compound::register_compound). The relations
+//! are constraints that wait until either the term or its name and arguments are known.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::compound::register_compound;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::{argo, functoro, univo};
+//!
+//! #[compound]
+//! struct Point(LTerm, LTerm);
+//!
+//! fn main() {
+//!     register_compound(|| -> LTerm { Point::new_template().into() });
+//!     let query = proto_vulcan_query!(|name, arity, y, list| {
+//!         |p| {
+//!             univo(p, ['Point, 1, 2]),
+//!             functoro(p, name, arity),
+//!             argo(2, p, y),
+//!             univo(p, list),
+//!         }
+//!     });
+//!     let result = query.run().next().unwrap();
+//!     assert_eq!(result.name, Atom::from("Point"));
+//!     assert_eq!(result.arity, 2);
+//!     assert_eq!(result.y, 2);
+//!     assert_eq!(result.list, lterm!(['Point, 1, 2]));
+//! }
+//! ```
+use crate::atom::Atom;
+use crate::compound::{compound_template, CompoundObject};
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, Goal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::operator::conde;
+use crate::relation::compare::list_elements;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+/// Name of non-empty lists
+const CONS_NAME: &str = "[|]";
+
+/// Term inspection relations
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+enum InspectOp<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    Functor(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>),
+    Univ(LTerm<U, E>, LTerm<U, E>),
+}
+
+impl<U, E> InspectOp<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn terms(&self) -> Vec<&LTerm<U, E>> {
+        match self {
+            InspectOp::Functor(t, name, arity) => vec![t, name, arity],
+            InspectOp::Univ(t, list) => vec![t, list],
+        }
+    }
+
+    fn walk_star(&self, smap: &SMap<U, E>) -> InspectOp<U, E> {
+        match self {
+            InspectOp::Functor(t, name, arity) => InspectOp::Functor(
+                smap.walk_star(t),
+                smap.walk_star(name),
+                smap.walk_star(arity),
+            ),
+            InspectOp::Univ(t, list) => InspectOp::Univ(smap.walk_star(t), smap.walk_star(list)),
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct InspectO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    op: InspectOp<U, E>,
+}
+
+impl<U, E> InspectO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn new<G: AnyGoal<U, E>>(op: InspectOp<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(InspectO { op })))
+    }
+}
+
+impl<U, E> Solve<U, E> for InspectO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let constraint = Rc::new(InspectConstraint {
+            op: self.op.clone(),
+        });
+        match constraint.run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// A relation where `name` and `arity` are the name and the number of arguments of `term`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::functoro;
+/// fn main() {
+///     let query = proto_vulcan_query!(|name, arity, t| {
+///         functoro([1, 2], name, arity),
+///         functoro(t, 'foo, 0),
+///     });
+///     let result = query.run().next().unwrap();
+///     assert_eq!(result.name, Atom::from("[|]"));
+///     assert_eq!(result.arity, 2);
+///     assert_eq!(result.t, Atom::from("foo"));
+/// }
+/// ```
+pub fn functoro<U, E, G>(
+    term: LTerm<U, E>,
+    name: LTerm<U, E>,
+    arity: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    InspectO::new(InspectOp::Functor(term, name, arity))
+}
+
+/// A relation where `list` is the name of `term` followed by the arguments of `term`.
+pub fn univo<U, E, G>(term: LTerm<U, E>, list: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    InspectO::new(InspectOp::Univ(term, list))
+}
+
+/// A relation where `arg` is the `n`th argument of `term`, counting from one. When `n` is not
+/// known, the relation succeeds once for each argument.
+pub fn argo<U, E>(n: LTerm<U, E>, term: LTerm<U, E>, arg: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan!(|name, args| {
+        univo(term, [name | args]),
+        nth_argo(n, args, arg, {1isize}),
+    })
+}
+
+fn nth_argo<U, E>(n: LTerm<U, E>, args: LTerm<U, E>, arg: LTerm<U, E>, k: isize) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let index = LTerm::from(k);
+    proto_vulcan_closure!(conde {
+        [n == index, args == [arg | _]],
+        |rest| {
+            args == [_ | rest],
+            nth_argo(n, rest, arg, {k + 1}),
+        },
+    })
+}
+
+/// Returns the name and the arguments of a term that is not a variable.
+fn decompose<U, E>(smap: &SMap<U, E>, t: &LTerm<U, E>) -> (LTerm<U, E>, Vec<LTerm<U, E>>)
+where
+    U: User,
+    E: Engine<U>,
+{
+    match t.as_ref() {
+        LTermInner::Compound(object) => {
+            let name = LTerm::from(Atom::from(object.type_name()));
+            let args = object
+                .children()
+                .map(|child| child_term(smap, child))
+                .collect();
+            (name, args)
+        }
+        LTermInner::Cons(head, tail) => (
+            LTerm::from(Atom::from(CONS_NAME)),
+            vec![head.clone(), tail.clone()],
+        ),
+        _ => (t.clone(), vec![]),
+    }
+}
+
+fn child_term<U, E>(smap: &SMap<U, E>, child: &dyn CompoundObject<U, E>) -> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match child.as_term() {
+        Some(t) => t.clone(),
+        None => child.walk_star(smap),
+    }
+}
+
+/// Constructs the term with the name and the arguments, and unifies it with `t`. Returns
+/// `None` if the name is not known yet.
+fn construct<U, E>(
+    state: State<U, E>,
+    t: &LTerm<U, E>,
+    name: &LTerm<U, E>,
+    args: Vec<LTerm<U, E>>,
+) -> Result<Option<State<U, E>>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let name = state.smap_ref().walk(name).clone();
+    match name.as_ref() {
+        LTermInner::Var(_, _) => Ok(None),
+        LTermInner::Cons(_, _) | LTermInner::Compound(_) => Err(()),
+        _ if args.is_empty() => state.unify(t, &name).map(Some),
+        LTermInner::Val(LValue::Atom(atom)) if atom.as_str() == CONS_NAME && args.len() == 2 => {
+            state
+                .unify(t, &LTerm::cons(args[0].clone(), args[1].clone()))
+                .map(Some)
+        }
+        LTermInner::Val(LValue::Atom(atom)) => {
+            let template = compound_template(atom.as_str(), args.len()).ok_or(())?;
+            let fields = match template.as_ref() {
+                LTermInner::Compound(object) => object
+                    .children()
+                    .map(|child| child_term(state.smap_ref(), child))
+                    .collect::<Vec<LTerm<U, E>>>(),
+                _ => return Err(()),
+            };
+            let mut state = state;
+            for (field, arg) in fields.iter().zip(args.iter()) {
+                state = state.unify(field, arg)?;
+            }
+            state.unify(t, &template).map(Some)
+        }
+        _ => Err(()),
+    }
+}
+
+/// Term inspection constraint
+///
+/// The constraint is stored until the term, or its name and arguments, are known.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct InspectConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    op: InspectOp<U, E>,
+}
+
+impl<U, E> InspectConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn wait(&self, state: State<U, E>) -> SResult<U, E> {
+        let op = self.op.walk_star(state.smap_ref());
+        Ok(state.with_constraint(Rc::new(InspectConstraint { op })))
+    }
+}
+
+impl<U, E> Constraint<U, E> for InspectConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        match &self.op {
+            InspectOp::Functor(t, name, arity) => {
+                let twalk = state.smap_ref().walk(t).clone();
+                if !twalk.is_var() {
+                    let (tname, args) = decompose(state.smap_ref(), &twalk);
+                    let state = state.unify(name, &tname)?;
+                    return state.unify(arity, &LTerm::from(args.len() as isize));
+                }
+                let n = match state.smap_ref().walk(arity).as_ref() {
+                    LTermInner::Var(_, _) => return self.wait(state),
+                    LTermInner::Val(LValue::Number(n)) if *n >= 0 => *n as usize,
+                    _ => return Err(()),
+                };
+                let args = (0..n).map(|_| LTerm::any()).collect();
+                match construct(state.clone(), &twalk, name, args)? {
+                    Some(state) => Ok(state),
+                    None => self.wait(state),
+                }
+            }
+            InspectOp::Univ(t, list) => {
+                let twalk = state.smap_ref().walk(t).clone();
+                if !twalk.is_var() {
+                    let (tname, mut args) = decompose(state.smap_ref(), &twalk);
+                    args.insert(0, tname);
+                    return state.unify(list, &LTerm::from_vec(args));
+                }
+                match list_elements(state.smap_ref(), list)? {
+                    Some(mut elements) if !elements.is_empty() => {
+                        let name = elements.remove(0);
+                        match construct(state.clone(), &twalk, &name, elements)? {
+                            Some(state) => Ok(state),
+                            None => self.wait(state),
+                        }
+                    }
+                    Some(_) => Err(()),
+                    None => self.wait(state),
+                }
+            }
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        self.op
+            .terms()
+            .into_iter()
+            .flat_map(|t| t.anyvars())
+            .collect()
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(Rc::new(InspectConstraint {
+            op: self.op.walk_star(smap),
+        }))
+    }
+}
+
+impl<U, E> std::fmt::Display for InspectConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.op {
+            InspectOp::Functor(t, name, arity) => {
+                write!(f, "functoro({}, {}, {})", t, name, arity)
+            }
+            InspectOp::Univ(t, list) => write!(f, "univo({}, {})", t, list),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{argo, functoro, univo};
+    use crate::compound::register_compound;
+    use crate::prelude::*;
+
+    #[compound]
+    struct Pair(LTerm, LTerm);
+
+    #[compound]
+    struct Named {
+        a: LTerm,
+        b: LTerm,
+    }
+
+    fn register_pair() {
+        register_compound(|| -> LTerm { Pair::new_template().into() });
+    }
+
+    #[test]
+    fn test_functoro_1() {
+        let query = proto_vulcan_query!(|name, arity| {
+            |p| {
+                p == Pair(1, []),
+                functoro(p, name, arity),
+            }
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.name, Atom::from("Pair"));
+        assert_eq!(result.arity, 2);
+
+        let query = proto_vulcan_query!(|name, arity| { functoro("s", name, arity) });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.name, "s");
+        assert_eq!(result.arity, 0);
+
+        let query = proto_vulcan_query!(|name, arity| { functoro([], name, arity) });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.name, lterm!([]));
+        assert_eq!(result.arity, 0);
+
+        // Atomic terms have no arguments
+        let query = proto_vulcan_query!(|t| { functoro(t, 1, 2) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_functoro_2() {
+        register_pair();
+        let query = proto_vulcan_query!(|q| {
+            |p| {
+                functoro(p, 'Pair, 2),
+                p == Pair(q, []),
+                q == 5,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 5);
+        assert!(iter.next().is_none());
+
+        // The constraint waits for the arity
+        let cons: LTerm = LTerm::from(Atom::from("[|]"));
+        let query = proto_vulcan_query!(|t, arity| {
+            functoro(t, cons, arity),
+            arity == 2,
+        });
+        let result = query.run().next().unwrap();
+        assert!(result.t.is_list());
+
+        // Unregistered compounds cannot be constructed
+        let query = proto_vulcan_query!(|t| { functoro(t, 'Pair, 3) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_univo_1() {
+        register_pair();
+        let query = proto_vulcan_query!(|q| {
+            |p| {
+                p == Pair(1, Pair(2, [])),
+                univo(p, q),
+            }
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(format!("{}", result.q), "[Pair, 1, Pair(2, Empty)]");
+
+        let query = proto_vulcan_query!(|q| { univo([1, 2], q) });
+        let result = query.run().next().unwrap();
+        assert_eq!(format!("{}", result.q), "[[|], 1, [2]]");
+
+        let query = proto_vulcan_query!(|q| { univo(q, ['Pair, 1, []]) });
+        let result = query.run().next().unwrap();
+        assert_eq!(format!("{}", result.q), "Pair(1, Empty)");
+
+        let query = proto_vulcan_query!(|q| { univo(q, [1]) });
+        assert_eq!(query.run().next().unwrap().q, 1);
+
+        let query = proto_vulcan_query!(|q| { univo(q, []) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_univo_2() {
+        // Round trip through construction and decomposition
+        register_pair();
+        let query = proto_vulcan_query!(|x, y| {
+            |p, m| {
+                univo(p, ['Pair, x, m]),
+                m == Pair(2, []),
+                p == Pair(1, Pair(y, [])),
+            }
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(result.x, 1);
+        assert_eq!(result.y, 2);
+    }
+
+    #[test]
+    fn test_univo_3() {
+        // Construction of a compound with named fields
+        register_compound(|| -> LTerm { Named::new_template().into() });
+        let query = proto_vulcan_query!(|y| {
+            |p| {
+                univo(p, ['Named, 1, 2]),
+                match p {
+                    Named { a: 1, b } => y == b,
+                },
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().y, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_argo_1() {
+        let query = proto_vulcan_query!(|q| { argo(2, ['a, 'b], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!(['b]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|n, q| {
+            |p| {
+                p == Pair(7, []),
+                argo(n, p, q),
+            }
+        });
+        let answers = query
+            .run()
+            .map(|result| format!("{} {}", result.n, result.q))
+            .collect::<Vec<String>>();
+        assert_eq!(answers, vec!["1 7", "2 []"]);
+
+        let query = proto_vulcan_query!(|q| { argo(1, 'a, q) });
+        assert!(query.run().next().is_none());
+    }
+}
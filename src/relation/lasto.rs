@@ -0,0 +1,59 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+
+/// A relation where `x` is the last element of the proper list `l`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::lasto;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         lasto([1, 2, 3], q)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, 3);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn lasto<U, E, G>(l: LTerm<U, E>, x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(match l {
+        [y] => x == y,
+        [_, y | rest] => lasto([y | rest], x),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::lasto;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_lasto_1() {
+        let query = proto_vulcan_query!(|q| { lasto([1, 2, 3], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { lasto([], q) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_lasto_2() {
+        let query = proto_vulcan_query!(|q| { lasto([1, 2 | q], 3) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([3]));
+        let result = iter.next().unwrap();
+        assert_eq!(result.q.iter().count(), 2);
+        assert_eq!(result.q.iter().last().unwrap(), &3);
+    }
+}
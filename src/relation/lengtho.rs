@@ -0,0 +1,85 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::{infdrange, plusfd};
+use crate::user::User;
+
+/// A relation where `n` is the length of the proper list `l`. The length is a finite domain
+/// variable.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::lengtho;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         lengtho(q, 2)
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert!(result.q.is_list() && result.q.iter().count() == 2);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn lengtho<U, E, G>(l: LTerm<U, E>, n: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [] => n == 0,
+            [_ | rest] => |m| {
+                infdrange(n, &(1..=isize::MAX)),
+                infdrange(m, &(0..=isize::MAX)),
+                plusfd(m, 1, n),
+                lengtho(rest, m),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::lengtho;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_lengtho_1() {
+        let query = proto_vulcan_query!(|q| { lengtho([1, 2, 3], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { lengtho([1, 2, 3], 2) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_lengtho_2() {
+        let query = proto_vulcan_query!(|q| { lengtho([1 | q], 3) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.q.iter().count(), 2);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { lengtho(q, 0) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_lengtho_3() {
+        // Lists are enumerated in the order of their lengths.
+        let query = proto_vulcan_query!(|l, n| { lengtho(l, n) });
+        let lengths = query
+            .run()
+            .take(3)
+            .map(|result| result.n.get_number().unwrap())
+            .collect::<Vec<isize>>();
+        assert_eq!(lengths, vec![0, 1, 2]);
+    }
+}
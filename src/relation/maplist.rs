@@ -0,0 +1,262 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+use std::rc::Rc;
+
+type Relation1<U, E, G> = Rc<dyn Fn(LTerm<U, E>) -> InferredGoal<U, E, G>>;
+type Relation2<U, E, G> = Rc<dyn Fn(LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G>>;
+type Relation3<U, E, G> =
+    Rc<dyn Fn(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G>>;
+type Relation4<U, E, G> =
+    Rc<dyn Fn(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G>>;
+
+/// A relation that succeeds when the relation `f` holds for every element of the list `l`.
+///
+/// The relation `f` is a Rust closure that returns a goal, such as
+/// `|x| proto_vulcan!(numbero(x))`. If the list is only partially instantiated, lists of increasing lengths are enumerated.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::maplist;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         q == [_, _],
+///         maplist(|x| proto_vulcan!(x == 1), q),
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 1]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn maplist<U, E, G, F>(f: F, l: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    F: Fn(LTerm<U, E>) -> InferredGoal<U, E, G> + 'static,
+{
+    maplist1_rec(Rc::new(f), l)
+}
+
+fn maplist1_rec<U, E, G>(f: Relation1<U, E, G>, l: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [] => ,
+            [x | xs] => {
+                f(x),
+                maplist1_rec({Rc::clone(&f)}, xs),
+            }
+        }
+    )
+}
+
+/// A relation that succeeds when the relation `f` holds for the corresponding elements of
+/// the lists `l1` and `l2`, which have the same length.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, maplist2, plusfd};
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         maplist2(
+///             |x, y| proto_vulcan!([infdrange(y, &(0..=10)), plusfd(x, 1, y)]),
+///             [1, 2, 3],
+///             q
+///         ),
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([2, 3, 4]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn maplist2<U, E, G, F>(f: F, l1: LTerm<U, E>, l2: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    F: Fn(LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G> + 'static,
+{
+    maplist2_rec(Rc::new(f), l1, l2)
+}
+
+fn maplist2_rec<U, E, G>(
+    f: Relation2<U, E, G>,
+    l1: LTerm<U, E>,
+    l2: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [l1, l2] {
+            [[], []] => ,
+            [[x | xs], [y | ys]] => {
+                f(x, y),
+                maplist2_rec({Rc::clone(&f)}, xs, ys),
+            }
+        }
+    )
+}
+
+/// A relation that succeeds when the relation `f` holds for the corresponding elements of
+/// the lists `l1`, `l2` and `l3`, which have the same length.
+pub fn maplist3<U, E, G, F>(
+    f: F,
+    l1: LTerm<U, E>,
+    l2: LTerm<U, E>,
+    l3: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    F: Fn(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G> + 'static,
+{
+    maplist3_rec(Rc::new(f), l1, l2, l3)
+}
+
+fn maplist3_rec<U, E, G>(
+    f: Relation3<U, E, G>,
+    l1: LTerm<U, E>,
+    l2: LTerm<U, E>,
+    l3: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [l1, l2, l3] {
+            [[], [], []] => ,
+            [[x | xs], [y | ys], [z | zs]] => {
+                f(x, y, z),
+                maplist3_rec({Rc::clone(&f)}, xs, ys, zs),
+            }
+        }
+    )
+}
+
+/// A relation that succeeds when the relation `f` holds for the corresponding elements of
+/// the lists `l1`, `l2`, `l3` and `l4`, which have the same length.
+pub fn maplist4<U, E, G, F>(
+    f: F,
+    l1: LTerm<U, E>,
+    l2: LTerm<U, E>,
+    l3: LTerm<U, E>,
+    l4: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    F: Fn(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G> + 'static,
+{
+    maplist4_rec(Rc::new(f), l1, l2, l3, l4)
+}
+
+fn maplist4_rec<U, E, G>(
+    f: Relation4<U, E, G>,
+    l1: LTerm<U, E>,
+    l2: LTerm<U, E>,
+    l3: LTerm<U, E>,
+    l4: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [l1, l2, l3, l4] {
+            [[], [], [], []] => ,
+            [[x | xs], [y | ys], [z | zs], [w | ws]] => {
+                f(x, y, z, w),
+                maplist4_rec({Rc::clone(&f)}, xs, ys, zs, ws),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{maplist, maplist2, maplist3, maplist4};
+    use crate::prelude::*;
+    use crate::relation::{append, numbero};
+
+    #[test]
+    fn test_maplist_1() {
+        let query = proto_vulcan_query!(|q| { maplist(|x| proto_vulcan!(numbero(x)), [1, 2, q]) });
+        let result = query.run().next().unwrap();
+        assert_eq!(
+            format!("{}", result.q),
+            format!("{0}  where  {{ numbero({0}) }}", *result.q)
+        );
+
+        let query = proto_vulcan_query!(|q| { maplist(|x| proto_vulcan!(numbero(x)), [1, "two"]) });
+        assert!(query.run().next().is_none());
+
+        // Lists of increasing lengths
+        let query = proto_vulcan_query!(|q| { maplist(|x| proto_vulcan!(x == 0), q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert_eq!(iter.next().unwrap().q, lterm!([0]));
+        assert_eq!(iter.next().unwrap().q, lterm!([0, 0]));
+    }
+
+    #[test]
+    fn test_maplist_2() {
+        let query = proto_vulcan_query!(|q| {
+            maplist2(|x, y| proto_vulcan!(y == [x, x]), q, [[1, 1], [2, 2]])
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+
+        let query =
+            proto_vulcan_query!(|q| { maplist2(|x, y| proto_vulcan!(x == y), [1], [1, 2]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_maplist_3() {
+        let query = proto_vulcan_query!(|q| {
+            maplist3(
+                |x, y, z| proto_vulcan!(append(x, y, z)),
+                [[1], []],
+                [[2], [3]],
+                q,
+            )
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([[1, 2], [3]]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|a, b| {
+            maplist4(
+                |x, y, z, w| proto_vulcan!([x, y] == [z, w]),
+                [1, 2],
+                [3, 4],
+                a,
+                b,
+            )
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.a, lterm!([1, 2]));
+        assert_eq!(result.b, lterm!([3, 4]));
+        assert!(iter.next().is_none());
+    }
+}
@@ -34,6 +34,13 @@
 //!
 //! # fn main() {}
 //! ```
+#[cfg(feature = "core")]
+#[doc(hidden)]
+pub mod absento;
+
+#[cfg(feature = "extras")]
+pub mod alist;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod always;
@@ -42,10 +49,17 @@ pub mod always;
 #[doc(hidden)]
 pub mod append;
 
+#[cfg(feature = "extras")]
+pub mod compare;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod cons;
 
+#[cfg(feature = "core")]
+#[doc(hidden)]
+pub mod copy_term;
+
 #[cfg(feature = "core")]
 #[doc(hidden)]
 pub mod diseq;
@@ -70,6 +84,36 @@ pub mod fail;
 #[doc(hidden)]
 pub mod first;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod flatteno;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod foldl;
+
+#[cfg(feature = "extras")]
+pub mod graph;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod include;
+
+#[cfg(feature = "extras")]
+pub mod inspect;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod lasto;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(hidden)]
+pub mod lengtho;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod maplist;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod member1;
@@ -78,10 +122,24 @@ pub mod member1;
 #[doc(hidden)]
 pub mod member;
 
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(hidden)]
+pub mod msorto;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod never;
 
+#[cfg(feature = "core")]
+pub mod nominal;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(hidden)]
+pub mod nth;
+
+#[cfg(feature = "extras")]
+pub mod oleg;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod permute;
@@ -94,10 +152,32 @@ pub mod rember;
 #[doc(hidden)]
 pub mod rest;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod reverseo;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod selecto;
+
+#[cfg(feature = "extras")]
+pub mod string;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod sublisto;
+
 #[cfg(feature = "core")]
 #[doc(hidden)]
 pub mod succeed;
 
+#[cfg(feature = "core")]
+pub mod typeo;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod zipo;
+
 // CLP(FD)
 #[cfg(feature = "clpfd")]
 pub mod clpfd;
@@ -107,6 +187,26 @@ pub mod clpfd;
 #[doc(hidden)]
 pub mod clpz;
 
+// CLP(B)
+#[cfg(feature = "clpb")]
+pub mod clpb;
+
+// CLP(Q)
+#[cfg(feature = "clpq")]
+pub mod clpq;
+
+// CLP(Set)
+#[cfg(feature = "clpset")]
+pub mod clpset;
+
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use absento::absento;
+
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use copy_term::copy_termo;
+
 #[cfg(feature = "core")]
 #[doc(inline)]
 pub use diseq::diseq;
@@ -115,6 +215,22 @@ pub use diseq::diseq;
 #[doc(inline)]
 pub use eq::eq;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use alist::extendo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use alist::keyso;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use alist::lookupo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use alist::removeo;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use always::always;
@@ -123,6 +239,22 @@ pub use always::always;
 #[doc(inline)]
 pub use append::append;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use compare::compareo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use compare::msort_terms;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use compare::sort;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use compare::term_lt;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use cons::cons;
@@ -139,6 +271,74 @@ pub use empty::empty;
 #[doc(inline)]
 pub use first::first;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use flatteno::flatteno;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use foldl::foldl;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use graph::edgeo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use graph::patho;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use graph::reachableo;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use graph::shortest_patho;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use include::exclude;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use include::include;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use inspect::argo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use inspect::functoro;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use inspect::univo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use lasto::lasto;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use lengtho::lengtho;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use maplist::maplist;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use maplist::maplist2;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use maplist::maplist3;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use maplist::maplist4;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use member1::member1;
@@ -147,10 +347,58 @@ pub use member1::member1;
 #[doc(inline)]
 pub use member::member;
 
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use msorto::msorto;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use never::never;
 
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use nominal::hash;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use nth::nth0;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use nth::nth1;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use oleg::divo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use oleg::expo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use oleg::leo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use oleg::logo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use oleg::lto;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use oleg::minuso;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use oleg::pluso;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use oleg::timeso;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use permute::permute;
@@ -163,14 +411,74 @@ pub use rember::rember;
 #[doc(inline)]
 pub use rest::rest;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use reverseo::reverseo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use selecto::selecto;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use string::string_chars;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use string::string_concat;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use string::string_length;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use string::sub_string;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use sublisto::sublisto;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use zipo::zipo;
+
 #[cfg(feature = "core")]
 #[doc(inline)]
 pub use fail::fail;
 
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use typeo::boolo;
+
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use typeo::charo;
+
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use typeo::numbero;
+
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use typeo::stringo;
+
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use typeo::symbolo;
+
 #[cfg(feature = "core")]
 #[doc(inline)]
 pub use succeed::succeed;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::arithfd::absfd;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::boolfd::andfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::diseqfd::diseqfd;
@@ -179,6 +487,26 @@ pub use clpfd::diseqfd::diseqfd;
 #[doc(inline)]
 pub use clpfd::distinctfd::distinctfd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::distinctfd::distinctfd_fc;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::arithfd::divfd;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::reified::diseqfd_reif;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::reified::eqfd_reif;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::boolfd::impliesfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::infd::infd;
@@ -187,22 +515,62 @@ pub use clpfd::infd::infd;
 #[doc(inline)]
 pub use clpfd::infd::infdrange;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::labeling::labeling;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::labeling::LabelingOption;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::ltefd::ltefd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::reified::ltefd_reif;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::ltfd::ltfd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::reified::ltfd_reif;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::arithfd::maxfd;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::arithfd::minfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::minusfd::minusfd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::arithfd::modfd;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::boolfd::notfd;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::boolfd::orfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::plusfd::plusfd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::arithfd::powfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::timesfd::timesfd;
@@ -214,3 +582,47 @@ pub use clpz::plusz::plusz;
 #[cfg(feature = "clpz")]
 #[doc(inline)]
 pub use clpz::timesz::timesz;
+
+#[cfg(feature = "clpb")]
+#[doc(inline)]
+pub use clpb::sat::card;
+
+#[cfg(feature = "clpb")]
+#[doc(inline)]
+pub use clpb::sat::sat;
+
+#[cfg(feature = "clpb")]
+#[doc(inline)]
+pub use clpb::taut::taut;
+
+#[cfg(feature = "clpq")]
+#[doc(inline)]
+pub use clpq::infsup::inf;
+
+#[cfg(feature = "clpq")]
+#[doc(inline)]
+pub use clpq::infsup::sup;
+
+#[cfg(feature = "clpset")]
+#[doc(inline)]
+pub use clpset::in_set::in_set;
+
+#[cfg(feature = "clpset")]
+#[doc(inline)]
+pub use clpset::intersection::intersection;
+
+#[cfg(feature = "clpset")]
+#[doc(inline)]
+pub use clpset::set_card::set_card;
+
+#[cfg(feature = "clpset")]
+#[doc(inline)]
+pub use clpset::setdom::setdom;
+
+#[cfg(feature = "clpset")]
+#[doc(inline)]
+pub use clpset::subset::subset;
+
+#[cfg(feature = "clpset")]
+#[doc(inline)]
+pub use clpset::union::union;
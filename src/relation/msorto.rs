@@ -0,0 +1,146 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::{ltefd, ltfd};
+use crate::user::User;
+
+/// A relation where `s` is the proper list of integers `l` sorted in ascending order with
+/// merge sort. Duplicates are kept. The elements are compared with finite domain constraints.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::msorto;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         msorto([3, 1, 2, 1], q)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 1, 2, 3]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn msorto<U, E, G>(l: LTerm<U, E>, s: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(|l1, l2, s1, s2| {
+        same_length(l, s),
+        match l {
+            [] => s == [],
+            [x] => s == [x],
+            [_, _ | _] => {
+                split(l, l1, l2),
+                msorto(l1, s1),
+                msorto(l2, s2),
+                merge(s1, s2, s),
+            }
+        }
+    })
+}
+
+fn same_length<U, E, G>(l1: LTerm<U, E>, l2: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [l1, l2] {
+            [[], []] => ,
+            [[_ | t1], [_ | t2]] => same_length(t1, t2),
+        }
+    )
+}
+
+/// Splits `l` into lists of the elements at even and odd positions.
+fn split<U, E, G>(l: LTerm<U, E>, l1: LTerm<U, E>, l2: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [l, l1, l2] {
+            [[], [], []] => ,
+            [[x], [x], []] => ,
+            [[x, y | rest], [x | rest1], [y | rest2]] => split(rest, rest1, rest2),
+        }
+    )
+}
+
+/// Merges the sorted lists `s1` and `s2`. Equal elements are taken from `s1` first.
+fn merge<U, E, G>(s1: LTerm<U, E>, s2: LTerm<U, E>, s: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [s1, s2, s] {
+            [[], t, t] => ,
+            [[x | t], [], [x | t]] => ,
+            [[x | t1], [y | t2], [x | t]] => {
+                ltefd(x, y),
+                merge(t1, [y | t2], t),
+            },
+            [[x | t1], [y | t2], [y | t]] => {
+                ltfd(y, x),
+                merge([x | t1], t2, t),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::msorto;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_msorto_1() {
+        let query = proto_vulcan_query!(|q| { msorto([5, -1, 3, 3, 0], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([-1, 0, 3, 3, 5]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { msorto([], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { msorto([2, 1], [2, 1]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_msorto_2() {
+        // The unsorted lists of a sorted list
+        let query = proto_vulcan_query!(|q| { msorto(q, [1, 2, 3]) });
+        let mut answers = query
+            .run()
+            .map(|result| format!("{}", result.q))
+            .collect::<Vec<String>>();
+        answers.sort();
+        assert_eq!(
+            answers,
+            vec![
+                "[1, 2, 3]",
+                "[1, 3, 2]",
+                "[2, 1, 3]",
+                "[2, 3, 1]",
+                "[3, 1, 2]",
+                "[3, 2, 1]"
+            ]
+        );
+
+        let query = proto_vulcan_query!(|q| { msorto(q, [2, 1]) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { msorto(q, [1, 1]) });
+        assert_eq!(query.run().count(), 1);
+    }
+}
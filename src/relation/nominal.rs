@@ -0,0 +1,316 @@
+//! # Nominal logic
+//! Proto-vulcan implements nominal logic in the style of alphaKanren. Nominal atoms are names
+//! that are distinct from each other, and a binder term `tie(a, t)` binds the atom `a` within
+//! the term `t`. Binder terms unify modulo alpha-equivalence: `tie(a, [a])` and `tie(b, [b])`
+//! are the same term.
+//!
+//! * `nom |a, b| { ... }`: introduces fresh nominal atoms `a` and `b` for the body.
+//! * `tie(a, t)`: binder term; outside of the macros it is built with `LTerm::tie(a, t)`.
+//! * `a # t`: freshness constraint; the atom `a` does not occur free in the term `t`.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! fn main() {
+//!     // The identity function is alpha-equivalent to itself with a renamed bound atom.
+//!     let query = proto_vulcan_query!(|q| {
+//!         nom |a, b| {
+//!             tie(a, ["var", a]) == tie(b, ["var", q]),
+//!             q == b,
+//!         }
+//!     });
+//!     assert_eq!(query.run().count(), 1);
+//!
+//!     // A bound atom cannot be captured by a free atom of a different name.
+//!     let query = proto_vulcan_query!(|q| {
+//!         nom |a, b| {
+//!             tie(a, ["var", a]) == tie(b, ["var", a]),
+//!         }
+//!     });
+//!     assert!(query.run().next().is_none());
+//! }
+//! ```
+use crate::compound::CompoundObject;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::solver::{Solve, Solver};
+use crate::state::nominal::{inverse, permute_nom, walk_susp};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Freshness<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    a: LTerm<U, E>,
+    t: LTerm<U, E>,
+}
+
+impl<U, E> Freshness<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(a: LTerm<U, E>, t: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Freshness { a, t })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Freshness<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match FreshnessConstraint::new(self.a.clone(), self.t.clone()).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Freshness constraint: the nominal atom `a` does not occur free in the term `t`.
+/// Proto-vulcan provides a built-in syntax `a # t`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         nom |a, b| {
+///             a # tie(a, [a, b]),
+///             conde {
+///                 [q == a, a # q],
+///                 [q == b, a # q],
+///             },
+///         }
+///     });
+///     assert_eq!(query.run().count(), 1);
+/// }
+/// ```
+pub fn hash<U, E, G>(a: LTerm<U, E>, t: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Freshness::new(a, t)
+}
+
+/// Freshness constraint
+///
+/// The constraint is checked for the parts of the term, and it is stored for the variables of
+/// the term until they are bound.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct FreshnessConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    a: LTerm<U, E>,
+    t: LTerm<U, E>,
+}
+
+impl<U, E> FreshnessConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(a: LTerm<U, E>, t: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(FreshnessConstraint { a, t })
+    }
+
+    pub fn t(&self) -> &LTerm<U, E> {
+        &self.t
+    }
+
+    fn run_compound(
+        state: State<U, E>,
+        a: &LTerm<U, E>,
+        compound: &dyn CompoundObject<U, E>,
+    ) -> SResult<U, E> {
+        let mut state = state;
+        for child in compound.children() {
+            state = match child.as_term() {
+                Some(t) => FreshnessConstraint::new(a.clone(), t.clone()).run(state)?,
+                None => Self::run_compound(state, a, child)?,
+            };
+        }
+        Ok(state)
+    }
+}
+
+impl<U, E> Constraint<U, E> for FreshnessConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let a = state.smap_ref().walk(&self.a).clone();
+        match a.as_ref() {
+            LTermInner::Nom(_, _) => (),
+            LTermInner::Var(_, _) => return Ok(state.with_constraint(self)),
+            _ => return Err(()),
+        }
+
+        let t = walk_susp(state.smap_ref(), &self.t);
+        match t.as_ref() {
+            LTermInner::Nom(_, _) if t == a => Err(()),
+            LTermInner::Var(_, _) => {
+                let exists = state
+                    .cstore_ref()
+                    .iter()
+                    .filter_map(|c| c.downcast_ref::<FreshnessConstraint<U, E>>())
+                    .any(|c| c.a == a && state.smap_ref().walk(&c.t) == &t);
+                if exists {
+                    Ok(state)
+                } else {
+                    Ok(state.with_constraint(FreshnessConstraint::new(a, t)))
+                }
+            }
+            LTermInner::Susp(perm, x) => {
+                // `a` is fresh in `perm * x` if the inversely permuted `a` is fresh in `x`.
+                let a = permute_nom(&inverse(perm), &a);
+                FreshnessConstraint::new(a, x.clone()).run(state)
+            }
+            LTermInner::Tie(b, _) if b == &a => Ok(state),
+            LTermInner::Tie(_, body) => FreshnessConstraint::new(a, body.clone()).run(state),
+            LTermInner::Cons(head, tail) => {
+                let state = FreshnessConstraint::new(a.clone(), head.clone()).run(state)?;
+                FreshnessConstraint::new(a, tail.clone()).run(state)
+            }
+            LTermInner::Compound(compound) => Self::run_compound(state, &a, compound.as_ref()),
+            _ => Ok(state),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.a.clone(), self.t.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(FreshnessConstraint::new(
+            smap.walk_star(&self.a),
+            smap.walk_star(&self.t),
+        ))
+    }
+}
+
+impl<U, E> std::fmt::Display for FreshnessConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} # {}", self.a, self.t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_nominal_1() {
+        // tie(a, a) and tie(b, b) are alpha-equivalent, tie(a, b) and tie(b, a) are not.
+        let query = proto_vulcan_query!(|q| {
+            nom |a, b| {
+                tie(a, a) == tie(b, b),
+            }
+        });
+        assert_eq!(query.run().count(), 1);
+
+        let query = proto_vulcan_query!(|q| {
+            nom |a, b| {
+                tie(a, b) == tie(b, a),
+            }
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            nom |a, b| {
+                [1, tie(a, [a, q])] == [1, tie(b, [b, a])],
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_nominal_2() {
+        // The variable is bound to the body with the atom renamed.
+        let query = proto_vulcan_query!(|q, r| {
+            nom |a, b| {
+                tie(a, [a, q]) == tie(b, [b, 5]),
+                tie(a, r) == tie(b, ["lam", b]),
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.q, 5);
+        assert_eq!(format!("{}", result.r), "[\"lam\", a]");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_nominal_3() {
+        // Unifying binders suspends the renaming on the unbound variable, and the renaming
+        // is applied when the variable is bound.
+        let query = proto_vulcan_query!(|q| {
+            nom |a, b| {
+                |x, y| {
+                    tie(a, x) == tie(b, y),
+                    y == [b, 1],
+                    q == x,
+                }
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(format!("{}", iter.next().unwrap().q), "[a, 1]");
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            nom |a, b| {
+                |x, y| {
+                    tie(a, x) == tie(b, y),
+                    y == [a],
+                }
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_nominal_4() {
+        // Freshness constraints are kept for unbound variables.
+        let query = proto_vulcan_query!(|q| {
+            nom |a| {
+                a # q,
+                a # [1, q],
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(
+            format!("{}", result.q),
+            format!("{0}  where  {{ a # {0} }}", *result.q)
+        );
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            nom |a| {
+                a # q,
+                q == [1, a],
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::{infdrange, plusfd};
+use crate::user::User;
+
+/// A relation where `x` is the element at zero-based index `i` of the list `l`. The index is
+/// a finite domain variable.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::nth0;
+/// fn main() {
+///     let query = proto_vulcan_query!(|i| {
+///         nth0(i, ["a", "b", "c"], "b")
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().i, 1);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn nth0<U, E, G>(i: LTerm<U, E>, l: LTerm<U, E>, x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [y | _] => {
+                i == 0,
+                x == y,
+            },
+            [_ | rest] => |j| {
+                infdrange(i, &(1..=isize::MAX)),
+                infdrange(j, &(0..=isize::MAX)),
+                plusfd(j, 1, i),
+                nth0(j, rest, x),
+            }
+        }
+    )
+}
+
+/// A relation where `x` is the element at one-based index `i` of the list `l`. The index is
+/// a finite domain variable.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::nth1;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x| {
+///         nth1(1, ["a", "b", "c"], x)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().x, "a");
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn nth1<U, E, G>(i: LTerm<U, E>, l: LTerm<U, E>, x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(|j| {
+        infdrange(i, &(1..=isize::MAX)),
+        infdrange(j, &(0..=isize::MAX)),
+        plusfd(j, 1, i),
+        nth0(j, l, x),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{nth0, nth1};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_nth0_1() {
+        let query = proto_vulcan_query!(|x| { nth0(2, [1, 2, 3], x) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, 3);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x| { nth0(3, [1, 2, 3], x) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|i, x| { nth0(i, [1, 2], x) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result.i == 0 && result.x == 1);
+        let result = iter.next().unwrap();
+        assert!(result.i == 1 && result.x == 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_nth0_2() {
+        let query = proto_vulcan_query!(|l| {
+            nth0(1, l, "b"),
+            l == [_, _, _],
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.l.iter().nth(1).unwrap(), &"b");
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|i| { nth0(i, [1, 2, 1], 1) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().i, 0);
+        assert_eq!(iter.next().unwrap().i, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_nth1_1() {
+        let query = proto_vulcan_query!(|i| { nth1(i, [1, 2, 3], 3) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().i, 3);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x| { nth1(0, [1, 2, 3], x) });
+        assert!(query.run().next().is_none());
+    }
+}
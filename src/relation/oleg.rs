@@ -0,0 +1,750 @@
+//! # Relational binary arithmetic
+//! Relations over natural numbers represented as little-endian lists of bits, as in
+//! *The Reasoned Schemer* by Friedman, Byrd, Kiselyov and Hemann. Zero is the empty list, and
+//! a non-zero number is a list of bits `0` and `1` whose last bit is `1`; for example, 6 is
+//! `[0, 1, 1]`. The relations need no domain bounds, and they are refutationally complete: for
+//! any instantiation of the arguments they either enumerate all the answers, or fail finitely
+//! when there are none.
+//!
+//! * `pluso(n, m, k)`: n + m = k
+//! * `minuso(n, m, k)`: n - m = k
+//! * `timeso(n, m, p)`: n * m = p (`*o` in the book)
+//! * `divo(n, m, q, r)`: n = m * q + r, where 0 <= r < m (`/o` in the book)
+//! * `logo(n, b, q, r)`: n = b^q + r, where 0 <= r and q is the largest such exponent
+//! * `expo(b, q, n)`: n = b^q
+//! * `lto(n, m)`: n < m (`<o` in the book)
+//! * `leo(n, m)`: n <= m (`<=o` in the book)
+//!
+//! Numbers are converted to bit lists and back with [`build_num`] and [`num_to_isize`].
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::oleg::{build_num, num_to_isize};
+//! use proto_vulcan::relation::pluso;
+//! fn main() {
+//!     let query = proto_vulcan_query!(|x, y| { pluso(x, y, {build_num(3)}) });
+//!     let mut answers = query
+//!         .run()
+//!         .map(|result| {
+//!             (
+//!                 num_to_isize(&result.x).unwrap(),
+//!                 num_to_isize(&result.y).unwrap(),
+//!             )
+//!         })
+//!         .collect::<Vec<(isize, isize)>>();
+//!     answers.sort();
+//!     assert_eq!(answers, vec![(0, 3), (1, 2), (2, 1), (3, 0)]);
+//! }
+//! ```
+use crate::engine::Engine;
+use crate::goal::Goal;
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::operator::conde;
+use crate::relation::append;
+use crate::user::User;
+
+/// Builds the little-endian bit list of the natural number `n`.
+///
+/// # Panics
+/// Panics if `n` is negative.
+pub fn build_num<U, E>(n: isize) -> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    assert!(n >= 0, "Error: Negative number {} has no bit list.", n);
+    let mut n = n;
+    let mut bits = vec![];
+    while n > 0 {
+        bits.push(LTerm::from(n & 1));
+        n >>= 1;
+    }
+    LTerm::from_vec(bits)
+}
+
+/// Returns the natural number of the bit list `t`, or `None` if `t` is not a fully
+/// instantiated bit list without trailing zeros, or if the number does not fit in `isize`.
+pub fn num_to_isize<U, E>(t: &LTerm<U, E>) -> Option<isize>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut n: isize = 0;
+    let mut last = None;
+    for (i, bit) in t.iter().enumerate() {
+        let bit = match bit.as_ref() {
+            LTermInner::Val(LValue::Number(b)) if *b == 0 || *b == 1 => *b,
+            _ => return None,
+        };
+        if bit == 1 {
+            n = n.checked_add(1isize.checked_shl(i as u32).filter(|x| *x > 0)?)?;
+        }
+        last = Some(bit);
+    }
+    if last == Some(0) || !t.is_list() {
+        return None;
+    }
+    Some(n)
+}
+
+/// A relation that succeeds when `n` is a positive number.
+pub fn poso<U, E>(n: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan!(n == [_ | _])
+}
+
+/// A relation that succeeds when `n` is greater than one.
+pub fn gt1o<U, E>(n: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan!(n == [_, _ | _])
+}
+
+/// Adds the bits `b`, `x` and `y` to the sum bit `r` and the carry bit `c`.
+fn full_addero<U, E>(
+    b: LTerm<U, E>,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+    r: LTerm<U, E>,
+    c: LTerm<U, E>,
+) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan!(
+        match [b, x, y, r, c] {
+            [0, 0, 0, 0, 0] => ,
+            [1, 0, 0, 1, 0] => ,
+            [0, 1, 0, 1, 0] => ,
+            [1, 1, 0, 0, 1] => ,
+            [0, 0, 1, 1, 0] => ,
+            [1, 0, 1, 0, 1] => ,
+            [0, 1, 1, 0, 1] => ,
+            [1, 1, 1, 1, 1] => ,
+        }
+    )
+}
+
+/// Adds the carry bit `b` and the numbers `n` and `m` to `r`.
+fn addero<U, E>(b: LTerm<U, E>, n: LTerm<U, E>, m: LTerm<U, E>, r: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [b == 0, m == [], n == r],
+        [b == 0, n == [], m == r, poso(m)],
+        [b == 1, m == [], addero(0, n, [1], r)],
+        [b == 1, n == [], poso(m), addero(0, [1], m, r)],
+        |a, c| {
+            n == [1],
+            m == [1],
+            r == [a, c],
+            full_addero(b, 1, 1, a, c),
+        },
+        [n == [1], gen_addero(b, n, m, r)],
+        [m == [1], gt1o(n), gt1o(r), addero(b, [1], n, r)],
+        [gt1o(n), gen_addero(b, n, m, r)],
+    })
+}
+
+fn gen_addero<U, E>(b: LTerm<U, E>, n: LTerm<U, E>, m: LTerm<U, E>, r: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(|a, c, d, e, x, y, z| {
+        n == [a | x],
+        m == [d | y],
+        poso(y),
+        r == [c | z],
+        poso(z),
+        full_addero(b, a, d, c, e),
+        addero(e, x, y, z),
+    })
+}
+
+/// A relation where `k` is the sum of `n` and `m`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::pluso;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| { pluso([1, 1], [1, 0, 1], q) });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([0, 0, 0, 1]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn pluso<U, E>(n: LTerm<U, E>, m: LTerm<U, E>, k: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    addero(LTerm::from(0), n, m, k)
+}
+
+/// A relation where `k` is `m` subtracted from `n`.
+pub fn minuso<U, E>(n: LTerm<U, E>, m: LTerm<U, E>, k: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pluso(m, k, n)
+}
+
+/// A relation where `p` is the product of `n` and `m`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::oleg::{build_num, num_to_isize};
+/// use proto_vulcan::relation::timeso;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, y| { timeso(x, y, {build_num(6)}) });
+///     let mut answers = query
+///         .run()
+///         .map(|result| {
+///             (
+///                 num_to_isize(&result.x).unwrap(),
+///                 num_to_isize(&result.y).unwrap(),
+///             )
+///         })
+///         .collect::<Vec<(isize, isize)>>();
+///     answers.sort();
+///     assert_eq!(answers, vec![(1, 6), (2, 3), (3, 2), (6, 1)]);
+/// }
+/// ```
+pub fn timeso<U, E>(n: LTerm<U, E>, m: LTerm<U, E>, p: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [n == [], p == []],
+        [poso(n), m == [], p == []],
+        [n == [1], poso(m), m == p],
+        [gt1o(n), m == [1], n == p],
+        |x, z| {
+            n == [0 | x],
+            poso(x),
+            p == [0 | z],
+            poso(z),
+            gt1o(m),
+            timeso(x, m, z),
+        },
+        |x, y| {
+            n == [1 | x],
+            poso(x),
+            m == [0 | y],
+            poso(y),
+            timeso(m, n, p),
+        },
+        |x, y| {
+            n == [1 | x],
+            poso(x),
+            m == [1 | y],
+            poso(y),
+            odd_timeso(x, n, m, p),
+        },
+    })
+}
+
+fn odd_timeso<U, E>(x: LTerm<U, E>, n: LTerm<U, E>, m: LTerm<U, E>, p: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(|q| {
+        bound_timeso(q, p, n, m),
+        timeso(x, m, q),
+        pluso([0 | q], m, p),
+    })
+}
+
+/// Bounds the length of the product `p` of `n` and `m` such that `timeso` terminates when
+/// the product is unknown.
+fn bound_timeso<U, E>(q: LTerm<U, E>, p: LTerm<U, E>, n: LTerm<U, E>, m: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [q == [], poso(p)],
+        |a0, a1, a2, a3, x, y, z| {
+            q == [a0 | x],
+            p == [a1 | y],
+            conde {
+                [n == [], m == [a2 | z], bound_timeso(x, y, z, [])],
+                [n == [a3 | z], bound_timeso(x, y, z, m)],
+            },
+        },
+    })
+}
+
+/// A relation where the numbers `n` and `m` have the same number of bits.
+fn eq_lengtho<U, E>(n: LTerm<U, E>, m: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [n == [], m == []],
+        [n == [1], m == [1]],
+        |x, y| {
+            n == [_ | x],
+            poso(x),
+            m == [_ | y],
+            poso(y),
+            eq_lengtho(x, y),
+        },
+    })
+}
+
+/// A relation where the number `n` has fewer bits than the number `m`.
+fn lt_lengtho<U, E>(n: LTerm<U, E>, m: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [n == [], poso(m)],
+        [n == [1], gt1o(m)],
+        |x, y| {
+            n == [_ | x],
+            poso(x),
+            m == [_ | y],
+            poso(y),
+            lt_lengtho(x, y),
+        },
+    })
+}
+
+fn le_lengtho<U, E>(n: LTerm<U, E>, m: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan!(conde {
+        eq_lengtho(n, m),
+        lt_lengtho(n, m),
+    })
+}
+
+/// A relation where `n` is less than `m`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::lto;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| { lto(q, [1, 1]) });
+///     let mut answers = query
+///         .run()
+///         .map(|result| format!("{}", result.q))
+///         .collect::<Vec<String>>();
+///     answers.sort();
+///     assert_eq!(answers, vec!["[0, 1]", "[1]", "[]"]);
+/// }
+/// ```
+pub fn lto<U, E>(n: LTerm<U, E>, m: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan!(conde {
+        lt_lengtho(n, m),
+        |x| {
+            eq_lengtho(n, m),
+            poso(x),
+            pluso(n, x, m),
+        },
+    })
+}
+
+/// A relation where `n` is less than or equal to `m`.
+pub fn leo<U, E>(n: LTerm<U, E>, m: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan!(conde {
+        n == m,
+        lto(n, m),
+    })
+}
+
+/// A relation where `q` is the quotient and `r` the remainder of `n` divided by `m`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::oleg::{build_num, num_to_isize};
+/// use proto_vulcan::relation::divo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q, r| { divo({build_num(23)}, {build_num(5)}, q, r) });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(num_to_isize(&result.q), Some(4));
+///     assert_eq!(num_to_isize(&result.r), Some(3));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn divo<U, E>(n: LTerm<U, E>, m: LTerm<U, E>, q: LTerm<U, E>, r: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [q == [], n == r, lto(n, m)],
+        [q == [1], eq_lengtho(m, n), pluso(r, m, n), lto(r, m)],
+        [poso(q), lt_lengtho(m, n), lto(r, m), n_wider_than_mo(n, m, q, r)],
+    })
+}
+
+fn n_wider_than_mo<U, E>(
+    n: LTerm<U, E>,
+    m: LTerm<U, E>,
+    q: LTerm<U, E>,
+    r: LTerm<U, E>,
+) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(|nhigh, nlow, qhigh, qlow, mqlow, mrqlow, rr, rhigh| {
+        splito(n, r, nlow, nhigh),
+        splito(q, r, qlow, qhigh),
+        conde {
+            [
+                nhigh == [],
+                qhigh == [],
+                minuso(nlow, r, mqlow),
+                timeso(m, qlow, mqlow),
+            ],
+            [
+                poso(nhigh),
+                timeso(m, qlow, mqlow),
+                pluso(r, mqlow, mrqlow),
+                minuso(mrqlow, nlow, rr),
+                splito(rr, r, [], rhigh),
+                divo(nhigh, m, qhigh, rhigh),
+            ],
+        },
+    })
+}
+
+/// Splits the bits of `n` into the low bits `l` and the high bits `h` at the length of `r`,
+/// such that `n = 2^(s+1) * h + l`, where `s` is the number of bits in `r`.
+fn splito<U, E>(n: LTerm<U, E>, r: LTerm<U, E>, l: LTerm<U, E>, h: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [n == [], h == [], l == []],
+        |b, n1| {
+            n == [0, b | n1],
+            r == [],
+            h == [b | n1],
+            l == [],
+        },
+        |n1| {
+            n == [1 | n1],
+            r == [],
+            n1 == h,
+            l == [1],
+        },
+        |b, n1, r1| {
+            n == [0, b | n1],
+            r == [_ | r1],
+            l == [],
+            splito([b | n1], r1, [], h),
+        },
+        |n1, r1| {
+            n == [1 | n1],
+            r == [_ | r1],
+            l == [1],
+            splito(n1, r1, [], h),
+        },
+        |b, n1, r1, l1| {
+            n == [b | n1],
+            r == [_ | r1],
+            l == [b | l1],
+            poso(l1),
+            splito(n1, r1, l1, h),
+        },
+    })
+}
+
+/// A relation where `n = b^q + r`, where `r` is not negative and `q` is the largest such
+/// exponent.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::oleg::{build_num, num_to_isize};
+/// use proto_vulcan::relation::logo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q, r| { logo({build_num(14)}, {build_num(2)}, q, r) });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(num_to_isize(&result.q), Some(3));
+///     assert_eq!(num_to_isize(&result.r), Some(6));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn logo<U, E>(n: LTerm<U, E>, b: LTerm<U, E>, q: LTerm<U, E>, r: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [n == [1], poso(b), q == [], r == []],
+        [q == [], lto(n, b), pluso(r, [1], n)],
+        [q == [1], gt1o(b), eq_lengtho(n, b), pluso(r, b, n)],
+        [b == [1], poso(q), pluso(r, [1], n)],
+        [b == [], poso(q), r == n],
+        |dd, s| {
+            b == [0, 1],
+            poso(dd),
+            n == [_, _ | dd],
+            exp2o(n, [], q),
+            splito(n, dd, r, s),
+        },
+        |bw1, bw, nw, nw1, ql1, ql, s, q1, bwq1, bql, qh, qdh, qd, bqd, bq1, bq| {
+            conde {
+                b == [1, 1],
+                b == [_, _, _ | _],
+            },
+            lt_lengtho(b, n),
+            exp2o(b, [], bw1),
+            pluso(bw1, [1], bw),
+            lt_lengtho(q, n),
+            pluso(q, [1], q1),
+            timeso(bw, q1, bwq1),
+            lto(nw1, bwq1),
+            exp2o(n, [], nw1),
+            pluso(nw1, [1], nw),
+            divo(nw, bw, ql1, s),
+            pluso(ql, [1], ql1),
+            le_lengtho(ql, q),
+            repeated_mulo(b, ql, bql),
+            divo(nw, bw1, qh, _),
+            pluso(ql, qdh, qh),
+            pluso(ql, qd, q),
+            leo(qd, qdh),
+            repeated_mulo(b, qd, bqd),
+            timeso(bql, bqd, bq),
+            timeso(b, bq, bq1),
+            pluso(bq, r, n),
+            lto(n, bq1),
+        },
+    })
+}
+
+/// A relation where `q` is the largest exponent such that `2^(q * (k + 1)) <= n`, where `k`
+/// is the number of bits in `b`.
+fn exp2o<U, E>(n: LTerm<U, E>, b: LTerm<U, E>, q: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [n == [1], q == []],
+        [gt1o(n), q == [1], splito(n, b, _, [1])],
+        |q1, b2| {
+            q == [0 | q1],
+            poso(q1),
+            lt_lengtho(b, n),
+            append(b, [1 | b], b2),
+            exp2o(n, b2, q1),
+        },
+        |q1, nh, b2| {
+            q == [1 | q1],
+            poso(q1),
+            poso(nh),
+            splito(n, b, _, nh),
+            append(b, [1 | b], b2),
+            exp2o(nh, b2, q1),
+        },
+    })
+}
+
+/// A relation where `nq` is `n` to the power of `q`.
+fn repeated_mulo<U, E>(n: LTerm<U, E>, q: LTerm<U, E>, nq: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    proto_vulcan_closure!(conde {
+        [poso(n), q == [], nq == [1]],
+        [q == [1], n == nq],
+        |q1, nq1| {
+            gt1o(q),
+            pluso(q1, [1], q),
+            repeated_mulo(n, q1, nq1),
+            timeso(nq1, n, nq),
+        },
+    })
+}
+
+/// A relation where `n` is `b` to the power of `q`.
+pub fn expo<U, E>(b: LTerm<U, E>, q: LTerm<U, E>, n: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    logo(n, b, q, LTerm::empty_list())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{build_num, num_to_isize};
+    use super::{divo, expo, leo, logo, lto, minuso, pluso, timeso};
+    use crate::lterm::LTerm;
+    use crate::prelude::*;
+
+    fn num(n: isize) -> LTerm {
+        build_num(n)
+    }
+
+    #[test]
+    fn test_oleg_build_num() {
+        assert_eq!(num(0), lterm!([]));
+        assert_eq!(num(6), lterm!([0, 1, 1]));
+        assert_eq!(num(19), lterm!([1, 1, 0, 0, 1]));
+        for n in 0..100 {
+            assert_eq!(num_to_isize(&num(n)), Some(n));
+        }
+        assert_eq!(num_to_isize(&num(isize::MAX)), Some(isize::MAX));
+        let t: LTerm = lterm!([1, 0]);
+        assert_eq!(num_to_isize(&t), None);
+        let t: LTerm = lterm!([1, 2]);
+        assert_eq!(num_to_isize(&t), None);
+        let t: LTerm = lterm!([1, _]);
+        assert_eq!(num_to_isize(&t), None);
+        let t: LTerm = LTerm::from_vec(vec![LTerm::from(1); 64]);
+        assert_eq!(num_to_isize(&t), None);
+    }
+
+    #[test]
+    fn test_oleg_pluso() {
+        for (a, b) in [(0, 0), (0, 5), (5, 0), (1, 1), (7, 9), (29, 35)]
+            .iter()
+            .copied()
+        {
+            let query = proto_vulcan_query!(|q| { pluso({ num(a) }, { num(b) }, q) });
+            let mut iter = query.run();
+            assert_eq!(num_to_isize(&iter.next().unwrap().q), Some(a + b));
+            assert!(iter.next().is_none());
+        }
+
+        let query = proto_vulcan_query!(|q| { minuso({ num(8) }, { num(5) }, q) });
+        let mut iter = query.run();
+        assert_eq!(num_to_isize(&iter.next().unwrap().q), Some(3));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { minuso({ num(5) }, { num(8) }, q) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_oleg_pluso_2() {
+        // All pairs that sum to 5
+        let query = proto_vulcan_query!(|x, y| { pluso(x, y, { num(5) }) });
+        let mut answers = query
+            .run()
+            .map(|r| (num_to_isize(&r.x).unwrap(), num_to_isize(&r.y).unwrap()))
+            .collect::<Vec<(isize, isize)>>();
+        answers.sort();
+        assert_eq!(
+            answers,
+            vec![(0, 5), (1, 4), (2, 3), (3, 2), (4, 1), (5, 0)]
+        );
+    }
+
+    #[test]
+    fn test_oleg_timeso() {
+        for (a, b) in [(0, 3), (3, 0), (1, 7), (6, 7), (13, 11)].iter().copied() {
+            let query = proto_vulcan_query!(|q| { timeso({ num(a) }, { num(b) }, q) });
+            let mut iter = query.run();
+            assert_eq!(num_to_isize(&iter.next().unwrap().q), Some(a * b));
+            assert!(iter.next().is_none());
+        }
+
+        // 7 is prime
+        let query = proto_vulcan_query!(|x, y| {
+            lto([1], x),
+            lto([1], y),
+            timeso(x, y, { num(7) }),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_oleg_lto() {
+        let query = proto_vulcan_query!(|q| { lto({ num(5) }, q) });
+        let mut iter = query.run();
+        let answers = (0..3)
+            .map(|_| format!("{}", iter.next().unwrap().q))
+            .collect::<Vec<String>>();
+        assert!(answers.contains(&String::from("[0, 1, 1]")));
+
+        let query = proto_vulcan_query!(|q| { leo(q, { num(2) }) });
+        assert_eq!(query.run().count(), 3);
+
+        let query = proto_vulcan_query!(|q| { lto(q, []) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_oleg_divo() {
+        for (n, m) in [(0, 1), (5, 7), (7, 7), (23, 5), (100, 9)].iter().copied() {
+            let query = proto_vulcan_query!(|q, r| { divo({ num(n) }, { num(m) }, q, r) });
+            let mut iter = query.run();
+            let result = iter.next().unwrap();
+            assert_eq!(num_to_isize(&result.q), Some(n / m));
+            assert_eq!(num_to_isize(&result.r), Some(n % m));
+            assert!(iter.next().is_none());
+        }
+
+        let query = proto_vulcan_query!(|q, r| { divo({ num(5) }, [], q, r) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_oleg_logo() {
+        let query = proto_vulcan_query!(|q, r| { logo({ num(10) }, { num(3) }, q, r) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(num_to_isize(&result.q), Some(2));
+        assert_eq!(num_to_isize(&result.r), Some(1));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { expo({ num(2) }, { num(5) }, q) });
+        let mut iter = query.run();
+        assert_eq!(num_to_isize(&iter.next().unwrap().q), Some(32));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { expo({ num(3) }, { num(2) }, q) });
+        let mut iter = query.run();
+        assert_eq!(num_to_isize(&iter.next().unwrap().q), Some(9));
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,94 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+
+/// A relation where `r` is the proper list `l` reversed.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::reverseo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         reverseo(q, [1, 2, 3])
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([3, 2, 1]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn reverseo<U, E, G>(l: LTerm<U, E>, r: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(reverse_acc(l, [], r, r))
+}
+
+/// Reverses `l` onto the accumulator `acc`. The list `bound` has as many elements as are left
+/// in `l`, which makes the relation terminate also when only `r` is known.
+fn reverse_acc<U, E, G>(
+    l: LTerm<U, E>,
+    acc: LTerm<U, E>,
+    r: LTerm<U, E>,
+    bound: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(match [l, bound] {
+        [[], []] => acc == r,
+        [[x | xs], [_ | rest]] => reverse_acc(xs, [x | acc], r, rest),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::reverseo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_reverseo_1() {
+        let query = proto_vulcan_query!(|q| { reverseo([1, 2, 3], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([3, 2, 1]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { reverseo([], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reverseo_2() {
+        let query = proto_vulcan_query!(|q| { reverseo(q, [1, 2, 3]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([3, 2, 1]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x, y| { reverseo([1, x, 3], [y, 2, 1]) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.y, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reverseo_3() {
+        // Palindromes
+        let query = proto_vulcan_query!(|q| { reverseo(q, q) });
+        let lengths = query
+            .run()
+            .take(3)
+            .map(|result| result.q.iter().count())
+            .collect::<Vec<usize>>();
+        assert_eq!(lengths, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+
+/// A relation where `rest` is the list `l` with one occurrence of `x` removed. The relation
+/// succeeds once for each occurrence of `x` in `l`, and can also be used to insert `x` into
+/// `rest`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::selecto;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         selecto(0, q, [1, 2])
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([0, 1, 2]));
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 0, 2]));
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 0]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn selecto<U, E, G>(x: LTerm<U, E>, l: LTerm<U, E>, rest: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(match [l, rest] {
+        [[y | ys], ys] => x == y,
+        [[y | ys], [y | zs]] => selecto(x, ys, zs),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::selecto;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_selecto_1() {
+        let query = proto_vulcan_query!(|x, r| { selecto(x, [1, 2, 3], r) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 1);
+        assert_eq!(result.r, lterm!([2, 3]));
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.r, lterm!([1, 3]));
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 3);
+        assert_eq!(result.r, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_selecto_2() {
+        let query = proto_vulcan_query!(|r| { selecto(2, [1, 2, 3, 2], r) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().r, lterm!([1, 3, 2]));
+        assert_eq!(iter.next().unwrap().r, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x| { selecto(x, [1, 2], [2]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, 1);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|r| { selecto(4, [1, 2, 3], r) });
+        assert!(query.run().next().is_none());
+    }
+}
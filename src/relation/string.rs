@@ -0,0 +1,651 @@
+//! # String relations
+//! Relations over string values. Lengths and positions are counted in characters.
+//!
+//! * `string_concat(a, b, ab)`: `ab` is the concatenation of `a` and `b`
+//! * `string_chars(s, chars)`: `chars` is the list of characters of `s`
+//! * `string_length(s, n)`: `s` has `n` characters
+//! * `sub_string(s, before, length, after, sub)`: `sub` is the substring of `s` that has
+//!   `before` characters before it and `after` characters after it, and `length` characters
+//!
+//! When the string that is split is known, `string_concat` and `sub_string` enumerate the
+//! solutions. Otherwise the relations wait until enough of the terms are known to determine
+//! the solution. A waiting relation whose string becomes known later keeps waiting while it
+//! has more than one solution, and its solutions are enumerated when the answer is reified.
+//! A relation that is still waiting for its string at reification is shown in the answer.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::{string_concat, string_length};
+//! fn main() {
+//!     let query = proto_vulcan_query!(|a, b| {
+//!         string_concat(a, b, "abc"),
+//!         string_length(a, 2),
+//!     });
+//!     let mut iter = query.run();
+//!     let result = iter.next().unwrap();
+//!     assert_eq!(result.a, "ab");
+//!     assert_eq!(result.b, "c");
+//!     assert!(iter.next().is_none());
+//! }
+//! ```
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::{LazyStream, Stream};
+use crate::user::User;
+use std::rc::Rc;
+
+/// String relation of `StringConstraint`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StringOp {
+    Concat,
+    Chars,
+    Length,
+    SubString,
+}
+
+impl StringOp {
+    fn name(self) -> &'static str {
+        match self {
+            StringOp::Concat => "string_concat",
+            StringOp::Chars => "string_chars",
+            StringOp::Length => "string_length",
+            StringOp::SubString => "sub_string",
+        }
+    }
+}
+
+type Unification<U, E> = (LTerm<U, E>, LTerm<U, E>);
+
+/// Unifications of one solution of a string relation
+pub(crate) type Unifications<U, E> = Vec<Unification<U, E>>;
+
+/// Returns the characters of a string term, or `None` for a variable.
+fn string_operand<U, E>(t: &LTerm<U, E>) -> Result<Option<Vec<char>>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match t.as_ref() {
+        LTermInner::Val(LValue::String(s)) => Ok(Some(s.chars().collect())),
+        LTermInner::Var(_, _) => Ok(None),
+        _ => Err(()),
+    }
+}
+
+/// Returns the value of a non-negative number term, or `None` for a variable.
+fn count_operand<U, E>(t: &LTerm<U, E>) -> Result<Option<usize>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match t.as_ref() {
+        LTermInner::Val(LValue::Number(n)) if *n >= 0 => Ok(Some(*n as usize)),
+        LTermInner::Var(_, _) => Ok(None),
+        _ => Err(()),
+    }
+}
+
+/// Returns the characters of a list of characters, or `None` if the list is not complete.
+fn chars_operand<U, E>(smap: &SMap<U, E>, t: &LTerm<U, E>) -> Result<Option<Vec<char>>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut chars = vec![];
+    let mut complete = true;
+    let mut t = smap.walk(t);
+    loop {
+        match t.as_ref() {
+            LTermInner::Empty => break,
+            LTermInner::Cons(head, tail) => {
+                match smap.walk(head).as_ref() {
+                    LTermInner::Val(LValue::Char(c)) => chars.push(*c),
+                    LTermInner::Var(_, _) => complete = false,
+                    _ => return Err(()),
+                }
+                t = smap.walk(tail);
+            }
+            LTermInner::Var(_, _) => return Ok(None),
+            _ => return Err(()),
+        }
+    }
+    if complete {
+        Ok(Some(chars))
+    } else {
+        Ok(None)
+    }
+}
+
+fn string_term<U, E>(chars: &[char]) -> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    LTerm::from(chars.iter().collect::<String>())
+}
+
+/// Returns the solutions of the relation, or `None` if the terms are not known well enough
+/// to enumerate them.
+fn solutions<U, E>(
+    op: StringOp,
+    smap: &SMap<U, E>,
+    args: &[LTerm<U, E>],
+) -> Result<Option<Vec<Unifications<U, E>>>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match op {
+        StringOp::Concat => {
+            let a = string_operand(smap.walk(&args[0]))?;
+            let b = string_operand(smap.walk(&args[1]))?;
+            let ab = string_operand(smap.walk(&args[2]))?;
+            match (a, b, ab) {
+                (Some(a), Some(b), _) => {
+                    let ab = [a, b].concat();
+                    Ok(Some(vec![vec![(args[2].clone(), string_term(&ab))]]))
+                }
+                (a, b, Some(ab)) => {
+                    let splits = (0..=ab.len())
+                        .map(|i| ab.split_at(i))
+                        .filter(|(pre, _)| a.iter().all(|a| a == pre))
+                        .filter(|(_, post)| b.iter().all(|b| b == post))
+                        .map(|(pre, post)| {
+                            vec![
+                                (args[0].clone(), string_term(pre)),
+                                (args[1].clone(), string_term(post)),
+                            ]
+                        })
+                        .collect();
+                    Ok(Some(splits))
+                }
+                _ => Ok(None),
+            }
+        }
+        StringOp::Chars => {
+            if let Some(s) = string_operand(smap.walk(&args[0]))? {
+                let chars = s.into_iter().map(LTerm::from).collect::<LTerm<U, E>>();
+                Ok(Some(vec![vec![(args[1].clone(), chars)]]))
+            } else if let Some(chars) = chars_operand(smap, &args[1])? {
+                Ok(Some(vec![vec![(args[0].clone(), string_term(&chars))]]))
+            } else {
+                Ok(None)
+            }
+        }
+        StringOp::Length => {
+            let n = count_operand(smap.walk(&args[1]))?;
+            match string_operand(smap.walk(&args[0]))? {
+                Some(s) if n.iter().all(|n| *n == s.len()) => {
+                    let n = LTerm::from(s.len() as isize);
+                    Ok(Some(vec![vec![(args[1].clone(), n)]]))
+                }
+                Some(_) => Ok(Some(vec![])),
+                None => Ok(None),
+            }
+        }
+        StringOp::SubString => {
+            let before = count_operand(smap.walk(&args[1]))?;
+            let length = count_operand(smap.walk(&args[2]))?;
+            let after = count_operand(smap.walk(&args[3]))?;
+            let sub = string_operand(smap.walk(&args[4]))?;
+            let s = match string_operand(smap.walk(&args[0]))? {
+                Some(s) => s,
+                None => return Ok(None),
+            };
+
+            let n = s.len();
+            let length = match (length, &sub) {
+                (Some(l), Some(sub)) if l != sub.len() => return Ok(Some(vec![])),
+                (_, Some(sub)) => Some(sub.len()),
+                (l, None) => l,
+            };
+            let mut solutions = vec![];
+            for b in 0..=n {
+                for l in 0..=(n - b) {
+                    if before.iter().any(|before| *before != b)
+                        || length.iter().any(|length| *length != l)
+                        || after.iter().any(|after| *after != n - b - l)
+                        || sub.iter().any(|sub| sub[..] != s[b..b + l])
+                    {
+                        continue;
+                    }
+                    solutions.push(vec![
+                        (args[1].clone(), LTerm::from(b as isize)),
+                        (args[2].clone(), LTerm::from(l as isize)),
+                        (args[3].clone(), LTerm::from((n - b - l) as isize)),
+                        (args[4].clone(), string_term(&s[b..b + l])),
+                    ]);
+                }
+            }
+            Ok(Some(solutions))
+        }
+    }
+}
+
+fn unify_all<U, E>(state: State<U, E>, unifications: &[Unification<U, E>]) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    unifications
+        .iter()
+        .try_fold(state, |state, (u, v)| state.unify(u, v))
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct StringRel<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    op: StringOp,
+    args: Vec<LTerm<U, E>>,
+}
+
+impl<U, E> StringRel<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(op: StringOp, args: Vec<LTerm<U, E>>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(StringRel { op, args })))
+    }
+}
+
+impl<U, E> Solve<U, E> for StringRel<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match solutions(self.op, state.smap_ref(), &self.args) {
+            Ok(Some(solutions)) => {
+                let mut stream = Stream::empty();
+                for unifications in solutions.iter().rev() {
+                    if let Ok(state) = unify_all(state.clone(), unifications) {
+                        stream =
+                            Stream::mplus(Stream::unit(Box::new(state)), LazyStream::delay(stream));
+                    }
+                }
+                stream
+            }
+            Ok(None) => {
+                let c = StringConstraint::new(self.op, self.args.clone());
+                Stream::unit(Box::new(state.with_constraint(c)))
+            }
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// Concatenation of strings: `ab` is `a` followed by `b`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::string_concat;
+/// fn main() {
+///     let query = proto_vulcan_query!(|a, b| {
+///         string_concat(a, b, "ab"),
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert!(result.a == "" && result.b == "ab");
+///     let result = iter.next().unwrap();
+///     assert!(result.a == "a" && result.b == "b");
+///     let result = iter.next().unwrap();
+///     assert!(result.a == "ab" && result.b == "");
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn string_concat<U, E, G>(
+    a: LTerm<U, E>,
+    b: LTerm<U, E>,
+    ab: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    StringRel::new(StringOp::Concat, vec![a, b, ab])
+}
+
+/// Characters of a string: `chars` is the list of characters of `s`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::string_chars;
+/// fn main() {
+///     let query = proto_vulcan_query!(|s, c| {
+///         string_chars("hi", [c | _]),
+///         string_chars(s, ['o', c]),
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.s, "oh");
+///     assert_eq!(result.c, 'h');
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn string_chars<U, E, G>(s: LTerm<U, E>, chars: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    StringRel::new(StringOp::Chars, vec![s, chars])
+}
+
+/// Length of a string: `s` has `n` characters.
+pub fn string_length<U, E, G>(s: LTerm<U, E>, n: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    StringRel::new(StringOp::Length, vec![s, n])
+}
+
+/// Substring: `sub` is the substring of `s` of `length` characters, that has `before`
+/// characters before it and `after` characters after it in `s`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::sub_string;
+/// fn main() {
+///     let query = proto_vulcan_query!(|before| {
+///         sub_string("banana", before, _, _, "an"),
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().before, 1);
+///     assert_eq!(iter.next().unwrap().before, 3);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn sub_string<U, E, G>(
+    s: LTerm<U, E>,
+    before: LTerm<U, E>,
+    length: LTerm<U, E>,
+    after: LTerm<U, E>,
+    sub: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    StringRel::new(StringOp::SubString, vec![s, before, length, after, sub])
+}
+
+/// String constraint
+///
+/// The constraint waits until the relation has a unique solution, or none. The solutions of
+/// a constraint that has more than one are enumerated at reification.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct StringConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    op: StringOp,
+    args: Vec<LTerm<U, E>>,
+}
+
+impl<U, E> StringConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(op: StringOp, args: Vec<LTerm<U, E>>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(StringConstraint { op, args })
+    }
+}
+
+/// Returns the solutions of a waiting string constraint that has more than one solution, or
+/// `None` if there is no such constraint.
+pub(crate) fn pending_solutions<U, E>(state: &State<U, E>) -> Option<Vec<Unifications<U, E>>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    state
+        .cstore_ref()
+        .iter()
+        .filter_map(|c| c.downcast_ref::<StringConstraint<U, E>>())
+        .filter_map(|c| solutions(c.op, state.smap_ref(), &c.args).ok().flatten())
+        .find(|solutions| solutions.len() > 1)
+}
+
+impl<U, E> Constraint<U, E> for StringConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        match solutions(self.op, state.smap_ref(), &self.args)? {
+            Some(solutions) if solutions.is_empty() => Err(()),
+            Some(solutions) if solutions.len() == 1 => unify_all(state, &solutions[0]),
+            _ => Ok(state.with_constraint(self)),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        self.args.clone()
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        let args = self.args.iter().map(|arg| smap.walk_star(arg)).collect();
+        Some(StringConstraint::new(self.op, args))
+    }
+}
+
+impl<U, E> std::fmt::Display for StringConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}(", self.op.name())?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{string_chars, string_concat, string_length, sub_string};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_string_concat_1() {
+        let query = proto_vulcan_query!(|q| {
+            string_concat("foo", "bar", q),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, "foobar");
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            string_concat(q, "bar", "foobar"),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, "foo");
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            string_concat(q, "baz", "foobar"),
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            string_concat(q, "bar", 1),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_string_concat_2() {
+        // The relation waits until the unknown strings are determined.
+        let query = proto_vulcan_query!(|a, b, q| {
+            string_concat(a, b, q),
+            a == "x",
+            string_concat(b, b, "yy"),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, "xy");
+        assert!(iter.next().is_none());
+
+        // Splits count characters, not bytes.
+        let query = proto_vulcan_query!(|a, b| {
+            string_concat(a, b, "äö"),
+        });
+        assert_eq!(query.run().count(), 3);
+    }
+
+    #[test]
+    fn test_string_concat_3() {
+        // The splits of a string that is bound after the relation are enumerated.
+        let query = proto_vulcan_query!(|a, b, s| {
+            string_concat(a, b, s),
+            s == "ab",
+        });
+        let answers = query
+            .run()
+            .map(|result| format!("{} {}", result.a, result.b))
+            .collect::<Vec<String>>();
+        assert_eq!(answers, vec!["\"\" \"ab\"", "\"a\" \"b\"", "\"ab\" \"\""]);
+
+        // A relation that still waits for its string is shown in the answer.
+        let query = proto_vulcan_query!(|a, b, s| {
+            string_concat(a, b, s),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result.a.is_var());
+        assert!(format!("{}", result.s).contains("string_concat("));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_string_chars_length() {
+        let query = proto_vulcan_query!(|q, n| {
+            |x| {
+                string_chars(q, ['a', x, 'c']),
+                x == 'b',
+                string_length(q, n),
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.q, "abc");
+        assert_eq!(result.n, 3);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            string_chars("", q),
+        });
+        let mut iter = query.run();
+        assert!(iter.next().unwrap().q.is_empty());
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            string_length(q, 1),
+            q == "ab",
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            string_length(q, {-1}),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_sub_string() {
+        let query = proto_vulcan_query!(|sub| {
+            sub_string("abc", _, 2, _, sub),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().sub, "ab");
+        assert_eq!(iter.next().unwrap().sub, "bc");
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|b, l, a, sub| {
+            sub_string("ab", b, l, a, sub),
+        });
+        assert_eq!(query.run().count(), 6);
+
+        let query = proto_vulcan_query!(|q| {
+            |sub| {
+                sub_string(q, 1, _, 0, sub),
+                sub == "b",
+                q == "ab",
+            }
+        });
+        assert_eq!(query.run().count(), 1);
+
+        let query = proto_vulcan_query!(|q| {
+            sub_string("abc", _, _, _, "ac"),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_sub_string_2() {
+        // The substrings of a string that is bound after the relation are enumerated.
+        let query = proto_vulcan_query!(|s, before, sub| {
+            sub_string(s, before, 1, _, sub),
+            s == "ab",
+        });
+        let answers = query
+            .run()
+            .map(|result| format!("{} {}", result.before, result.sub))
+            .collect::<Vec<String>>();
+        assert_eq!(answers, vec!["0 \"a\"", "1 \"b\""]);
+
+        let query = proto_vulcan_query!(|s, before| {
+            sub_string(s, before, _, _, "b"),
+            s == "bab",
+        });
+        let answers = query
+            .run()
+            .map(|result| format!("{}", result.before))
+            .collect::<Vec<String>>();
+        assert_eq!(answers, vec!["0", "2"]);
+
+        // With an unknown string, the relation waits and is shown in the answer.
+        let query = proto_vulcan_query!(|s| {
+            sub_string(s, 0, 1, _, "b"),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result.s.is_var());
+        assert!(format!("{}", result.s).contains("sub_string("));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|s| {
+            sub_string(s, 0, 1, _, "b"),
+            s == "ab",
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::append;
+use crate::user::User;
+
+/// A relation where `s` is a contiguous sublist of the proper list `l`. Each non-empty
+/// sublist is found once for each position where it occurs in `l`, and the empty list is
+/// found once.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::sublisto;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         sublisto(q, [1, 2])
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([]));
+///     assert_eq!(iter.next().unwrap().q, lterm!([1]));
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+///     assert_eq!(iter.next().unwrap().q, lterm!([2]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn sublisto<U, E, G>(s: LTerm<U, E>, l: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(
+        match s {
+            [] => ,
+            [_ | _] => |prefix, suffix, rest| {
+                append(prefix, suffix, l),
+                append(s, rest, suffix),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::sublisto;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_sublisto_1() {
+        let query = proto_vulcan_query!(|q| { sublisto([2, 3], [1, 2, 3, 4]) });
+        assert_eq!(query.run().count(), 1);
+
+        let query = proto_vulcan_query!(|q| { sublisto([1, 3], [1, 2, 3, 4]) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { sublisto([1, q], [1, 2, 1, 3]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 2);
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_sublisto_2() {
+        let query = proto_vulcan_query!(|q| { sublisto(q, [1, 2, 3]) });
+        assert_eq!(query.run().count(), 7);
+
+        let query = proto_vulcan_query!(|q| {
+            q == [_, _, _],
+            sublisto([1, 2], q),
+        });
+        assert_eq!(query.run().count(), 2);
+    }
+}
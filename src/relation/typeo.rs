@@ -0,0 +1,407 @@
+//! # Type constraints
+//! Type constraints restrict a term to literal values of one type: `numbero(x)`, `stringo(x)`,
+//! `boolo(x)`, `charo(x)` and `symbolo(x)`. A variable with a type constraint can still be bound
+//! to any value of the type, and the constraint is shown in the reified answer.
+//!
+//! # Example
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::{numbero, stringo};
+//! fn main() {
+//!     let query = proto_vulcan_query!(|x, y| {
+//!         numbero(x),
+//!         stringo(y),
+//!         x != "foo",
+//!     });
+//!     for result in query.run() {
+//!         println!("{}", result);
+//!     }
+//! }
+//! ```
+//! The disequality is always satisfied by a number, and it is left out of the answer:
+//! ```text
+//! x: _.0  where  { numbero(_.0) }
+//! y: _.1  where  { stringo(_.1) }
+//! ```
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+/// Type of literal values
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TermType {
+    Number,
+    String,
+    Bool,
+    Char,
+    Symbol,
+}
+
+impl TermType {
+    /// Returns `true` if the value is of this type.
+    pub fn matches(&self, value: &LValue) -> bool {
+        match (self, value) {
+            (TermType::Number, LValue::Number(_)) => true,
+            (TermType::Number, LValue::Float(_)) => true,
+            #[cfg(feature = "bigint")]
+            (TermType::Number, LValue::BigInt(_)) => true,
+            #[cfg(feature = "rational")]
+            (TermType::Number, LValue::Rational(_)) => true,
+            (TermType::String, LValue::String(_)) => true,
+            (TermType::Bool, LValue::Bool(_)) => true,
+            (TermType::Char, LValue::Char(_)) => true,
+            (TermType::Symbol, LValue::Atom(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `false` if the term is known to not be of this type under the given type
+    /// constraints. Variables without a type constraint may be of any type.
+    pub fn admits<U, E>(&self, t: &LTerm<U, E>, types: &[(LTerm<U, E>, TermType)]) -> bool
+    where
+        U: User,
+        E: Engine<U>,
+    {
+        match t.as_ref() {
+            LTermInner::Val(value) => self.matches(value),
+            LTermInner::Var(_, _) => types.iter().all(|(x, ty)| x != t || ty == self),
+            _ => false,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            TermType::Number => "numbero",
+            TermType::String => "stringo",
+            TermType::Bool => "boolo",
+            TermType::Char => "charo",
+            TermType::Symbol => "symbolo",
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct TypeO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+    ty: TermType,
+}
+
+impl<U, E> TypeO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(x: LTerm<U, E>, ty: TermType) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(TypeO { x, ty })))
+    }
+}
+
+impl<U, E> Solve<U, E> for TypeO<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match TypeConstraint::new(self.x.clone(), self.ty).run(state) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(_) => Stream::empty(),
+        }
+    }
+}
+
+/// A relation that succeeds for numbers.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::numbero;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x| {
+///         numbero(x),
+///         conde {
+///             x == "one",
+///             x == 1,
+///         },
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().x, 1);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn numbero<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    TypeO::new(x, TermType::Number)
+}
+
+/// A relation that succeeds for strings.
+pub fn stringo<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    TypeO::new(x, TermType::String)
+}
+
+/// A relation that succeeds for symbols, that is, atoms such as `'red`. Strings are not
+/// symbols.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::symbolo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x| {
+///         symbolo(x),
+///         conde {
+///             x == "red",
+///             x == 'red,
+///         },
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().x, Atom::from("red"));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn symbolo<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    TypeO::new(x, TermType::Symbol)
+}
+
+/// A relation that succeeds for booleans.
+pub fn boolo<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    TypeO::new(x, TermType::Bool)
+}
+
+/// A relation that succeeds for characters.
+pub fn charo<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    TypeO::new(x, TermType::Char)
+}
+
+/// Type constraint
+///
+/// The constraint is stored for a variable until the variable is bound. A variable can have
+/// only one type constraint; constraining it to another type fails.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct TypeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+    ty: TermType,
+}
+
+impl<U, E> TypeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(x: LTerm<U, E>, ty: TermType) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(TypeConstraint { x, ty })
+    }
+
+    pub fn x(&self) -> &LTerm<U, E> {
+        &self.x
+    }
+
+    pub fn ty(&self) -> TermType {
+        self.ty
+    }
+}
+
+impl<U, E> Constraint<U, E> for TypeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let xwalk = state.smap_ref().walk(&self.x).clone();
+        match xwalk.as_ref() {
+            LTermInner::Var(_, _) => {
+                // Variables with finite domains are numbers.
+                if self.ty != TermType::Number && state.dstore_ref().contains_key(&xwalk) {
+                    return Err(());
+                }
+
+                let old_ty = state
+                    .cstore_ref()
+                    .iter()
+                    .filter_map(|c| c.downcast_ref::<TypeConstraint<U, E>>())
+                    .find(|typec| state.smap_ref().walk(&typec.x) == &xwalk)
+                    .map(|typec| typec.ty);
+                match old_ty {
+                    Some(ty) if ty == self.ty => return Ok(state),
+                    Some(_) => return Err(()),
+                    None => (),
+                }
+                Ok(state.with_constraint(TypeConstraint::new(xwalk, self.ty)))
+            }
+            LTermInner::Val(value) if self.ty.matches(value) => Ok(state),
+            _ => Err(()),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.x.clone()]
+    }
+
+    fn walk_star_copy(&self, smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        Some(TypeConstraint::new(smap.walk_star(&self.x), self.ty))
+    }
+}
+
+impl<U, E> std::fmt::Display for TypeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}({})", self.ty.name(), self.x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{boolo, charo, numbero, stringo};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_typeo_1() {
+        let query = proto_vulcan_query!(|x| {
+            numbero(x),
+            x == 5,
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, 5);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x| {
+            x == 'c',
+            stringo(x),
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x| {
+            boolo(x),
+            x == [true],
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_typeo_2() {
+        // A variable cannot have two types, also when variables with types are unified.
+        let query = proto_vulcan_query!(|x| {
+            numbero(x),
+            charo(x),
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x, y| {
+            numbero(x),
+            stringo(y),
+            x == y,
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x, y| {
+            numbero(x),
+            numbero(y),
+            x == y,
+            numbero(x),
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(
+            format!("{}", result.x),
+            format!("{0}  where  {{ numbero({0}) }}", *result.x)
+        );
+    }
+
+    #[test]
+    fn test_typeo_3() {
+        // Disequalities that are satisfied by the type are not shown.
+        let query = proto_vulcan_query!(|x| {
+            numbero(x),
+            x != "foo",
+            x != 3,
+        });
+        let result = query.run().next().unwrap();
+        let s = format!("{}", result.x);
+        assert!(s.contains(&format!("numbero({})", *result.x)));
+        assert!(s.contains(&format!("{} != 3", *result.x)));
+        assert!(!s.contains("foo"));
+    }
+
+    #[cfg(feature = "clpfd")]
+    #[test]
+    fn test_typeo_4() {
+        use crate::relation::infdrange;
+
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(0..=3)),
+            stringo(x),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_typeo_5() {
+        use super::symbolo;
+
+        let query = proto_vulcan_query!(|x, y| {
+            symbolo(x),
+            x == y,
+            conde {
+                y == "foo",
+                y == 'foo,
+                y == ['foo],
+            },
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().y, Atom::from("foo"));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x| {
+            symbolo(x),
+        });
+        let result = query.run().next().unwrap();
+        assert_eq!(
+            format!("{}", result.x),
+            format!("{0}  where  {{ symbolo({0}) }}", *result.x)
+        );
+    }
+}
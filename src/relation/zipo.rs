@@ -0,0 +1,64 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+
+/// A relation where `pairs` is the list of two-element lists of the corresponding elements of
+/// the proper lists `l1` and `l2`, which have the same length.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::zipo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         zipo([1, 2], ["a", "b"], q)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([[1, "a"], [2, "b"]]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn zipo<U, E, G>(l1: LTerm<U, E>, l2: LTerm<U, E>, pairs: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [l1, l2, pairs] {
+            [[], [], []] => ,
+            [[x | xs], [y | ys], [[x, y] | rest]] => zipo(xs, ys, rest),
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::zipo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_zipo_1() {
+        let query = proto_vulcan_query!(|q| { zipo([1, 2], [3], q) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x, y| { zipo(x, y, [[1, "a"], [2, "b"]]) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, lterm!([1, 2]));
+        assert_eq!(result.y, lterm!(["a", "b"]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_zipo_2() {
+        let query = proto_vulcan_query!(|x, q| { zipo(x, [1, 2], q) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x.iter().count(), 2);
+        assert_eq!(result.q.iter().count(), 2);
+        assert!(iter.next().is_none());
+    }
+}
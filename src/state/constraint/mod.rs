@@ -20,6 +20,14 @@ where
     fn reify(&self, _state: &mut State<U, E>) {}
 
     fn operands(&self) -> Vec<LTerm<U, E>>;
+
+    /// Returns a copy of the constraint where the operands are replaced with their values in
+    /// the substitution map, or `None` if the constraint does not support copying. Used for
+    /// reifying the constraint store, and for copying the constraint to renamed variables in
+    /// `copy_term`.
+    fn walk_star_copy(&self, _smap: &SMap<U, E>) -> Option<Rc<dyn Constraint<U, E>>> {
+        None
+    }
 }
 
 pub trait AnyConstraint<U, E>: Any
@@ -1,6 +1,9 @@
 use super::SMap;
 use crate::lterm::LTerm;
+use crate::relation::absento::AbsentoConstraint;
 use crate::relation::diseq::DisequalityConstraint;
+use crate::relation::nominal::FreshnessConstraint;
+use crate::relation::typeo::{TermType, TypeConstraint};
 use crate::state::constraint::Constraint;
 use crate::engine::Engine;
 use crate::state::User;
@@ -29,17 +32,59 @@ where
     /// substitution map. Unassociated variables can be Var(_) or Any. Associated variables are
     /// already fully constrained by the values they are associated with, whereas unassociated
     /// variables are constrained by the constraints.
+    ///
+    /// Disequality and absento constraints that always hold under the type constraints, and
+    /// disequalities that are implied by absento constraints, are removed as well.
     pub fn purify(self, r: &SMap<U, E>) -> ConstraintStore<U, E> {
+        let types = self
+            .iter()
+            .filter_map(|c| c.downcast_ref::<TypeConstraint<U, E>>())
+            .map(|c| (r.walk(c.x()).clone(), c.ty()))
+            .collect::<Vec<(LTerm<U, E>, TermType)>>();
+        let absent = self
+            .iter()
+            .filter_map(|c| c.downcast_ref::<AbsentoConstraint<U, E>>())
+            .map(|c| (r.walk_star(c.tag()), r.walk(c.t()).clone()))
+            .collect::<Vec<(LTerm<U, E>, LTerm<U, E>)>>();
+        let type_of = |u: &LTerm<U, E>| types.iter().find(|(x, _)| x == u).map(|(_, ty)| *ty);
+
         let mut purified_cstore = ConstraintStore::new();
         for constraint in self.0.into_iter() {
             if let Some(tree_constraint) = constraint.downcast_ref::<DisequalityConstraint<U, E>>() {
-                if tree_constraint
-                    .smap_ref()
-                    .iter()
-                    .any(|(u, _)| r.is_anyvar(u))
+                let always_holds = tree_constraint.smap_ref().iter().any(|(u, v)| {
+                    let u = r.walk(u);
+                    let v = r.walk(v);
+                    let mistyped = match type_of(u) {
+                        Some(ty) => !ty.admits(v, &types),
+                        None => false,
+                    };
+                    mistyped || absent.contains(&(r.walk_star(v), u.clone()))
+                });
+                if !always_holds
+                    && tree_constraint
+                        .smap_ref()
+                        .iter()
+                        .any(|(u, _)| r.is_anyvar(u))
                 {
                     purified_cstore.insert(constraint);
                 }
+            } else if let Some(typec) = constraint.downcast_ref::<TypeConstraint<U, E>>() {
+                if r.is_anyvar(typec.x()) {
+                    purified_cstore.insert(constraint);
+                }
+            } else if let Some(absentc) = constraint.downcast_ref::<AbsentoConstraint<U, E>>() {
+                let t = r.walk(absentc.t());
+                let always_holds = match type_of(t) {
+                    Some(ty) => !ty.admits(&r.walk_star(absentc.tag()), &types),
+                    None => false,
+                };
+                if !always_holds && r.is_anyvar(absentc.t()) {
+                    purified_cstore.insert(constraint);
+                }
+            } else if let Some(freshc) = constraint.downcast_ref::<FreshnessConstraint<U, E>>() {
+                if r.is_anyvar(freshc.t()) {
+                    purified_cstore.insert(constraint);
+                }
             } else {
                 purified_cstore.insert(constraint);
             }
@@ -47,14 +92,13 @@ where
         purified_cstore
     }
 
-    /// Do walk_star for each substitution of each constraint
+    /// Do walk_star for each substitution of each constraint. Constraints that do not
+    /// support walking are dropped.
     pub fn walk_star(&self, smap: &SMap<U, E>) -> ConstraintStore<U, E> {
         let mut walked_cstore = ConstraintStore::new();
         for constraint in self.iter() {
-            if let Some(tree_constraint) = constraint.downcast_ref::<DisequalityConstraint<U, E>>() {
-                let ws = tree_constraint.walk_star(smap);
-                let c = DisequalityConstraint::new(ws);
-                walked_cstore.insert(c);
+            if let Some(walked) = constraint.walk_star_copy(smap) {
+                walked_cstore.insert(walked);
             }
         }
         walked_cstore
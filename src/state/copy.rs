@@ -0,0 +1,132 @@
+//! Copying of terms with their constraints
+//!
+//! A copy of a term is the term with its variables renamed to fresh variables. The constraints
+//! that refer to the variables of the term are copied to the fresh variables: the constraints
+//! of the constraint store are walked with the renaming by `Constraint::walk_star_copy`, and
+//! the finite domains and set domains of the variables are copied as they are.
+use super::constraint::store::ConstraintStore;
+use super::{SMap, SResult, State};
+use crate::compound::CompoundObject;
+use crate::engine::Engine;
+use crate::lterm::{LTerm, LTermInner};
+use crate::user::User;
+
+/// Collects the variables of the term that are not already in `vars`.
+fn collect_vars<U, E>(t: &LTerm<U, E>, vars: &mut Vec<LTerm<U, E>>)
+where
+    U: User,
+    E: Engine<U>,
+{
+    match t.as_ref() {
+        LTermInner::Var(_, _) if !vars.contains(t) => vars.push(t.clone()),
+        LTermInner::Cons(head, tail) => {
+            collect_vars(head, vars);
+            collect_vars(tail, vars);
+        }
+        LTermInner::Compound(compound) => collect_compound_vars(compound.as_ref(), vars),
+        LTermInner::Tie(_, body) => collect_vars(body, vars),
+        LTermInner::Susp(_, x) => collect_vars(x, vars),
+        _ => (),
+    }
+}
+
+fn collect_compound_vars<U, E>(compound: &dyn CompoundObject<U, E>, vars: &mut Vec<LTerm<U, E>>)
+where
+    U: User,
+    E: Engine<U>,
+{
+    for child in compound.children() {
+        match child.as_term() {
+            Some(t) => collect_vars(t, vars),
+            None => collect_compound_vars(child, vars),
+        }
+    }
+}
+
+impl<U, E> State<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Unifies `copy` with a copy of the term `t` where the variables are renamed to fresh
+    /// variables, and copies the constraints on the variables of `t` to the fresh variables.
+    /// Returns an error if a copied constraint fails or if the unification fails.
+    pub fn copy_term(mut self, t: &LTerm<U, E>, copy: &LTerm<U, E>) -> SResult<U, E> {
+        let t = self.smap_ref().walk_star(t);
+        let mut vars = vec![];
+        collect_vars(&t, &mut vars);
+
+        let mut renaming = SMap::new();
+        for x in vars.iter() {
+            let fresh = match x.as_ref() {
+                LTermInner::Var(_, "_") => LTerm::any(),
+                LTermInner::Var(_, name) => LTerm::var(name),
+                _ => unreachable!(),
+            };
+            renaming.extend(x.clone(), fresh);
+        }
+        let fresh_t = renaming.walk_star(&t);
+
+        for x in vars.iter() {
+            if let Some(domain) = self.dstore_ref().get(x).cloned() {
+                let _ = self
+                    .dstore_to_mut()
+                    .insert(renaming.walk(x).clone(), domain);
+            }
+            #[cfg(feature = "clpset")]
+            {
+                if let Some(domain) = self.sstore_ref().get(x).cloned() {
+                    let _ = self
+                        .sstore_to_mut()
+                        .insert(renaming.walk(x).clone(), domain);
+                }
+            }
+        }
+
+        let mut relevant = ConstraintStore::new();
+        for constraint in self.cstore_ref().iter() {
+            let mut operand_vars = vec![];
+            for operand in constraint.operands() {
+                collect_vars(&self.smap_ref().walk_star(&operand), &mut operand_vars);
+            }
+            if operand_vars.iter().any(|x| vars.contains(x)) {
+                relevant.insert(constraint.clone());
+            }
+        }
+        let copied = relevant.walk_star(self.smap_ref()).walk_star(&renaming);
+        for constraint in copied.into_iter() {
+            self = constraint.run(self)?;
+        }
+
+        self.unify(copy, &fresh_t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::state::State;
+
+    #[test]
+    fn test_copy_term_1() {
+        let x: LTerm = LTerm::var("x");
+        let y: LTerm = LTerm::var("y");
+        let t = lterm!([x, y, x, 1]);
+        let c: LTerm = LTerm::var("c");
+        let state = State::new(DefaultUser::new()).copy_term(&t, &c).unwrap();
+        let items = state
+            .smap_ref()
+            .walk_star(&c)
+            .iter()
+            .cloned()
+            .collect::<Vec<LTerm>>();
+        assert_eq!(items.len(), 4);
+        assert!(items[0].is_var() && items[1].is_var());
+        assert_ne!(items[0], x);
+        assert_ne!(items[1], y);
+        assert_ne!(items[0], items[1]);
+        assert_eq!(items[0], items[2]);
+        assert_eq!(items[3], 1);
+        assert!(state.cstore_ref().is_empty());
+    }
+}
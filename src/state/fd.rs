@@ -1,21 +1,42 @@
 use std::borrow::Borrow;
 use std::cmp::{max, min};
 use std::iter::Iterator;
-use std::ops::RangeInclusive;
+use std::ops::{Deref, DerefMut, RangeInclusive};
 use std::slice::Iter;
 use std::vec::IntoIter;
 
+/// Sparse domains whose values span at most this many integers are stored as bitsets.
+const BITSET_MAX_SPAN: usize = 4096;
+
+const WORD_BITS: usize = 64;
+
 #[derive(Debug, Clone)]
 pub enum FiniteDomain {
     Interval(RangeInclusive<isize>),
     Sparse(Vec<isize>),
+    BitSet(BitSet),
 }
 
 impl FiniteDomain {
+    /// Constructs a domain from sorted values without duplicates, choosing the representation
+    /// by the span of the values. Returns `None` if there are no values.
+    fn from_sorted(v: Vec<isize>) -> Option<FiniteDomain> {
+        let (first, last) = match (v.first(), v.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return None,
+        };
+        if (last.wrapping_sub(first) as usize) < BITSET_MAX_SPAN {
+            Some(FiniteDomain::BitSet(BitSet::from_sorted(&v)))
+        } else {
+            Some(FiniteDomain::Sparse(v))
+        }
+    }
+
     pub fn is_singleton(&self) -> bool {
         match self {
-            FiniteDomain::Interval(r) => (r.end() - r.start()).saturating_add(1) == 1,
+            FiniteDomain::Interval(r) => r.start() == r.end(),
             FiniteDomain::Sparse(v) => v.len() == 1,
+            FiniteDomain::BitSet(b) => b.words.len() == 1 && b.words[0].count_ones() == 1,
         }
     }
 
@@ -27,10 +48,23 @@ impl FiniteDomain {
         }
     }
 
+    /// Number of values in the domain. Saturates to `usize::MAX` for intervals that span
+    /// the whole range of `isize`.
+    pub fn size(&self) -> usize {
+        match self {
+            FiniteDomain::Interval(r) => {
+                (r.end().wrapping_sub(*r.start()) as usize).saturating_add(1)
+            }
+            FiniteDomain::Sparse(v) => v.len(),
+            FiniteDomain::BitSet(b) => b.len(),
+        }
+    }
+
     pub fn min(&self) -> isize {
         match self {
             FiniteDomain::Interval(r) => *r.start(),
             FiniteDomain::Sparse(v) => v.first().copied().unwrap(),
+            FiniteDomain::BitSet(b) => b.min(),
         }
     }
 
@@ -38,30 +72,22 @@ impl FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => *r.end(),
             FiniteDomain::Sparse(v) => v.last().copied().unwrap(),
+            FiniteDomain::BitSet(b) => b.max(),
         }
     }
 
     pub fn copy_before<P: FnMut(&isize) -> bool>(&self, mut predicate: P) -> Option<FiniteDomain> {
         match self {
             FiniteDomain::Interval(r) => match r.clone().into_iter().find(predicate) {
-                Some(u) => {
-                    let r = *r.start()..=u.saturating_sub(1);
-                    if r.is_empty() {
-                        None
-                    } else {
-                        Some(FiniteDomain::Interval(r))
+                Some(u) => match u.checked_sub(1) {
+                    Some(end) if *r.start() <= end => {
+                        Some(FiniteDomain::Interval(*r.start()..=end))
                     }
-                }
+                    _ => None,
+                },
                 None => Some(self.clone()),
             },
-            FiniteDomain::Sparse(v) => {
-                let v: Vec<isize> = v.iter().copied().take_while(|u| !predicate(u)).collect();
-                if v.is_empty() {
-                    None
-                } else {
-                    Some(FiniteDomain::Sparse(v))
-                }
-            }
+            _ => FiniteDomain::from_sorted(self.iter().take_while(|u| !predicate(u)).collect()),
         }
     }
 
@@ -74,14 +100,7 @@ impl FiniteDomain {
                 }
                 None => None,
             },
-            FiniteDomain::Sparse(v) => {
-                let v: Vec<isize> = v.iter().copied().skip_while(|u| !predicate(u)).collect();
-                if v.is_empty() {
-                    None
-                } else {
-                    Some(FiniteDomain::Sparse(v))
-                }
-            }
+            _ => FiniteDomain::from_sorted(self.iter().skip_while(|u| !predicate(u)).collect()),
         }
     }
 
@@ -98,6 +117,23 @@ impl FiniteDomain {
                     None
                 }
             }
+            (FiniteDomain::BitSet(bself), FiniteDomain::BitSet(bother)) => {
+                let mut intersection = bself.clone();
+                if intersection.intersect_with(bother) {
+                    Some(FiniteDomain::BitSet(intersection))
+                } else {
+                    None
+                }
+            }
+            (FiniteDomain::BitSet(b), FiniteDomain::Interval(r))
+            | (FiniteDomain::Interval(r), FiniteDomain::BitSet(b)) => {
+                let mut intersection = b.clone();
+                if intersection.retain_range(*r.start(), *r.end()) {
+                    Some(FiniteDomain::BitSet(intersection))
+                } else {
+                    None
+                }
+            }
             (FiniteDomain::Sparse(v), FiniteDomain::Interval(r))
             | (FiniteDomain::Interval(r), FiniteDomain::Sparse(v)) => {
                 // Intersection between sparse and interval domains results in sparse
@@ -108,12 +144,16 @@ impl FiniteDomain {
                     .skip_while(|u| u < r.start())
                     .take_while(|u| u <= r.end())
                     .collect::<Vec<isize>>();
-
-                if intersection.is_empty() {
-                    None
-                } else {
-                    Some(FiniteDomain::Sparse(intersection))
-                }
+                FiniteDomain::from_sorted(intersection)
+            }
+            (FiniteDomain::Sparse(v), FiniteDomain::BitSet(b))
+            | (FiniteDomain::BitSet(b), FiniteDomain::Sparse(v)) => {
+                let intersection = v
+                    .iter()
+                    .copied()
+                    .filter(|u| b.contains(*u))
+                    .collect::<Vec<isize>>();
+                FiniteDomain::from_sorted(intersection)
             }
             _ => {
                 let mut intersection = vec![];
@@ -133,48 +173,80 @@ impl FiniteDomain {
                         _ => break,
                     }
                 }
-
-                if intersection.is_empty() {
-                    None
-                } else {
-                    Some(FiniteDomain::Sparse(intersection))
-                }
+                FiniteDomain::from_sorted(intersection)
             }
         }
     }
 
     pub fn diff<T: Borrow<FiniteDomain>>(&self, other: T) -> Option<FiniteDomain> {
-        let mut difference = vec![];
-        let mut siter = self.iter();
-        let mut oiter = other.borrow().iter();
-        let mut maybe_s = siter.next();
-        let mut maybe_o = oiter.next();
-        loop {
-            match (maybe_s, maybe_o) {
-                (Some(s), None) => {
-                    maybe_s = siter.next();
-                    difference.push(s);
+        match (self, other.borrow()) {
+            (FiniteDomain::BitSet(bself), FiniteDomain::BitSet(bother)) => {
+                let mut difference = bself.clone();
+                if difference.difference_with(bother) {
+                    Some(FiniteDomain::BitSet(difference))
+                } else {
+                    None
                 }
-                (Some(s), Some(o)) if s < o => {
-                    maybe_s = siter.next();
-                    difference.push(s);
+            }
+            (FiniteDomain::Interval(rself), FiniteDomain::Interval(rother)) => {
+                let (start, end) = (*rself.start(), *rself.end());
+                let (ostart, oend) = (*rother.start(), *rother.end());
+                if ostart <= start && oend >= end {
+                    None
+                } else if ostart > end || oend < start {
+                    Some(self.clone())
+                } else if ostart <= start {
+                    Some(FiniteDomain::Interval(oend + 1..=end))
+                } else if oend >= end {
+                    Some(FiniteDomain::Interval(start..=ostart - 1))
+                } else if (end.wrapping_sub(start) as usize) < BITSET_MAX_SPAN {
+                    // A hole in the middle of the interval
+                    let mut difference = BitSet::from_range(start, end);
+                    difference.remove_range(ostart, oend);
+                    Some(FiniteDomain::BitSet(difference))
+                } else {
+                    let mut difference = (start..ostart).collect::<Vec<isize>>();
+                    difference.extend(oend + 1..=end);
+                    Some(FiniteDomain::Sparse(difference))
                 }
-                (Some(s), Some(o)) if s == o => {
-                    maybe_s = siter.next();
-                    maybe_o = oiter.next();
+            }
+            (FiniteDomain::BitSet(b), FiniteDomain::Interval(r)) => {
+                let mut difference = b.clone();
+                if difference.remove_range(*r.start(), *r.end()) {
+                    Some(FiniteDomain::BitSet(difference))
+                } else {
+                    None
                 }
-                (Some(s), Some(o)) if s > o => {
-                    maybe_o = oiter.next();
+            }
+            (_, other) => {
+                let mut difference = vec![];
+                let mut siter = self.iter();
+                let mut oiter = other.iter();
+                let mut maybe_s = siter.next();
+                let mut maybe_o = oiter.next();
+                loop {
+                    match (maybe_s, maybe_o) {
+                        (Some(s), None) => {
+                            maybe_s = siter.next();
+                            difference.push(s);
+                        }
+                        (Some(s), Some(o)) if s < o => {
+                            maybe_s = siter.next();
+                            difference.push(s);
+                        }
+                        (Some(s), Some(o)) if s == o => {
+                            maybe_s = siter.next();
+                            maybe_o = oiter.next();
+                        }
+                        (Some(s), Some(o)) if s > o => {
+                            maybe_o = oiter.next();
+                        }
+                        _ => break,
+                    }
                 }
-                _ => break,
+                FiniteDomain::from_sorted(difference)
             }
         }
-
-        if difference.is_empty() {
-            None
-        } else {
-            Some(FiniteDomain::Sparse(difference))
-        }
     }
 
     pub fn is_disjoint<T: Borrow<FiniteDomain>>(&self, other: T) -> bool {
@@ -183,6 +255,10 @@ impl FiniteDomain {
             return true;
         }
 
+        if let (FiniteDomain::BitSet(bself), FiniteDomain::BitSet(bother)) = (self, other) {
+            return bself.is_disjoint(bother);
+        }
+
         let mut siter = self.iter();
         let mut oiter = other.iter();
         let mut maybe_s = siter.next();
@@ -205,6 +281,7 @@ impl FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => r.contains(&u),
             FiniteDomain::Sparse(v) => v.binary_search(&u).is_ok(),
+            FiniteDomain::BitSet(b) => b.contains(u),
         }
     }
 
@@ -212,6 +289,7 @@ impl FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => FiniteDomainIter::IntervalIter(r.clone().into_iter()),
             FiniteDomain::Sparse(v) => FiniteDomainIter::SparseIter(v.iter()),
+            FiniteDomain::BitSet(b) => FiniteDomainIter::BitSetIter(b.iter()),
         }
     }
 
@@ -219,6 +297,7 @@ impl FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => FiniteDomainIntoIter::IntervalIter(r.clone().into_iter()),
             FiniteDomain::Sparse(v) => FiniteDomainIntoIter::SparseIter(v.clone().into_iter()),
+            FiniteDomain::BitSet(b) => FiniteDomainIntoIter::BitSetIter(b.into_iter()),
         }
     }
 }
@@ -229,9 +308,314 @@ impl PartialEq for FiniteDomain {
     }
 }
 
+/// Set of integers stored as the bits of 64-bit words, for domains of small span.
+///
+/// The offset, i.e. the value of the first bit, is aligned to a multiple of 64 so that the
+/// words of two bitsets line up, and the first and the last words are never zero. Set
+/// operations are done in place, word by word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    offset: isize,
+    words: Words,
+}
+
+/// Words of a bitset. Sets that fit in a single word are stored without allocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Words {
+    Inline(u64),
+    Heap(Vec<u64>),
+}
+
+impl From<Vec<u64>> for Words {
+    fn from(words: Vec<u64>) -> Words {
+        if words.len() == 1 {
+            Words::Inline(words[0])
+        } else {
+            Words::Heap(words)
+        }
+    }
+}
+
+impl Deref for Words {
+    type Target = [u64];
+
+    fn deref(&self) -> &[u64] {
+        match self {
+            Words::Inline(word) => std::slice::from_ref(word),
+            Words::Heap(words) => words,
+        }
+    }
+}
+
+impl DerefMut for Words {
+    fn deref_mut(&mut self) -> &mut [u64] {
+        match self {
+            Words::Inline(word) => std::slice::from_mut(word),
+            Words::Heap(words) => words,
+        }
+    }
+}
+
+impl BitSet {
+    /// Constructs a bitset from non-empty sorted values.
+    fn from_sorted(v: &[isize]) -> BitSet {
+        let offset = v[0].div_euclid(WORD_BITS as isize) * WORD_BITS as isize;
+        let mut words = vec![0; BitSet::index(offset, v[v.len() - 1]) / WORD_BITS + 1];
+        for u in v {
+            let i = BitSet::index(offset, *u);
+            words[i / WORD_BITS] |= 1 << (i % WORD_BITS);
+        }
+        BitSet {
+            offset,
+            words: Words::from(words),
+        }
+    }
+
+    /// Constructs a bitset of values `start..=end`.
+    fn from_range(start: isize, end: isize) -> BitSet {
+        let offset = start.div_euclid(WORD_BITS as isize) * WORD_BITS as isize;
+        let nwords = BitSet::index(offset, end) / WORD_BITS + 1;
+        let mut b = BitSet {
+            offset,
+            words: Words::from(vec![u64::MAX; nwords]),
+        };
+        b.retain_range(start, end);
+        b
+    }
+
+    /// Index of the bit of value `u`, which must not be smaller than `offset`.
+    fn index(offset: isize, u: isize) -> usize {
+        u.wrapping_sub(offset) as usize
+    }
+
+    /// Value of the bit at index `i`.
+    fn value(offset: isize, i: usize) -> isize {
+        offset.wrapping_add(i as isize)
+    }
+
+    fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn min(&self) -> isize {
+        BitSet::value(self.offset, self.words[0].trailing_zeros() as usize)
+    }
+
+    fn max(&self) -> isize {
+        let last = self.words.len() - 1;
+        let bit = WORD_BITS - 1 - self.words[last].leading_zeros() as usize;
+        BitSet::value(self.offset, last * WORD_BITS + bit)
+    }
+
+    fn contains(&self, u: isize) -> bool {
+        if u < self.offset {
+            return false;
+        }
+        let i = BitSet::index(self.offset, u);
+        match self.words.get(i / WORD_BITS) {
+            Some(word) => word >> (i % WORD_BITS) & 1 == 1,
+            None => false,
+        }
+    }
+
+    /// Value of the first bit of word `k`.
+    fn word_start(&self, k: usize) -> i128 {
+        self.offset as i128 + (k * WORD_BITS) as i128
+    }
+
+    /// The word of `other` that is aligned with the word `k` of `self`.
+    fn aligned_word(&self, other: &BitSet, k: usize) -> u64 {
+        let j = (self.word_start(k) - other.offset as i128) / WORD_BITS as i128;
+        if j < 0 || j >= other.words.len() as i128 {
+            0
+        } else {
+            other.words[j as usize]
+        }
+    }
+
+    /// Mask of the bits in range `lo..=hi` of a word that begins at `start`.
+    fn range_mask(start: i128, lo: isize, hi: isize) -> u64 {
+        let lo = max(lo as i128 - start, 0);
+        let hi = min(hi as i128 - start, WORD_BITS as i128 - 1);
+        if lo > hi {
+            0
+        } else if hi - lo + 1 == WORD_BITS as i128 {
+            u64::MAX
+        } else {
+            ((1 << (hi - lo + 1)) - 1) << lo
+        }
+    }
+
+    /// Removes zero words from both ends. Returns `false` if the set became empty.
+    fn trim(&mut self) -> bool {
+        match &mut self.words {
+            Words::Inline(word) => *word != 0,
+            Words::Heap(words) => {
+                while let Some(0) = words.last() {
+                    words.pop();
+                }
+                let leading = words.iter().take_while(|w| **w == 0).count();
+                if leading > 0 {
+                    words.drain(..leading);
+                    self.offset = BitSet::value(self.offset, leading * WORD_BITS);
+                }
+                match words.len() {
+                    0 => false,
+                    1 => {
+                        self.words = Words::Inline(words[0]);
+                        true
+                    }
+                    _ => true,
+                }
+            }
+        }
+    }
+
+    fn intersect_with(&mut self, other: &BitSet) -> bool {
+        for k in 0..self.words.len() {
+            self.words[k] &= self.aligned_word(other, k);
+        }
+        self.trim()
+    }
+
+    fn difference_with(&mut self, other: &BitSet) -> bool {
+        for k in 0..self.words.len() {
+            self.words[k] &= !self.aligned_word(other, k);
+        }
+        self.trim()
+    }
+
+    fn retain_range(&mut self, lo: isize, hi: isize) -> bool {
+        for k in 0..self.words.len() {
+            self.words[k] &= BitSet::range_mask(self.word_start(k), lo, hi);
+        }
+        self.trim()
+    }
+
+    fn remove_range(&mut self, lo: isize, hi: isize) -> bool {
+        for k in 0..self.words.len() {
+            self.words[k] &= !BitSet::range_mask(self.word_start(k), lo, hi);
+        }
+        self.trim()
+    }
+
+    fn is_disjoint(&self, other: &BitSet) -> bool {
+        (0..self.words.len()).all(|k| self.words[k] & self.aligned_word(other, k) == 0)
+    }
+
+    fn iter(&self) -> BitSetIter<'_> {
+        BitSetIter {
+            words: &self.words,
+            cursor: BitCursor::new(self),
+        }
+    }
+
+    fn into_iter(self) -> BitSetIntoIter {
+        BitSetIntoIter {
+            cursor: BitCursor::new(&self),
+            words: self.words,
+        }
+    }
+}
+
+/// Position of double-ended iteration over the bits of a bitset.
+#[derive(Clone)]
+struct BitCursor {
+    offset: isize,
+    // Bit index of the next value from the front
+    front: usize,
+    // Bit index after the next value from the back
+    back: usize,
+}
+
+impl BitCursor {
+    fn new(b: &BitSet) -> BitCursor {
+        BitCursor {
+            offset: b.offset,
+            front: 0,
+            back: b.words.len() * WORD_BITS,
+        }
+    }
+
+    fn next(&mut self, words: &[u64]) -> Option<isize> {
+        while self.front < self.back {
+            let word = words[self.front / WORD_BITS] >> (self.front % WORD_BITS);
+            if word == 0 {
+                self.front = (self.front / WORD_BITS + 1) * WORD_BITS;
+                continue;
+            }
+            let i = self.front + word.trailing_zeros() as usize;
+            if i >= self.back {
+                break;
+            }
+            self.front = i + 1;
+            return Some(BitSet::value(self.offset, i));
+        }
+        self.front = self.back;
+        None
+    }
+
+    fn next_back(&mut self, words: &[u64]) -> Option<isize> {
+        while self.front < self.back {
+            let last = self.back - 1;
+            let word = words[last / WORD_BITS] << (WORD_BITS - 1 - last % WORD_BITS);
+            if word == 0 {
+                self.back = last / WORD_BITS * WORD_BITS;
+                continue;
+            }
+            let i = last - word.leading_zeros() as usize;
+            if i < self.front {
+                break;
+            }
+            self.back = i;
+            return Some(BitSet::value(self.offset, i));
+        }
+        self.back = self.front;
+        None
+    }
+}
+
+pub struct BitSetIter<'a> {
+    words: &'a [u64],
+    cursor: BitCursor,
+}
+
+impl<'a> Iterator for BitSetIter<'a> {
+    type Item = isize;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next(self.words)
+    }
+}
+
+impl<'a> DoubleEndedIterator for BitSetIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor.next_back(self.words)
+    }
+}
+
+#[derive(Clone)]
+pub struct BitSetIntoIter {
+    words: Words,
+    cursor: BitCursor,
+}
+
+impl Iterator for BitSetIntoIter {
+    type Item = isize;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next(&self.words)
+    }
+}
+
+impl DoubleEndedIterator for BitSetIntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor.next_back(&self.words)
+    }
+}
+
 pub enum FiniteDomainIter<'a> {
     IntervalIter(RangeInclusive<isize>),
     SparseIter(Iter<'a, isize>),
+    BitSetIter(BitSetIter<'a>),
 }
 
 impl<'a> Iterator for FiniteDomainIter<'a> {
@@ -240,6 +624,7 @@ impl<'a> Iterator for FiniteDomainIter<'a> {
         match self {
             FiniteDomainIter::IntervalIter(r) => r.next(),
             FiniteDomainIter::SparseIter(v) => v.copied().next(),
+            FiniteDomainIter::BitSetIter(b) => b.next(),
         }
     }
 }
@@ -249,6 +634,7 @@ impl<'a> DoubleEndedIterator for FiniteDomainIter<'a> {
         match self {
             FiniteDomainIter::IntervalIter(r) => r.next_back(),
             FiniteDomainIter::SparseIter(v) => v.copied().next_back(),
+            FiniteDomainIter::BitSetIter(b) => b.next_back(),
         }
     }
 }
@@ -257,6 +643,7 @@ impl<'a> DoubleEndedIterator for FiniteDomainIter<'a> {
 pub enum FiniteDomainIntoIter {
     IntervalIter(RangeInclusive<isize>),
     SparseIter(IntoIter<isize>),
+    BitSetIter(BitSetIntoIter),
 }
 
 impl Iterator for FiniteDomainIntoIter {
@@ -265,6 +652,7 @@ impl Iterator for FiniteDomainIntoIter {
         match self {
             FiniteDomainIntoIter::IntervalIter(r) => r.next(),
             FiniteDomainIntoIter::SparseIter(v) => v.next(),
+            FiniteDomainIntoIter::BitSetIter(b) => b.next(),
         }
     }
 }
@@ -274,6 +662,7 @@ impl DoubleEndedIterator for FiniteDomainIntoIter {
         match self {
             FiniteDomainIntoIter::IntervalIter(r) => r.next_back(),
             FiniteDomainIntoIter::SparseIter(v) => v.next_back(),
+            FiniteDomainIntoIter::BitSetIter(b) => b.next_back(),
         }
     }
 }
@@ -284,7 +673,8 @@ impl From<Vec<isize>> for FiniteDomain {
             panic!("Cannot construct empty finite domain");
         }
         v.sort();
-        FiniteDomain::Sparse(v)
+        v.dedup();
+        FiniteDomain::from_sorted(v).unwrap()
     }
 }
 
@@ -329,6 +719,17 @@ mod test {
         assert_eq!(fd.max(), 0);
     }
 
+    #[test]
+    fn test_finitedomain_size() {
+        assert_eq!(FiniteDomain::from(1..=8).size(), 8);
+        assert_eq!(FiniteDomain::from(vec![-1, 2, 3, 4]).size(), 4);
+        assert_eq!(FiniteDomain::from(0).size(), 1);
+        assert_eq!(
+            FiniteDomain::from(isize::MIN..=isize::MAX).size(),
+            usize::MAX
+        );
+    }
+
     #[test]
     fn test_finitedomain_2() {
         // copy_before interval
@@ -441,4 +842,122 @@ mod test {
         // Intesection of disjoint intervals is None
         assert!(a.intersect(&c).is_none());
     }
+
+    #[test]
+    fn test_finitedomain_bitset_1() {
+        // Small spans are stored as bitsets, large spans as sparse vectors
+        let fd = FiniteDomain::from(vec![-70, -1, 0, 63, 64, 200]);
+        assert!(matches!(fd, FiniteDomain::BitSet(_)));
+        assert_eq!(fd.min(), -70);
+        assert_eq!(fd.max(), 200);
+        assert_eq!(fd.size(), 6);
+        assert!(fd.contains(-1));
+        assert!(fd.contains(64));
+        assert!(!fd.contains(65));
+        assert!(!fd.contains(-200));
+        assert!(!fd.contains(isize::MAX));
+        assert_eq!(
+            fd.iter().collect::<Vec<isize>>(),
+            vec![-70, -1, 0, 63, 64, 200]
+        );
+        assert_eq!(
+            fd.iter().rev().collect::<Vec<isize>>(),
+            vec![200, 64, 63, 0, -1, -70]
+        );
+
+        let fd = FiniteDomain::from(vec![0, 1_000_000]);
+        assert!(matches!(fd, FiniteDomain::Sparse(_)));
+    }
+
+    #[test]
+    fn test_finitedomain_bitset_2() {
+        // bitset operations
+        let a = FiniteDomain::from(vec![1, 2, 3, 100, 130]);
+        let b = FiniteDomain::from(vec![3, 4, 130, 131]);
+        assert_eq!(a.intersect(&b).unwrap(), FiniteDomain::from(vec![3, 130]));
+        assert_eq!(a.diff(&b).unwrap(), FiniteDomain::from(vec![1, 2, 100]));
+        assert_eq!(
+            a.intersect(FiniteDomain::from(2..=100)).unwrap(),
+            FiniteDomain::from(vec![2, 3, 100])
+        );
+        assert_eq!(
+            FiniteDomain::from(2..=100).intersect(&a).unwrap(),
+            FiniteDomain::from(vec![2, 3, 100])
+        );
+        assert_eq!(
+            a.diff(FiniteDomain::from(2..=100)).unwrap(),
+            FiniteDomain::from(vec![1, 130])
+        );
+        assert!(a.diff(FiniteDomain::from(0..=200)).is_none());
+        assert!(a.intersect(FiniteDomain::from(vec![4, 5])).is_none());
+        assert!(!a.is_disjoint(&b));
+        assert!(a.is_disjoint(FiniteDomain::from(vec![4, 131])));
+
+        // Removing values from the ends keeps min and max up to date
+        let c = a.diff(FiniteDomain::from(vec![1, 2, 3, 130])).unwrap();
+        assert!(c.is_singleton());
+        assert_eq!(c.singleton_value(), Some(100));
+    }
+
+    #[test]
+    fn test_finitedomain_diff_interval() {
+        let a = FiniteDomain::from(1..=9);
+        assert_eq!(
+            a.diff(FiniteDomain::from(1)).unwrap(),
+            FiniteDomain::from(2..=9)
+        );
+        assert_eq!(
+            a.diff(FiniteDomain::from(9)).unwrap(),
+            FiniteDomain::from(1..=8)
+        );
+        assert_eq!(
+            a.diff(FiniteDomain::from(3..=5)).unwrap(),
+            FiniteDomain::from(vec![1, 2, 6, 7, 8, 9])
+        );
+        assert_eq!(a.diff(FiniteDomain::from(10..=12)).unwrap(), a);
+        assert!(a.diff(FiniteDomain::from(0..=10)).is_none());
+
+        let b = FiniteDomain::from(0..=1_000_000);
+        let c = b.diff(FiniteDomain::from(1..=999_999)).unwrap();
+        assert_eq!(c, FiniteDomain::from(vec![0, 1_000_000]));
+    }
+
+    #[test]
+    fn test_finitedomain_bitset_3() {
+        // bitsets at the ends of isize
+        let a = FiniteDomain::from(vec![isize::MAX - 1, isize::MAX]);
+        assert_eq!(a.max(), isize::MAX);
+        assert_eq!(a.iter().rev().next(), Some(isize::MAX));
+        let b = FiniteDomain::from(vec![isize::MIN, isize::MIN + 1]);
+        assert_eq!(b.min(), isize::MIN);
+        assert_eq!(
+            b.diff(FiniteDomain::from(isize::MIN)).unwrap().min(),
+            isize::MIN + 1
+        );
+        assert!(a.is_disjoint(&b));
+    }
+
+    #[test]
+    fn test_finitedomain_interval_bounds() {
+        // Intervals at the ends of isize
+        let a = FiniteDomain::from(isize::MIN..=isize::MIN + 2);
+        assert!(a.copy_before(|x| *x == isize::MIN).is_none());
+        assert_eq!(
+            a.copy_before(|x| *x > isize::MIN).unwrap(),
+            FiniteDomain::from(isize::MIN)
+        );
+        let b = FiniteDomain::from(isize::MAX - 2..=isize::MAX);
+        assert_eq!(
+            b.drop_before(|x| *x == isize::MAX).unwrap(),
+            FiniteDomain::from(isize::MAX)
+        );
+        assert_eq!(
+            b.diff(FiniteDomain::from(isize::MAX)).unwrap().max(),
+            isize::MAX - 1
+        );
+        assert_eq!(
+            FiniteDomain::from(isize::MIN..=isize::MAX).size(),
+            usize::MAX
+        );
+    }
 }
@@ -15,9 +15,23 @@ pub use unification::unify_rec;
 pub mod constraint;
 pub use constraint::Constraint;
 
+mod copy;
+
 pub mod fd;
 pub use fd::FiniteDomain;
 
+pub mod nominal;
+
+#[cfg(feature = "clpset")]
+pub mod set;
+#[cfg(feature = "clpset")]
+pub use set::SetDomain;
+
+#[cfg(feature = "clpq")]
+pub mod simplex;
+#[cfg(feature = "clpq")]
+pub use simplex::Tableau;
+
 use constraint::store::ConstraintStore;
 
 pub mod map_sum;
@@ -39,6 +53,10 @@ pub type SResult<U, E> = Result<State<U, E>, ()>;
 ///    2. The constraint store
 ///    3. The domain store
 ///    4. User data
+///
+/// With the `clpset` feature, the domains of set variables are kept in a set domain store next
+/// to the domain store. With the `clpq` feature, the linear constraints over rationals are kept
+/// in a simplex tableau. Both are also clone-on-write.
 #[derive(Derivative)]
 #[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
 pub struct State<U = DefaultUser, E = DefaultEngine<DefaultUser>>
@@ -55,6 +73,14 @@ where
     /// The domain store
     dstore: Rc<HashMap<LTerm<U, E>, Rc<FiniteDomain>>>,
 
+    /// The set domain store
+    #[cfg(feature = "clpset")]
+    sstore: Rc<HashMap<LTerm<U, E>, Rc<SetDomain>>>,
+
+    /// The simplex tableau of linear constraints over rationals
+    #[cfg(feature = "clpq")]
+    qstore: Rc<Tableau<U, E>>,
+
     pub user_state: U,
 }
 
@@ -68,6 +94,10 @@ where
             smap: Rc::new(SMap::new()),
             cstore: Rc::new(ConstraintStore::new()),
             dstore: Rc::new(HashMap::new()),
+            #[cfg(feature = "clpset")]
+            sstore: Rc::new(HashMap::new()),
+            #[cfg(feature = "clpq")]
+            qstore: Rc::new(Tableau::new()),
             user_state,
         }
     }
@@ -140,6 +170,17 @@ where
         Rc::clone(&self.dstore)
     }
 
+    /// Return a reference to the simplex tableau of the state
+    #[cfg(feature = "clpq")]
+    pub fn qstore_ref(&self) -> &Tableau<U, E> {
+        self.qstore.as_ref()
+    }
+
+    #[cfg(feature = "clpq")]
+    pub fn qstore_to_mut(&mut self) -> &mut Tableau<U, E> {
+        Rc::make_mut(&mut self.qstore)
+    }
+
     /// Return the state with a new constraint
     pub fn with_constraint(mut self, constraint: Rc<dyn Constraint<U, E>>) -> State<U, E> {
         U::with_constraint(&mut self, &constraint);
@@ -163,9 +204,13 @@ where
     /// Adds a new domain constraint for a variable `x`; or if the term is a value, then
     /// checks that the value is within the domain. If new domain constraint is added for a
     /// variable, it is updated to the domain store.
+    ///
+    /// The term is walked first, because a variable walked by the caller may have become bound
+    /// by constraints that were run while processing an earlier domain.
     pub fn process_domain(self, x: &LTerm<U, E>, domain: Rc<FiniteDomain>) -> SResult<U, E> {
+        let x = self.smap_ref().walk(x).clone();
         match x.as_ref() {
-            LTermInner::Var(_, _) => self.update_var_domain(x, domain),
+            LTermInner::Var(_, _) => self.update_var_domain(&x, domain),
             LTermInner::Val(LValue::Number(v)) if domain.contains(*v) => Ok(self),
             _ => Err(()),
         }
@@ -316,6 +361,19 @@ where
         Ok(self)
     }
 
+    /// Processes extension for linear constraints over rationals.
+    ///
+    /// A variable of the tableau that is bound to a number is fixed to the value in the
+    /// tableau, and two variables of the tableau that are unified are constrained equal.
+    #[cfg(feature = "clpq")]
+    fn process_extension_q(self, extension: &SMap<U, E>) -> SResult<U, E> {
+        if self.qstore_ref().is_empty() {
+            Ok(self)
+        } else {
+            crate::relation::clpq::process_extension(self, extension)
+        }
+    }
+
     fn process_extension_user(self, extension: &SMap<U, E>) -> SResult<U, E> {
         User::process_extension(self, extension)
     }
@@ -326,9 +384,14 @@ where
     /// unification. It consists of the substitutions had to be added in order to unify
     /// the two terms.
     fn process_extension(self, extension: SMap<U, E>) -> SResult<U, E> {
-        self.process_extension_diseq(&extension)?
-            .process_extension_fd(&extension)?
-            .process_extension_user(&extension)
+        let state = self
+            .process_extension_diseq(&extension)?
+            .process_extension_fd(&extension)?;
+        #[cfg(feature = "clpset")]
+        let state = state.process_extension_set(&extension)?;
+        #[cfg(feature = "clpq")]
+        let state = state.process_extension_q(&extension)?;
+        state.process_extension_user(&extension)
     }
 
     fn is_finite_domain(constraint: &Rc<dyn Constraint<U, E>>) -> bool {
@@ -339,6 +402,9 @@ where
             || constraint.is::<crate::relation::clpfd::diseqfd::DiseqFdConstraint<U, E>>()
             || constraint.is::<crate::relation::clpfd::distinctfd::DistinctFdConstraint<U, E>>()
             || constraint.is::<crate::relation::clpfd::distinctfd::DistinctFd2Constraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::reified::ReifiedFdConstraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::boolfd::BoolFdConstraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::arithfd::ArithFdConstraint<U, E>>()
     }
 
     /// Verifies that all variables constrained by domain constraints have domains
@@ -397,3 +463,31 @@ where
         U::reify(self);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{FiniteDomain, State};
+    use crate::prelude::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_process_domain_bound_var() {
+        // A variable that has been bound after the caller walked it is checked against the
+        // domain instead of getting a domain of its own.
+        let x: LTerm = LTerm::var("x");
+        let state: State<DefaultUser, DefaultEngine<DefaultUser>> = State::new(DefaultUser::new())
+            .process_domain(&x, Rc::new(FiniteDomain::from(0..=5)))
+            .unwrap()
+            .unify(&x, &lterm!(3))
+            .unwrap();
+        assert!(state
+            .clone()
+            .process_domain(&x, Rc::new(FiniteDomain::from(4..=5)))
+            .is_err());
+        let state = state
+            .process_domain(&x, Rc::new(FiniteDomain::from(0..=3)))
+            .unwrap();
+        assert!(state.dstore_ref().get(&x).is_none());
+        assert_eq!(state.smap_ref().walk(&x), &3);
+    }
+}
@@ -0,0 +1,126 @@
+//! Permutations of nominal atoms
+//!
+//! A permutation is a list of swaps of nominal atoms. The swaps are applied from the last to
+//! the first, such that the permutation `p1 ++ p2` applies `p2` first and `p1` after it.
+use super::SMap;
+use crate::engine::Engine;
+use crate::lterm::{LTerm, LTermInner};
+use crate::user::User;
+
+/// Swap of two nominal atoms
+pub type Swap<U, E> = (LTerm<U, E>, LTerm<U, E>);
+
+pub type Permutation<U, E> = Vec<Swap<U, E>>;
+
+/// Applies the permutation to a nominal atom.
+pub fn permute_nom<U, E>(perm: &[Swap<U, E>], a: &LTerm<U, E>) -> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut a = a.clone();
+    for (b, c) in perm.iter().rev() {
+        if &a == b {
+            a = c.clone();
+        } else if &a == c {
+            a = b.clone();
+        }
+    }
+    a
+}
+
+/// Applies the permutation to a term. The permutation is suspended on the variables of the
+/// term. Compound objects are not permuted.
+pub fn permute<U, E>(perm: &[Swap<U, E>], t: &LTerm<U, E>) -> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    if perm.is_empty() {
+        return t.clone();
+    }
+
+    match t.as_ref() {
+        LTermInner::Nom(_, _) => permute_nom(perm, t),
+        LTermInner::Var(_, _) => LTerm::susp(perm.to_vec(), t.clone()),
+        LTermInner::Susp(inner, x) => {
+            let mut composed = perm.to_vec();
+            composed.extend(inner.iter().cloned());
+            LTerm::susp(composed, x.clone())
+        }
+        LTermInner::Tie(a, body) => LTerm::tie(permute_nom(perm, a), permute(perm, body)),
+        LTermInner::Cons(head, tail) => LTerm::cons(permute(perm, head), permute(perm, tail)),
+        _ => t.clone(),
+    }
+}
+
+/// Inverse of the permutation
+pub fn inverse<U, E>(perm: &[Swap<U, E>]) -> Permutation<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    perm.iter().rev().cloned().collect()
+}
+
+/// Returns the nominal atoms that the permutations map to different atoms.
+pub fn disagreement_set<U, E>(p1: &[Swap<U, E>], p2: &[Swap<U, E>]) -> Vec<LTerm<U, E>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut noms: Vec<LTerm<U, E>> = vec![];
+    for (a, b) in p1.iter().chain(p2.iter()) {
+        for nom in [a, b].iter().copied() {
+            if !noms.contains(nom) && permute_nom(p1, nom) != permute_nom(p2, nom) {
+                noms.push(nom.clone());
+            }
+        }
+    }
+    noms
+}
+
+/// Walks the term, and if it is a suspension on a bound variable, applies the permutation to
+/// the value of the variable. The result is not a suspension on a bound variable.
+pub fn walk_susp<U, E>(smap: &SMap<U, E>, t: &LTerm<U, E>) -> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let t = smap.walk(t);
+    match t.as_ref() {
+        LTermInner::Susp(perm, x) => {
+            let xwalk = smap.walk(x);
+            if xwalk.is_var() {
+                LTerm::susp(perm.clone(), xwalk.clone())
+            } else {
+                walk_susp(smap, &permute(perm, xwalk))
+            }
+        }
+        _ => t.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lterm::LTerm;
+
+    #[test]
+    fn test_permute() {
+        let a: LTerm = LTerm::nom("a");
+        let b = LTerm::nom("b");
+        let c = LTerm::nom("c");
+        let perm = vec![(a.clone(), b.clone()), (b.clone(), c.clone())];
+        // (a b)(b c) maps c -> b -> a
+        assert_eq!(permute_nom(&perm, &c), a);
+        assert_eq!(permute_nom(&perm, &a), b);
+        assert_eq!(permute_nom(&inverse(&perm), &a), c);
+
+        let t = LTerm::tie(a.clone(), LTerm::from_vec(vec![a.clone(), c.clone()]));
+        let u = LTerm::tie(b.clone(), LTerm::from_vec(vec![b.clone(), a.clone()]));
+        assert_eq!(permute(&perm, &t), u);
+
+        assert_eq!(disagreement_set(&perm, &[]), vec![a, b, c]);
+    }
+}
@@ -4,10 +4,20 @@ use crate::lterm::{LTerm, LTermInner};
 use crate::stream::Stream;
 use crate::user::User;
 
-#[cfg(feature = "clpfd")]
+#[cfg(any(feature = "clpfd", feature = "clpset"))]
 use crate::operator::onceo;
+#[cfg(feature = "clpset")]
+use crate::state::SetDomain;
+#[cfg(feature = "clpset")]
+use std::rc::Rc;
 
+#[cfg(feature = "clpq")]
+use crate::relation::clpq::reify_tableau;
+#[cfg(feature = "extras")]
+use crate::relation::string::pending_solutions;
 use crate::state::map_sum::map_sum;
+#[cfg(not(feature = "clpq"))]
+use crate::state::State;
 
 /// Enforces the finite domain constraints by expanding the domains into sequences of numbers,
 /// and returning solutions for all numbers. Adds a `x == d` substitution for each `d` in
@@ -64,22 +74,132 @@ fn enforce_constraints_fd<U: User, E: Engine<U>>(_x: LTerm<U, E>) -> Goal<U, E>
     Goal::succeed()
 }
 
+/// Enforces the set constraints by enumerating the sets within the bounds of the set variables.
+/// The smallest element that a set may contain is first excluded from the set, and then
+/// included in it, so that smaller sets are found first.
+#[cfg(feature = "clpset")]
+fn force_set_ans<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
+    proto_vulcan!(fngoal move |solver, state| {
+        let xwalk: LTerm<U, E> = state.smap_ref().walk(&x).clone();
+        let maybe_xdomain = state.sstore_ref().get(&xwalk).cloned();
+
+        match (xwalk.as_ref(), maybe_xdomain) {
+            (LTermInner::<U, E>::Var(_, _), Some(xdomain)) => {
+                // A set variable whose bounds meet is bound, so the bounds differ.
+                let e = *xdomain.lub().difference(xdomain.glb()).next().unwrap();
+                let mut glb = xdomain.glb().clone();
+                let _ = glb.insert(e);
+                let mut lub = xdomain.lub().clone();
+                let _ = lub.remove(&e);
+                let including = SetDomain::new(glb, xdomain.lub().clone()).unwrap();
+                let excluding = SetDomain::new(xdomain.glb().clone(), lub).unwrap();
+                map_sum(solver, state, |d| {
+                    let d = Rc::new(d);
+                    let x = xwalk.clone();
+                    let xwalk = xwalk.clone();
+                    proto_vulcan!([
+                        fngoal move |solver, state| {
+                            match state
+                                .process_set_domain(&x, Rc::clone(&d))
+                                .and_then(|state| state.run_constraints())
+                            {
+                                Ok(state) => solver.start(&Goal::Succeed, state),
+                                Err(_) => Stream::empty(),
+                            }
+                        },
+                        force_set_ans(xwalk),
+                    ])
+                }, vec![including, excluding].into_iter())
+            }
+            (LTermInner::<U, E>::Cons(head, tail), _) => {
+                let head: LTerm<U, E> = head.clone();
+                let tail: LTerm<U, E> = tail.clone();
+                let g: Goal<U, E>  = proto_vulcan!([
+                    force_set_ans(head),
+                    force_set_ans(tail),
+                ]);
+                g.solve(solver, state)
+            },
+            (_, _) => solver.start(&Goal::Succeed, state),
+        }
+    })
+}
+
+#[cfg(feature = "clpset")]
+fn enforce_constraints_set<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
+    proto_vulcan!([
+        force_set_ans(x),
+        fngoal move |engine, state| {
+            let bound_x = state.sstore_ref().keys().cloned().collect::<LTerm<U, E>>();
+            proto_vulcan!( onceo { force_set_ans(bound_x) } ).solve(engine, state)
+        }
+    ])
+}
+
+#[cfg(not(feature = "clpset"))]
+fn enforce_constraints_set<U: User, E: Engine<U>>(_x: LTerm<U, E>) -> Goal<U, E> {
+    Goal::succeed()
+}
+
+/// Enforces the string constraints by enumerating the solutions of the waiting string
+/// relations that have more than one solution.
+#[cfg(feature = "extras")]
+fn enforce_constraints_string<U: User, E: Engine<U>>() -> Goal<U, E> {
+    proto_vulcan!(fngoal move |solver, state| {
+        match pending_solutions(&state) {
+            Some(solutions) => map_sum(solver, state, |unifications| {
+                proto_vulcan!([
+                    fngoal move |solver, state| {
+                        match unifications
+                            .iter()
+                            .try_fold(state, |state, (u, v)| state.unify(u, v))
+                        {
+                            Ok(state) => solver.start(&Goal::Succeed, state),
+                            Err(_) => Stream::empty(),
+                        }
+                    },
+                    enforce_constraints_string(),
+                ])
+            }, solutions.into_iter().rev()),
+            None => solver.start(&Goal::Succeed, state),
+        }
+    })
+}
+
+#[cfg(not(feature = "extras"))]
+fn enforce_constraints_string<U: User, E: Engine<U>>() -> Goal<U, E> {
+    Goal::succeed()
+}
+
+#[cfg(not(feature = "clpq"))]
+fn reify_tableau<U: User, E: Engine<U>>(state: State<U, E>) -> State<U, E> {
+    state
+}
+
 /// A goal that enforces the current set of constraints.
 ///
 /// The constraints are enforced just before reification.
 ///
 /// For finite domain constraints it means that the domains are converted into sequences
-/// of answers such that the result variariables always have singular domains.
+/// of answers such that the result variariables always have singular domains. Likewise, the
+/// set variables are enumerated into the sets within their bounds, and the solutions of the
+/// string relations are enumerated.
 ///
 /// For disequality constraints this is a no-op.
 fn enforce_constraints<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
-    proto_vulcan!([enforce_constraints_fd(x), U::enforce_constraints(x)])
+    proto_vulcan!([
+        enforce_constraints_fd(x),
+        enforce_constraints_set(x),
+        enforce_constraints_string(),
+        U::enforce_constraints(x)
+    ])
 }
 
 pub fn reify<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
     proto_vulcan!([
         enforce_constraints(x),
         fngoal move |_engine, state| {
+            let state = reify_tableau(state);
             let smap = state.get_smap();
             let v = smap.walk_star(&x);
             let r = smap.reify(&v);
@@ -0,0 +1,202 @@
+//! Finite set domains
+//!
+//! The domain of a set variable is represented by its bounds: the greatest lower bound contains
+//! the elements that the set must contain, and the least upper bound contains the elements that
+//! the set may contain. A set value is a list of distinct numbers in ascending order; lists in
+//! other orders or with duplicates are not sets, since unification of set values is structural.
+use super::{SMap, SResult, State};
+use crate::engine::Engine;
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::user::User;
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetDomain {
+    glb: BTreeSet<isize>,
+    lub: BTreeSet<isize>,
+}
+
+impl SetDomain {
+    /// Constructs a set domain from its bounds, or returns `None` if the lower bound is not a
+    /// subset of the upper bound.
+    pub fn new(glb: BTreeSet<isize>, lub: BTreeSet<isize>) -> Option<SetDomain> {
+        if glb.is_subset(&lub) {
+            Some(SetDomain { glb, lub })
+        } else {
+            None
+        }
+    }
+
+    /// Domain of a known set.
+    pub fn fixed(set: BTreeSet<isize>) -> SetDomain {
+        SetDomain {
+            glb: set.clone(),
+            lub: set,
+        }
+    }
+
+    /// The elements that the set must contain.
+    pub fn glb(&self) -> &BTreeSet<isize> {
+        &self.glb
+    }
+
+    /// The elements that the set may contain.
+    pub fn lub(&self) -> &BTreeSet<isize> {
+        &self.lub
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        self.glb == self.lub
+    }
+
+    /// Domain of the sets that are in both domains, or `None` if there are none.
+    pub fn intersect(&self, other: &SetDomain) -> Option<SetDomain> {
+        SetDomain::new(
+            self.glb.union(&other.glb).copied().collect(),
+            self.lub.intersection(&other.lub).copied().collect(),
+        )
+    }
+
+    pub fn contains(&self, set: &BTreeSet<isize>) -> bool {
+        self.glb.is_subset(set) && set.is_subset(&self.lub)
+    }
+}
+
+/// Returns the set that the term is bound to, `Ok(None)` if the term is not ground, or `Err(())`
+/// if the term is not a list of distinct numbers in ascending order.
+pub fn set_value<U, E>(smap: &SMap<U, E>, t: &LTerm<U, E>) -> Result<Option<BTreeSet<isize>>, ()>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut set = BTreeSet::new();
+    let mut t = smap.walk(t);
+    loop {
+        match t.as_ref() {
+            LTermInner::Empty => return Ok(Some(set)),
+            LTermInner::Cons(head, tail) => {
+                match smap.walk(head).as_ref() {
+                    LTermInner::Var(_, _) => return Ok(None),
+                    LTermInner::Val(LValue::Number(n)) => match set.iter().next_back() {
+                        Some(last) if last >= n => return Err(()),
+                        _ => {
+                            let _ = set.insert(*n);
+                        }
+                    },
+                    _ => return Err(()),
+                }
+                t = smap.walk(tail);
+            }
+            LTermInner::Var(_, _) => return Ok(None),
+            _ => return Err(()),
+        }
+    }
+}
+
+/// The set as a list of numbers in ascending order.
+pub fn set_lterm<U, E>(set: &BTreeSet<isize>) -> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    set.iter().map(|n| LTerm::from(*n)).collect()
+}
+
+impl<U, E> State<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Return a reference to the set domain store of the state
+    pub fn sstore_ref(&self) -> &HashMap<LTerm<U, E>, Rc<SetDomain>> {
+        self.sstore.as_ref()
+    }
+
+    pub fn sstore_to_mut(&mut self) -> &mut HashMap<LTerm<U, E>, Rc<SetDomain>> {
+        Rc::make_mut(&mut self.sstore)
+    }
+
+    /// Returns the set domain of a term: the domain of a variable, or the fixed domain of a set
+    /// value. Returns `Ok(None)` if the term is a variable without a domain.
+    pub fn get_set_domain(&self, x: &LTerm<U, E>) -> Result<Option<Rc<SetDomain>>, ()> {
+        let x = self.smap_ref().walk(x);
+        match self.sstore_ref().get(x) {
+            Some(domain) => Ok(Some(Rc::clone(domain))),
+            None => Ok(set_value(self.smap_ref(), x)?.map(|set| Rc::new(SetDomain::fixed(set)))),
+        }
+    }
+
+    /// Constrains the set term `x` to the set `domain`. A variable is given the intersection of
+    /// its domain and the new domain, and a set value is checked to be within the domain. If the
+    /// domain of a variable becomes fixed, the variable is bound to the set value.
+    pub fn process_set_domain(mut self, x: &LTerm<U, E>, domain: Rc<SetDomain>) -> SResult<U, E> {
+        let x = self.smap_ref().walk(x).clone();
+        if x.is_var() {
+            let domain = match self.sstore_ref().get(&x) {
+                Some(old_domain) => Rc::new(old_domain.intersect(&domain).ok_or(())?),
+                None => domain,
+            };
+            if domain.is_fixed() {
+                // The domain is removed when the extension of the substitution is processed.
+                let value = set_lterm(domain.glb());
+                let _ = self.sstore_to_mut().insert(x.clone(), domain);
+                self.unify(&x, &value)
+            } else {
+                let _ = self.sstore_to_mut().insert(x, domain);
+                Ok(self)
+            }
+        } else {
+            match set_value(self.smap_ref(), &x)? {
+                Some(set) if domain.contains(&set) => Ok(self),
+                Some(_) => Err(()),
+                // A partially instantiated list is not a set value.
+                None => Err(()),
+            }
+        }
+    }
+
+    /// Processes extension for set constraints.
+    ///
+    /// If a variable `x` that has a set domain is substituted with term `v`, the domain is
+    /// moved to `v`, and the constraints are run again.
+    pub(super) fn process_extension_set(mut self, extension: &SMap<U, E>) -> SResult<U, E> {
+        let mut processed = false;
+        for (x, v) in extension.iter() {
+            if let Some(domain) = self.sstore_to_mut().remove(x) {
+                self = self.process_set_domain(v, domain)?;
+                processed = true;
+            }
+        }
+        if processed {
+            self.run_constraints()
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SetDomain;
+    use std::collections::BTreeSet;
+
+    fn set(a: &[isize]) -> BTreeSet<isize> {
+        a.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_set_domain_intersect() {
+        let d1 = SetDomain::new(set(&[1]), set(&[1, 2, 3])).unwrap();
+        let d2 = SetDomain::new(set(&[2]), set(&[1, 2, 4])).unwrap();
+        let d = d1.intersect(&d2).unwrap();
+        assert_eq!(d.glb(), &set(&[1, 2]));
+        assert_eq!(d.lub(), &set(&[1, 2]));
+        assert!(d.is_fixed());
+
+        let d3 = SetDomain::new(set(&[3]), set(&[3])).unwrap();
+        assert!(d2.intersect(&d3).is_none());
+        assert!(SetDomain::new(set(&[5]), set(&[1])).is_none());
+    }
+}
@@ -0,0 +1,637 @@
+//! Incremental simplex tableau for linear constraints over rationals
+//!
+//! The tableau follows the general simplex of Dutertre and de Moura: every linear constraint
+//! becomes a bound on a variable. Constraints on a single variable bound it directly, and other
+//! constraints introduce a slack variable that is defined by a tableau row. Feasibility is
+//! restored after new bounds by pivoting with Bland's rule. Strict bounds are represented with
+//! an infinitesimal `δ` as delta-rationals.
+use crate::engine::Engine;
+use crate::lterm::LTerm;
+use crate::user::User;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::{Add, Sub};
+
+pub type Rational = BigRational;
+
+/// Relation of a linear constraint `expr REL 0`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LinearRelation {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl LinearRelation {
+    /// The relation with the sides swapped, used when a constraint is divided by a negative
+    /// coefficient.
+    fn flip(self) -> LinearRelation {
+        match self {
+            LinearRelation::Eq => LinearRelation::Eq,
+            LinearRelation::Lt => LinearRelation::Gt,
+            LinearRelation::Le => LinearRelation::Ge,
+            LinearRelation::Gt => LinearRelation::Lt,
+            LinearRelation::Ge => LinearRelation::Le,
+        }
+    }
+
+    /// Checks the relation between two constants.
+    pub fn holds(self, u: &Rational, v: &Rational) -> bool {
+        match self {
+            LinearRelation::Eq => u == v,
+            LinearRelation::Lt => u < v,
+            LinearRelation::Le => u <= v,
+            LinearRelation::Gt => u > v,
+            LinearRelation::Ge => u >= v,
+        }
+    }
+}
+
+/// Rational number `real + delta * δ`, where `δ` is a positive infinitesimal.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DeltaRational {
+    pub real: Rational,
+    pub delta: Rational,
+}
+
+impl DeltaRational {
+    pub fn new(real: Rational, delta: Rational) -> DeltaRational {
+        DeltaRational { real, delta }
+    }
+
+    pub fn zero() -> DeltaRational {
+        DeltaRational::from(Rational::zero())
+    }
+
+    fn scale(&self, k: &Rational) -> DeltaRational {
+        DeltaRational::new(&self.real * k, &self.delta * k)
+    }
+}
+
+impl From<Rational> for DeltaRational {
+    fn from(real: Rational) -> DeltaRational {
+        DeltaRational::new(real, Rational::zero())
+    }
+}
+
+impl<'a> Add<&'a DeltaRational> for &'a DeltaRational {
+    type Output = DeltaRational;
+
+    fn add(self, other: &DeltaRational) -> DeltaRational {
+        DeltaRational::new(&self.real + &other.real, &self.delta + &other.delta)
+    }
+}
+
+impl<'a> Sub<&'a DeltaRational> for &'a DeltaRational {
+    type Output = DeltaRational;
+
+    fn sub(self, other: &DeltaRational) -> DeltaRational {
+        DeltaRational::new(&self.real - &other.real, &self.delta - &other.delta)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Column {
+    lower: Option<DeltaRational>,
+    upper: Option<DeltaRational>,
+    value: DeltaRational,
+}
+
+impl Column {
+    fn below_lower(&self) -> bool {
+        matches!(&self.lower, Some(lower) if &self.value < lower)
+    }
+
+    fn above_upper(&self) -> bool {
+        matches!(&self.upper, Some(upper) if &self.value > upper)
+    }
+
+    fn can_increase(&self) -> bool {
+        !matches!(&self.upper, Some(upper) if &self.value >= upper)
+    }
+
+    fn can_decrease(&self) -> bool {
+        !matches!(&self.lower, Some(lower) if &self.value <= lower)
+    }
+}
+
+/// Simplex tableau
+///
+/// Columns are the variables of the tableau. The rows define the basic variables as linear
+/// combinations of the non-basic variables.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+pub struct Tableau<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    vars: HashMap<LTerm<U, E>, usize>,
+    columns: Vec<Column>,
+    rows: BTreeMap<usize, BTreeMap<usize, Rational>>,
+    /// Definitions `slack = sum(a * x)` of the slack variables over the columns of the
+    /// variables, as they were added.
+    slacks: BTreeMap<usize, Vec<(usize, Rational)>>,
+}
+
+impl<U, E> Default for Tableau<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn default() -> Tableau<U, E> {
+        Tableau::new()
+    }
+}
+
+impl<U, E> Tableau<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new() -> Tableau<U, E> {
+        Tableau {
+            vars: HashMap::new(),
+            columns: vec![],
+            rows: BTreeMap::new(),
+            slacks: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    fn new_column(&mut self) -> usize {
+        self.columns.push(Column {
+            lower: None,
+            upper: None,
+            value: DeltaRational::zero(),
+        });
+        self.columns.len() - 1
+    }
+
+    /// Returns the column of the variable `x`, adding a new column if there is none.
+    pub fn column(&mut self, x: &LTerm<U, E>) -> usize {
+        match self.vars.get(x) {
+            Some(col) => *col,
+            None => {
+                let col = self.new_column();
+                self.vars.insert(x.clone(), col);
+                col
+            }
+        }
+    }
+
+    pub fn lookup(&self, x: &LTerm<U, E>) -> Option<usize> {
+        self.vars.get(x).copied()
+    }
+
+    /// Moves the column of the variable `from` to the variable `to`.
+    pub fn rename(&mut self, from: &LTerm<U, E>, to: &LTerm<U, E>) {
+        if let Some(col) = self.vars.remove(from) {
+            self.vars.insert(to.clone(), col);
+        }
+    }
+
+    /// Iterates over the variables and their columns.
+    pub fn vars(&self) -> impl Iterator<Item = (&LTerm<U, E>, usize)> {
+        self.vars.iter().map(|(x, col)| (x, *col))
+    }
+
+    /// Returns the value of a column whose bounds only allow a single value.
+    pub fn fixed_value(&self, col: usize) -> Option<Rational> {
+        match (&self.columns[col].lower, &self.columns[col].upper) {
+            (Some(lower), Some(upper)) if lower == upper && lower.delta.is_zero() => {
+                Some(lower.real.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the columns that share a row with the columns `cols`, directly or through other
+    /// rows. Bounds on `cols` can only determine the values of the connected columns.
+    pub fn connected(&self, cols: &[usize]) -> HashSet<usize> {
+        let mut connected = cols.iter().copied().collect::<HashSet<usize>>();
+        loop {
+            let mut changed = false;
+            for (basic, row) in self.rows.iter() {
+                if connected.contains(basic) || row.keys().any(|k| connected.contains(k)) {
+                    for k in std::iter::once(basic).chain(row.keys()) {
+                        changed |= connected.insert(*k);
+                    }
+                }
+            }
+            if !changed {
+                return connected;
+            }
+        }
+    }
+
+    /// Returns the bounds of the columns as constraints `sum(a * x) REL c`, where the bounds of
+    /// the slack variables are given over their definitions.
+    pub fn bounds(&self) -> Vec<(Vec<(usize, Rational)>, LinearRelation, Rational)> {
+        let mut bounds = vec![];
+        for (col, column) in self.columns.iter().enumerate() {
+            let coeffs = match self.slacks.get(&col) {
+                Some(definition) => definition.clone(),
+                None => vec![(col, Rational::one())],
+            };
+            match (&column.lower, &column.upper) {
+                (Some(lower), Some(upper)) if lower == upper => {
+                    bounds.push((coeffs, LinearRelation::Eq, lower.real.clone()));
+                }
+                (lower, upper) => {
+                    if let Some(lower) = lower {
+                        let relation = if lower.delta.is_zero() {
+                            LinearRelation::Ge
+                        } else {
+                            LinearRelation::Gt
+                        };
+                        bounds.push((coeffs.clone(), relation, lower.real.clone()));
+                    }
+                    if let Some(upper) = upper {
+                        let relation = if upper.delta.is_zero() {
+                            LinearRelation::Le
+                        } else {
+                            LinearRelation::Lt
+                        };
+                        bounds.push((coeffs, relation, upper.real.clone()));
+                    }
+                }
+            }
+        }
+        bounds
+    }
+
+    /// Adds the constraint `sum(a * x) + constant REL 0` and restores feasibility.
+    pub fn add_constraint(
+        &mut self,
+        coeffs: &[(usize, Rational)],
+        constant: &Rational,
+        relation: LinearRelation,
+    ) -> Result<(), ()> {
+        let rhs = -constant;
+        match coeffs {
+            [] => {
+                if relation.holds(&Rational::zero(), &rhs) {
+                    Ok(())
+                } else {
+                    Err(())
+                }
+            }
+            [(col, a)] => {
+                let relation = if a.is_negative() {
+                    relation.flip()
+                } else {
+                    relation
+                };
+                self.assert_relation(*col, relation, &(rhs / a))?;
+                self.check()
+            }
+            _ => {
+                let slack = self.add_row(coeffs);
+                self.assert_relation(slack, relation, &rhs)?;
+                self.check()
+            }
+        }
+    }
+
+    /// Fixes the value of a column and restores feasibility. Returns `true` if the column was
+    /// not already fixed to the value.
+    pub fn assert_value(&mut self, col: usize, value: &Rational) -> Result<bool, ()> {
+        if self.fixed_value(col).as_ref() == Some(value) {
+            return Ok(false);
+        }
+        self.assert_relation(col, LinearRelation::Eq, value)?;
+        self.check()?;
+        Ok(true)
+    }
+
+    fn assert_relation(
+        &mut self,
+        col: usize,
+        relation: LinearRelation,
+        c: &Rational,
+    ) -> Result<(), ()> {
+        let bound = |delta: isize| {
+            DeltaRational::new(c.clone(), Rational::from_integer(BigInt::from(delta)))
+        };
+        match relation {
+            LinearRelation::Eq => {
+                self.assert_lower(col, bound(0))?;
+                self.assert_upper(col, bound(0))
+            }
+            LinearRelation::Lt => self.assert_upper(col, bound(-1)),
+            LinearRelation::Le => self.assert_upper(col, bound(0)),
+            LinearRelation::Gt => self.assert_lower(col, bound(1)),
+            LinearRelation::Ge => self.assert_lower(col, bound(0)),
+        }
+    }
+
+    fn assert_upper(&mut self, col: usize, c: DeltaRational) -> Result<(), ()> {
+        let column = &mut self.columns[col];
+        if matches!(&column.upper, Some(upper) if &c >= upper) {
+            return Ok(());
+        }
+        if matches!(&column.lower, Some(lower) if &c < lower) {
+            return Err(());
+        }
+        column.upper = Some(c.clone());
+        if !self.rows.contains_key(&col) && self.columns[col].value > c {
+            self.update(col, c);
+        }
+        Ok(())
+    }
+
+    fn assert_lower(&mut self, col: usize, c: DeltaRational) -> Result<(), ()> {
+        let column = &mut self.columns[col];
+        if matches!(&column.lower, Some(lower) if &c <= lower) {
+            return Ok(());
+        }
+        if matches!(&column.upper, Some(upper) if &c > upper) {
+            return Err(());
+        }
+        column.lower = Some(c.clone());
+        if !self.rows.contains_key(&col) && self.columns[col].value < c {
+            self.update(col, c);
+        }
+        Ok(())
+    }
+
+    /// Adds a slack variable that is defined by a new row `slack = sum(a * x)`.
+    fn add_row(&mut self, coeffs: &[(usize, Rational)]) -> usize {
+        let mut row: BTreeMap<usize, Rational> = BTreeMap::new();
+        for (col, a) in coeffs {
+            // Basic variables are replaced by their rows.
+            match self.rows.get(col) {
+                Some(basic_row) => {
+                    for (k, b) in basic_row {
+                        *row.entry(*k).or_insert_with(Rational::zero) += a * b;
+                    }
+                }
+                None => *row.entry(*col).or_insert_with(Rational::zero) += a,
+            }
+        }
+        row.retain(|_, a| !a.is_zero());
+
+        let slack = self.new_column();
+        let value = row.iter().fold(DeltaRational::zero(), |acc, (k, a)| {
+            &acc + &self.columns[*k].value.scale(a)
+        });
+        self.columns[slack].value = value;
+        self.rows.insert(slack, row);
+        self.slacks.insert(slack, coeffs.to_vec());
+        slack
+    }
+
+    /// Sets the value of a non-basic variable, and updates the basic variables.
+    fn update(&mut self, col: usize, value: DeltaRational) {
+        let change = &value - &self.columns[col].value;
+        for (basic, row) in self.rows.iter() {
+            if let Some(a) = row.get(&col) {
+                let v = &self.columns[*basic].value + &change.scale(a);
+                self.columns[*basic].value = v;
+            }
+        }
+        self.columns[col].value = value;
+    }
+
+    /// Exchanges the basic variable `basic` with the non-basic variable `col`.
+    fn pivot(&mut self, basic: usize, col: usize) {
+        let mut row = self.rows.remove(&basic).unwrap();
+        let a = row.remove(&col).unwrap();
+
+        // col = (basic - sum(a_k * x_k)) / a
+        let mut col_row = BTreeMap::new();
+        col_row.insert(basic, a.recip());
+        for (k, ak) in row {
+            col_row.insert(k, -ak / &a);
+        }
+
+        for other_row in self.rows.values_mut() {
+            if let Some(c) = other_row.remove(&col) {
+                for (k, b) in col_row.iter() {
+                    let entry = other_row.entry(*k).or_insert_with(Rational::zero);
+                    *entry += &c * b;
+                    if entry.is_zero() {
+                        other_row.remove(k);
+                    }
+                }
+            }
+        }
+        self.rows.insert(col, col_row);
+    }
+
+    /// Sets the value of basic variable `basic` by changing non-basic variable `col`, and
+    /// exchanges them.
+    fn pivot_and_update(&mut self, basic: usize, col: usize, value: DeltaRational) {
+        let a = self.rows[&basic][&col].clone();
+        let theta = (&value - &self.columns[basic].value).scale(&a.recip());
+        self.columns[basic].value = value;
+        let v = &self.columns[col].value + &theta;
+        self.columns[col].value = v;
+        for (other, row) in self.rows.iter() {
+            if *other != basic {
+                if let Some(b) = row.get(&col) {
+                    let v = &self.columns[*other].value + &theta.scale(b);
+                    self.columns[*other].value = v;
+                }
+            }
+        }
+        self.pivot(basic, col);
+    }
+
+    /// Restores the bounds of the basic variables, or fails if the constraints are infeasible.
+    fn check(&mut self) -> Result<(), ()> {
+        loop {
+            let violated = self.rows.keys().copied().find(|b| {
+                let column = &self.columns[*b];
+                column.below_lower() || column.above_upper()
+            });
+            let basic = match violated {
+                Some(basic) => basic,
+                None => return Ok(()),
+            };
+
+            let column = &self.columns[basic];
+            let increase = column.below_lower();
+            let target = if increase {
+                column.lower.clone().unwrap()
+            } else {
+                column.upper.clone().unwrap()
+            };
+            let entering = self.rows[&basic].iter().find(|(k, a)| {
+                let column = &self.columns[**k];
+                if a.is_positive() == increase {
+                    column.can_increase()
+                } else {
+                    column.can_decrease()
+                }
+            });
+            match entering {
+                Some((col, _)) => {
+                    let col = *col;
+                    self.pivot_and_update(basic, col, target)
+                }
+                None => return Err(()),
+            }
+        }
+    }
+
+    /// Minimizes the column, returning `None` if it is unbounded from below.
+    fn minimize_column(&mut self, col: usize, sign: &Rational) -> Option<DeltaRational> {
+        loop {
+            // Objective: sign * col, expressed in the non-basic variables
+            let objective = match self.rows.get(&col) {
+                Some(row) => row.iter().map(|(k, a)| (*k, a * sign)).collect(),
+                None => vec![(col, sign.clone())],
+            };
+
+            let entering = objective.into_iter().find(|(k, c)| {
+                (c.is_positive() && self.columns[*k].can_decrease())
+                    || (c.is_negative() && self.columns[*k].can_increase())
+            });
+            let (entering, c) = match entering {
+                Some(entering) => entering,
+                None => return Some(self.columns[col].value.scale(sign)),
+            };
+            let increase = c.is_negative();
+
+            // Ratio test: the largest step the entering variable can take within the bounds.
+            let column = &self.columns[entering];
+            let mut best = if increase {
+                column
+                    .upper
+                    .as_ref()
+                    .map(|upper| (upper - &column.value, None))
+            } else {
+                column
+                    .lower
+                    .as_ref()
+                    .map(|lower| (&column.value - lower, None))
+            };
+            for (basic, row) in self.rows.iter() {
+                let a = match row.get(&entering) {
+                    Some(a) => a,
+                    None => continue,
+                };
+                let rate = if increase { a.clone() } else { -a };
+                let column = &self.columns[*basic];
+                let limit = if rate.is_positive() {
+                    column
+                        .upper
+                        .as_ref()
+                        .map(|upper| ((upper - &column.value).scale(&rate.recip()), upper))
+                } else {
+                    column
+                        .lower
+                        .as_ref()
+                        .map(|lower| ((&column.value - lower).scale(&(-rate).recip()), lower))
+                };
+                if let Some((step, bound)) = limit {
+                    if !matches!(&best, Some((best_step, _)) if best_step <= &step) {
+                        best = Some((step, Some((*basic, bound.clone()))));
+                    }
+                }
+            }
+
+            match best {
+                None => return None,
+                Some((step, None)) => {
+                    let value = if increase {
+                        &self.columns[entering].value + &step
+                    } else {
+                        &self.columns[entering].value - &step
+                    };
+                    self.update(entering, value);
+                }
+                Some((_, Some((basic, bound)))) => self.pivot_and_update(basic, entering, bound),
+            }
+        }
+    }
+
+    /// Smallest value of the column, or `None` if it is unbounded.
+    pub fn minimize(&self, col: usize) -> Option<DeltaRational> {
+        self.clone().minimize_column(col, &Rational::one())
+    }
+
+    /// Largest value of the column, or `None` if it is unbounded.
+    pub fn maximize(&self, col: usize) -> Option<DeltaRational> {
+        self.clone()
+            .minimize_column(col, &-Rational::one())
+            .map(|v| v.scale(&-Rational::one()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinearRelation, Rational, Tableau};
+    use crate::prelude::*;
+    use num_bigint::BigInt;
+
+    fn q(n: isize) -> Rational {
+        Rational::from_integer(BigInt::from(n))
+    }
+
+    #[test]
+    fn test_simplex_1() {
+        let mut t: Tableau<DefaultUser, DefaultEngine<DefaultUser>> = Tableau::new();
+        let x = t.column(&LTerm::var("x"));
+        let y = t.column(&LTerm::var("y"));
+        // x + y <= 4, x - y >= 2, y >= 0
+        t.add_constraint(&[(x, q(1)), (y, q(1))], &q(-4), LinearRelation::Le)
+            .unwrap();
+        t.add_constraint(&[(x, q(1)), (y, q(-1))], &q(-2), LinearRelation::Ge)
+            .unwrap();
+        t.add_constraint(&[(y, q(1))], &q(0), LinearRelation::Ge)
+            .unwrap();
+        assert_eq!(t.maximize(x).unwrap().real, q(4));
+        assert_eq!(t.minimize(x).unwrap().real, q(2));
+        assert_eq!(t.maximize(y).unwrap().real, q(1));
+        assert!(t.minimize(y).is_some());
+
+        // x > 4 is infeasible
+        assert!(t
+            .clone()
+            .add_constraint(&[(x, q(1))], &q(-4), LinearRelation::Gt)
+            .is_err());
+    }
+
+    #[test]
+    fn test_simplex_2() {
+        let mut t: Tableau<DefaultUser, DefaultEngine<DefaultUser>> = Tableau::new();
+        let x = t.column(&LTerm::var("x"));
+        // x < 1 has supremum 1 that is not attained, and no infimum.
+        t.add_constraint(&[(x, q(1))], &q(-1), LinearRelation::Lt)
+            .unwrap();
+        let sup = t.maximize(x).unwrap();
+        assert_eq!(sup.real, q(1));
+        assert!(sup.delta < q(0));
+        assert!(t.minimize(x).is_none());
+    }
+
+    #[test]
+    fn test_simplex_connected() {
+        let mut t: Tableau<DefaultUser, DefaultEngine<DefaultUser>> = Tableau::new();
+        let x = t.column(&LTerm::var("x"));
+        let y = t.column(&LTerm::var("y"));
+        let z = t.column(&LTerm::var("z"));
+        let w = t.column(&LTerm::var("w"));
+        // x + y <= 4, y - z >= 2, w >= 0
+        t.add_constraint(&[(x, q(1)), (y, q(1))], &q(-4), LinearRelation::Le)
+            .unwrap();
+        t.add_constraint(&[(y, q(1)), (z, q(-1))], &q(-2), LinearRelation::Ge)
+            .unwrap();
+        t.add_constraint(&[(w, q(1))], &q(0), LinearRelation::Ge)
+            .unwrap();
+        let connected = t.connected(&[x]);
+        assert!(connected.contains(&x) && connected.contains(&y) && connected.contains(&z));
+        assert!(!connected.contains(&w));
+        assert_eq!(t.connected(&[w]).len(), 1);
+    }
+}
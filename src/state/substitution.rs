@@ -1,3 +1,4 @@
+use super::nominal;
 use crate::compound::CompoundObject;
 use crate::lterm::{LTerm, LTermInner};
 use crate::user::User;
@@ -87,6 +88,15 @@ where
         match v.as_ref() {
             LTermInner::Cons(head, tail) => LTerm::cons(self.walk_star(head), self.walk_star(tail)),
             LTermInner::Compound(compound) => compound.walk_star(self),
+            LTermInner::Tie(a, body) => LTerm::tie(a.clone(), self.walk_star(body)),
+            LTermInner::Susp(perm, x) => {
+                let xwalk = self.walk(x);
+                if xwalk.is_var() {
+                    LTerm::susp(perm.clone(), xwalk.clone())
+                } else {
+                    self.walk_star(&nominal::permute(perm, xwalk))
+                }
+            }
             _ => v.clone(),
         }
     }
@@ -113,6 +123,8 @@ where
                 self.occurs_check(x, head) || self.occurs_check(x, tail)
             }
             LTermInner::Compound(compound) => self.occurs_check_compound(x, compound.as_ref()),
+            LTermInner::Tie(_, body) => self.occurs_check(x, body),
+            LTermInner::Susp(_, y) => self.occurs_check(x, y),
             _ => false,
         }
     }
@@ -149,6 +161,8 @@ where
             }
             LTermInner::Cons(head, tail) => self.reify(head).reify(tail),
             LTermInner::Compound(compound) => self.reify_compound(compound.as_ref()),
+            LTermInner::Tie(_, body) => self.reify(body),
+            LTermInner::Susp(_, y) => self.reify(y),
             _ => self.clone(),
         }
     }
@@ -169,6 +183,8 @@ where
             }
             LTermInner::Cons(u, v) => self.is_anyvar(u) || self.is_anyvar(v),
             LTermInner::Compound(compound) => self.is_anyvar_compound(compound.as_ref()),
+            LTermInner::Tie(_, body) => self.is_anyvar(body),
+            LTermInner::Susp(_, y) => self.is_anyvar(y),
             _ => false,
         }
     }
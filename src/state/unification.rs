@@ -1,8 +1,10 @@
+use super::nominal::{disagreement_set, inverse, permute, walk_susp, Swap};
 use super::substitution::SMap;
 use crate::compound::CompoundObject;
+use crate::engine::Engine;
 use crate::lterm::{LTerm, LTermInner};
+use crate::relation::nominal::FreshnessConstraint;
 use crate::state::{SResult, State};
-use crate::engine::Engine;
 use crate::user::User;
 
 /// Recursive unification of tree terms
@@ -16,14 +18,25 @@ where
     U: User,
     E: Engine<U>,
 {
-    let uwalk = state.smap_ref().walk(u).clone();
-    let vwalk = state.smap_ref().walk(v).clone();
+    let uwalk = walk_susp(state.smap_ref(), u);
+    let vwalk = walk_susp(state.smap_ref(), v);
     match (uwalk.as_ref(), vwalk.as_ref()) {
         (LTermInner::Var(uvar, _), LTermInner::Var(vvar, _)) if uvar == vvar => {
             // If both terms are variables that walk to the same variable id, then the current
             // state can already unify the variables. Return the input state unchanged.
             Ok(state)
         }
+        (LTermInner::Susp(uperm, x), LTermInner::Susp(vperm, y)) if x == y => {
+            // Permutations of the same variable are equal if the variable is fresh for all
+            // atoms on which the permutations disagree.
+            unify_permutations(state, uperm, vperm, x)
+        }
+        (LTermInner::Susp(perm, x), LTermInner::Var(_, _)) if x == &vwalk => {
+            unify_permutations(state, perm, &[], x)
+        }
+        (LTermInner::Var(_, _), LTermInner::Susp(perm, y)) if &uwalk == y => {
+            unify_permutations(state, &[], perm, y)
+        }
         (LTermInner::Var(_, _), _) => {
             // The term u is a variable and the term v is something else. The variable u and
             // the term v can be unified by extending the substitution map.
@@ -46,6 +59,14 @@ where
                 Ok(state)
             }
         }
+        (LTermInner::Susp(perm, x), _) => {
+            // The permuted variable `perm * x` unifies with `v` by substituting the variable
+            // with the inversely permuted `v`.
+            unify_rec(state, extension, x, &permute(&inverse(perm), &vwalk))
+        }
+        (_, LTermInner::Susp(perm, y)) => {
+            unify_rec(state, extension, &permute(&inverse(perm), &uwalk), y)
+        }
         (LTermInner::Val(uval), LTermInner::Val(vval)) if uval == vval => {
             // If both terms walk to identical values, then they are already unified.
             Ok(state)
@@ -63,10 +84,39 @@ where
         (LTermInner::Compound(ucf), LTermInner::Compound(vcf)) => {
             unify_rec_compound(state, extension, ucf.as_ref(), vcf.as_ref())
         }
+        (LTermInner::Nom(a, _), LTermInner::Nom(b, _)) if a == b => Ok(state),
+        (LTermInner::Tie(a, ubody), LTermInner::Tie(b, vbody)) => {
+            if a == b {
+                unify_rec(state, extension, ubody, vbody)
+            } else {
+                // Binders of different atoms unify if the atom `a` is fresh in the body of the
+                // other binder, and the bodies unify after renaming `b` to `a`.
+                let state = FreshnessConstraint::new(a.clone(), vbody.clone()).run(state)?;
+                let vbody = permute(&[(a.clone(), b.clone())], vbody);
+                unify_rec(state, extension, ubody, &vbody)
+            }
+        }
         _ => Err(()),
     }
 }
 
+/// Unification of two permutations of the same variable `x`
+fn unify_permutations<U, E>(
+    mut state: State<U, E>,
+    uperm: &[Swap<U, E>],
+    vperm: &[Swap<U, E>],
+    x: &LTerm<U, E>,
+) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    for a in disagreement_set(uperm, vperm) {
+        state = FreshnessConstraint::new(a, x.clone()).run(state)?;
+    }
+    Ok(state)
+}
+
 /// Recursive unification of compound terms
 fn unify_rec_compound<U, E>(
     mut state: State<U, E>,